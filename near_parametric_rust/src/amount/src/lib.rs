@@ -0,0 +1,151 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::json_types::U128;
+use near_sdk::serde::{Deserialize, Serialize};
+use std::fmt;
+
+///# description
+///
+/// a fixed-point monetary value. amounts are kept as an integer count of the currency's minor units
+/// (e.g. cents for `USD`) so that balances never accumulate floating point rounding error. shared by the
+/// `QuoteManager`, `PolicyManager` and `ClaimsManager`.
+///
+///# fields
+/// * `minor_units` {`U128`} the value in minor units. serialized as a string in JSON
+/// * `currency` {`String`} the currency code (e.g. `USD`)
+///
+///# notes
+/// arithmetic between two `Amount`s is only defined for the same `currency`
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Amount {
+	minor_units: U128,
+	currency: String,
+}
+
+///# description
+///
+/// the reasons an `Amount` operation can be refused
+#[derive(Debug, PartialEq, Eq)]
+pub enum AmountError {
+	CurrencyMismatch,
+	Overflow,
+	NegativeBalance,
+	InvalidPercent,
+}
+
+impl fmt::Display for AmountError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match *self {
+			AmountError::CurrencyMismatch => write!(f, "CURRENCY_MISMATCH"),
+			AmountError::Overflow => write!(f, "AMOUNT_OVERFLOW"),
+			AmountError::NegativeBalance => write!(f, "NEGATIVE_BALANCE"),
+			AmountError::InvalidPercent => write!(f, "INVALID_PERCENT"),
+		}
+	}
+}
+
+impl Amount {
+	pub fn new(minor_units: u128, currency: String) -> Self {
+		Self {
+			minor_units: U128(minor_units),
+			currency,
+		}
+	}
+
+	pub fn zero(currency: String) -> Self {
+		Self::new(0, currency)
+	}
+
+	pub fn minor_units(&self) -> u128 {
+		self.minor_units.0
+	}
+
+	pub fn currency(&self) -> &String {
+		&self.currency
+	}
+
+	pub fn is_zero(&self) -> bool {
+		self.minor_units.0 == 0
+	}
+
+	/// `self + other`
+	pub fn checked_add(&self, other: &Amount) -> Result<Amount, AmountError> {
+		self.assert_same_currency(other)?;
+		let sum = self
+				.minor_units
+				.0
+				.checked_add(other.minor_units.0)
+				.ok_or(AmountError::Overflow)?;
+		Ok(Amount::new(sum, self.currency.clone()))
+	}
+
+	/// `self - other`. refuses to go below zero
+	pub fn checked_sub(&self, other: &Amount) -> Result<Amount, AmountError> {
+		self.assert_same_currency(other)?;
+		let difference = self
+				.minor_units
+				.0
+				.checked_sub(other.minor_units.0)
+				.ok_or(AmountError::NegativeBalance)?;
+		Ok(Amount::new(difference, self.currency.clone()))
+	}
+
+	/// `percent`% of `self`, rounded down to the nearest minor unit
+	pub fn checked_percent(&self, percent: u8) -> Result<Amount, AmountError> {
+		if percent > 100 {
+			return Err(AmountError::InvalidPercent);
+		}
+		let scaled = self
+				.minor_units
+				.0
+				.checked_mul(percent as u128)
+				.ok_or(AmountError::Overflow)?;
+		Ok(Amount::new(scaled / 100, self.currency.clone()))
+	}
+
+	fn assert_same_currency(&self, other: &Amount) -> Result<(), AmountError> {
+		if self.currency != other.currency {
+			return Err(AmountError::CurrencyMismatch);
+		}
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn usd(minor_units: u128) -> Amount {
+		Amount::new(minor_units, "USD".to_string())
+	}
+
+	#[test]
+	fn add_and_sub() {
+		assert_eq!(usd(150).checked_add(&usd(25)), Ok(usd(175)));
+		assert_eq!(usd(150).checked_sub(&usd(150)), Ok(usd(0)));
+	}
+
+	#[test]
+	fn sub_below_zero_is_refused() {
+		assert_eq!(usd(10).checked_sub(&usd(11)), Err(AmountError::NegativeBalance));
+	}
+
+	#[test]
+	fn add_overflow_is_refused() {
+		assert_eq!(usd(u128::MAX).checked_add(&usd(1)), Err(AmountError::Overflow));
+	}
+
+	#[test]
+	fn currencies_do_not_mix() {
+		let eur = Amount::new(10, "EUR".to_string());
+		assert_eq!(usd(10).checked_add(&eur), Err(AmountError::CurrencyMismatch));
+		assert_eq!(usd(10).checked_sub(&eur), Err(AmountError::CurrencyMismatch));
+	}
+
+	#[test]
+	fn percent_rounds_down() {
+		assert_eq!(usd(999).checked_percent(30), Ok(usd(299)));
+		assert_eq!(usd(999).checked_percent(100), Ok(usd(999)));
+		assert_eq!(usd(999).checked_percent(101), Err(AmountError::InvalidPercent));
+	}
+}
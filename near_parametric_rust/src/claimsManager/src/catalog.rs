@@ -0,0 +1,201 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::AccountId;
+use std::collections::HashMap;
+
+///# description
+///
+/// one axis of a `PayoutTable`: a trigger split into bands.
+///
+///# fields
+/// * `trigger` the `OracleMetadata.triggering_values` key (e.g. `category`, `distance`)
+/// * `thresholds` strictly ascending lower bounds of the bands. band `i` is `[thresholds[i], thresholds[i + 1])`
+///   and the last band is open ended. values below `thresholds[0]` are outside the table
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct TriggerDimension {
+	pub trigger: String,
+	pub thresholds: Vec<u32>,
+}
+
+impl TriggerDimension {
+	/// the band that `value` falls into
+	fn band(&self, value: u32) -> Option<usize> {
+		let bands_at_or_below = self.thresholds.iter().take_while(|threshold| **threshold <= value).count();
+		bands_at_or_below.checked_sub(1)
+	}
+}
+
+///# description
+///
+/// the payout percent of a product as a step function (one dimension) or matrix (several dimensions)
+/// over trigger bands. e.g. hurricane category × distance band.
+///
+///# fields
+/// * `dimensions` the axes of the table
+/// * `percents` the payout percent of every cell, row-major with the last dimension varying fastest
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct PayoutTable {
+	pub dimensions: Vec<TriggerDimension>,
+	pub percents: Vec<u8>,
+}
+
+impl PayoutTable {
+	/// the payout percent for `triggering_values`. a missing trigger or a value below the first band
+	/// never pays out
+	pub fn payout_percent(&self, triggering_values: &HashMap<String, u32>) -> u8 {
+		let mut index: usize = 0;
+		for dimension in self.dimensions.iter() {
+			let band = match triggering_values
+					.get(&dimension.trigger)
+					.and_then(|value| dimension.band(*value))
+			{
+				Some(band) => band,
+				None => return 0,
+			};
+			index = index * dimension.thresholds.len() + band;
+		}
+		self.percents.get(index).copied().unwrap_or(0)
+	}
+
+	/// why the table cannot be registered, if anything
+	pub fn validation_error(&self) -> Option<&'static str> {
+		if self.dimensions.is_empty() {
+			return Some("EMPTY_PAYOUT_TABLE");
+		}
+		let mut cells: usize = 1;
+		for dimension in self.dimensions.iter() {
+			if dimension.thresholds.is_empty() {
+				return Some("EMPTY_TRIGGER_DIMENSION");
+			}
+			if dimension.thresholds.windows(2).any(|pair| pair[0] >= pair[1]) {
+				return Some("THRESHOLDS_NOT_ASCENDING");
+			}
+			if self.dimensions.iter().filter(|other| other.trigger == dimension.trigger).count() > 1 {
+				return Some("DUPLICATE_TRIGGER_DIMENSION");
+			}
+			cells = match cells.checked_mul(dimension.thresholds.len()) {
+				Some(cells) => cells,
+				None => return Some("PAYOUT_TABLE_TOO_LARGE"),
+			};
+		}
+		if self.percents.len() != cells {
+			return Some("PERCENTS_DO_NOT_MATCH_DIMENSIONS");
+		}
+		if self.percents.iter().any(|percent| *percent > 100) {
+			return Some("INVALID_PERCENT");
+		}
+		None
+	}
+}
+
+///# description
+///
+/// an entry of the product catalog: every `PayoutTable` an issuer has registered for a `policy_type`.
+/// tables are never edited, a change is a new version, so a `Policy` keeps the table it was sold under.
+///
+///# fields
+/// * `issuer` the account that registered the `policy_type` and may add versions
+/// * `versions` the tables, version `n` at index `n - 1`
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Debug, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Product {
+	pub issuer: AccountId,
+	pub versions: Vec<PayoutTable>,
+}
+
+impl Product {
+	pub fn latest_version(&self) -> u32 {
+		self.versions.len() as u32
+	}
+
+	pub fn table(&self, version: u32) -> Option<&PayoutTable> {
+		(version as usize).checked_sub(1).and_then(|index| self.versions.get(index))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// category 3, 4, 5+ × distance [0, 25), [25, 50), [50, 100), 100+
+	fn category_distance_table() -> PayoutTable {
+		PayoutTable {
+			dimensions: vec![
+				TriggerDimension {
+					trigger: "category".to_string(),
+					thresholds: vec![3, 4, 5],
+				},
+				TriggerDimension {
+					trigger: "distance".to_string(),
+					thresholds: vec![0, 25, 50, 100],
+				},
+			],
+			percents: vec![
+				30, 20, 10, 0, //
+				50, 30, 20, 0, //
+				100, 50, 30, 0,
+			],
+		}
+	}
+
+	fn triggers(values: &[(&str, u32)]) -> HashMap<String, u32> {
+		values.iter().map(|(key, value)| (key.to_string(), *value)).collect()
+	}
+
+	#[test]
+	fn matrix_lookup() {
+		let table = category_distance_table();
+		assert_eq!(table.validation_error(), None);
+		assert_eq!(table.payout_percent(&triggers(&[("category", 3), ("distance", 10)])), 30);
+		assert_eq!(table.payout_percent(&triggers(&[("category", 4), ("distance", 25)])), 30);
+		assert_eq!(table.payout_percent(&triggers(&[("category", 5), ("distance", 99)])), 30);
+		assert_eq!(table.payout_percent(&triggers(&[("category", 5), ("distance", 500)])), 0);
+	}
+
+	#[test]
+	fn below_first_band_or_missing_trigger_never_pays() {
+		let table = category_distance_table();
+		assert_eq!(table.payout_percent(&triggers(&[("category", 2), ("distance", 10)])), 0);
+		assert_eq!(table.payout_percent(&triggers(&[("category", 5)])), 0);
+	}
+
+	#[test]
+	fn step_function() {
+		let table = PayoutTable {
+			dimensions: vec![TriggerDimension {
+				trigger: "rainfall".to_string(),
+				thresholds: vec![200, 300, 400],
+			}],
+			percents: vec![25, 50, 100],
+		};
+		assert_eq!(table.validation_error(), None);
+		assert_eq!(table.payout_percent(&triggers(&[("rainfall", 350)])), 50);
+	}
+
+	#[test]
+	fn invalid_tables() {
+		let mut table = category_distance_table();
+		table.percents.pop();
+		assert_eq!(table.validation_error(), Some("PERCENTS_DO_NOT_MATCH_DIMENSIONS"));
+		let mut table = category_distance_table();
+		table.dimensions[1].thresholds = vec![0, 50, 25, 100];
+		assert_eq!(table.validation_error(), Some("THRESHOLDS_NOT_ASCENDING"));
+		let mut table = category_distance_table();
+		table.percents[0] = 101;
+		assert_eq!(table.validation_error(), Some("INVALID_PERCENT"));
+	}
+
+	#[test]
+	fn versions_are_one_based() {
+		let product = Product {
+			issuer: "issuer.near".parse().unwrap(),
+			versions: vec![category_distance_table()],
+		};
+		assert_eq!(product.latest_version(), 1);
+		assert!(product.table(0).is_none());
+		assert!(product.table(1).is_some());
+		assert!(product.table(2).is_none());
+	}
+}
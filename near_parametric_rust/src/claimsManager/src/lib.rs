@@ -0,0 +1,431 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::collections::UnorderedMap;
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::{env, near_bindgen, AccountId, BorshStorageKey, PanicOnDefault};
+use std::collections::HashMap;
+
+mod catalog;
+
+pub use amount::{Amount, AmountError};
+pub use crate::catalog::{PayoutTable, Product, TriggerDimension};
+
+#[derive(BorshStorageKey, BorshSerialize)]
+pub enum StorageKeys {
+	Products,
+}
+
+/// the identity of a loss. mirrors `LossIdentity` in `PolicyManager`
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Debug, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct LossIdentity {
+	id: String,
+	event_id: String,
+	policy_id: String,
+	client_id: String,
+	issuer_id: String,
+}
+
+/// mirrors `OracleMetadata` in `PolicyManager`
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Debug, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct OracleMetadata {
+	triggering_values: HashMap<String, u32>,
+	claims_manager: AccountId,
+	oracle: AccountId,
+}
+
+///# description
+///
+/// everything needed to decide if an `Event` causes a loss on a `Policy`. mirrors `LossContext` in `PolicyManager`
+///
+///# fields
+/// * `identity` the loss being considered. `id` is assigned by the `ClaimsManager`
+/// * `oracle_data` the triggering values reported by the oracle
+/// * `policy_type` selects the `Product`
+/// * `product_version` the `PayoutTable` version the `Policy` was sold under
+/// * `balance_snapshot` the `Policy` balance when the `Event` was first reported. payouts are a percent of it
+/// * `current_percent` the payout percent already computed for this `Event`
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Debug, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct LossContext {
+	identity: LossIdentity,
+	oracle_data: OracleMetadata,
+	policy_type: u8,
+	product_version: u32,
+	balance_snapshot: Amount,
+	current_percent: u8,
+}
+
+///# description
+///
+/// mirrors `LossCalculation` in `PolicyManager`
+///
+///# fields
+/// * `payout_percent` the total payout percent of the `Event` after this loss
+/// * `amount_due` the amount owed for the increase from the previous percent to `payout_percent`
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Debug, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct LossCalculation {
+	payout_percent: u8,
+	amount_due: Amount,
+}
+
+/// mirrors `ComputedLoss` in `PolicyManager`
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Debug, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ComputedLoss {
+	identity: LossIdentity,
+	oracle_data: OracleMetadata,
+	calculations: LossCalculation,
+}
+
+///# description
+///
+/// computes the losses of parametric policies for `PolicyManager`s. given the triggering values of an
+/// `Event` it looks up the payout percent in the product catalog, using the `PayoutTable` version
+/// that the `Policy` was sold under.
+///
+///# notes
+/// payouts do not stack within an `Event`: only the increase over `current_percent` is owed, and it is
+/// computed against `balance_snapshot` rather than the current balance
+#[near_bindgen]
+#[derive(BorshDeserialize, BorshSerialize, PanicOnDefault)]
+pub struct ClaimsManager {
+	/// the owner of this contract
+	owner: AccountId,
+	/// `PolicyManager` accounts permitted to compute losses
+	policy_managers: Vec<AccountId>,
+	/// accounts permitted to register products
+	issuers: Vec<AccountId>,
+	/// the product catalog indexed by `policy_type`
+	products: UnorderedMap<u8, Product>,
+}
+
+#[near_bindgen]
+impl ClaimsManager {
+	#[init]
+	pub fn new() -> Self {
+		assert!(!env::state_exists(), "Already initialized");
+		Self {
+			owner: env::predecessor_account_id(),
+			policy_managers: Vec::new(),
+			issuers: Vec::new(),
+			products: UnorderedMap::new(StorageKeys::Products),
+		}
+	}
+
+	///# description
+	/// compute the losses due for `loss_contexts`. contexts that do not raise the payout percent of
+	/// their `Event` produce no loss.
+	///
+	///# parameters
+	/// * `loss_contexts` {`Vec<LossContext>`}
+	///
+	///# returns
+	/// `Vec<ComputedLoss>` each with a loss id derived from the policy, event and payout percent
+	pub fn compute_loss(&self, loss_contexts: Vec<LossContext>) -> Vec<ComputedLoss> {
+		assert!(
+			self.policy_managers.contains(&env::predecessor_account_id()),
+			"POLICY_MANAGER_RESTRICTED"
+		);
+		// the payout percent reached so far per (policy_id, event_id) in this batch
+		let mut event_percents: HashMap<(String, String), u8> = HashMap::new();
+		let mut computed_losses: Vec<ComputedLoss> = Vec::new();
+		for loss_context in loss_contexts.into_iter() {
+			let payout_percent = self.payout_percent(
+				loss_context.policy_type,
+				loss_context.product_version,
+				&loss_context.oracle_data.triggering_values,
+			);
+			let event_key = (
+				loss_context.identity.policy_id.clone(),
+				loss_context.identity.event_id.clone(),
+			);
+			let current_percent = *event_percents
+					.get(&event_key)
+					.unwrap_or(&loss_context.current_percent);
+			if payout_percent <= current_percent {
+				continue;
+			}
+			let amount_due = loss_context
+					.balance_snapshot
+					.checked_percent(payout_percent - current_percent)
+					.unwrap_or_else(|error| env::panic(error.to_string().as_bytes()));
+			event_percents.insert(event_key, payout_percent);
+			let mut identity = loss_context.identity;
+			identity.id = loss_id(&identity, payout_percent);
+			computed_losses.push(ComputedLoss {
+				identity,
+				oracle_data: loss_context.oracle_data,
+				calculations: LossCalculation {
+					payout_percent,
+					amount_due,
+				},
+			});
+		}
+		computed_losses
+	}
+
+	///# description
+	/// register a `PayoutTable` for a `policy_type`. the first registration makes the caller the issuer
+	/// of the `policy_type`; later registrations by that issuer add a new version.
+	///
+	///# returns
+	/// the version of the registered table
+	pub fn register_payout_table(&mut self, policy_type: u8, payout_table: PayoutTable) -> u32 {
+		let issuer = env::predecessor_account_id();
+		assert!(self.issuers.contains(&issuer), "ISSUER_RESTRICTED");
+		if let Some(error) = payout_table.validation_error() {
+			env::panic(error.as_bytes());
+		}
+		let mut product = self.products.get(&policy_type).unwrap_or(Product {
+			issuer: issuer.clone(),
+			versions: Vec::new(),
+		});
+		assert_eq!(product.issuer, issuer, "POLICY_TYPE_OWNED_BY_ANOTHER_ISSUER");
+		product.versions.push(payout_table);
+		self.products.insert(&policy_type, &product);
+		product.latest_version()
+	}
+
+	/// get a catalog entry with every version of its `PayoutTable`
+	pub fn get_product(&self, policy_type: u8) -> Option<Product> {
+		self.products.get(&policy_type)
+	}
+
+	/// get one version of the `PayoutTable` of a `policy_type`
+	pub fn get_payout_table(&self, policy_type: u8, version: u32) -> Option<PayoutTable> {
+		self.products
+				.get(&policy_type)
+				.and_then(|product| product.table(version).cloned())
+	}
+
+	///# description
+	/// preview the payout percent of a product for the given trigger values
+	///
+	///# parameters
+	/// * `policy_type` the product
+	/// * `version` the `PayoutTable` version. defaults to the latest
+	/// * `triggering_values` e.g. `{"category": 4, "distance": 30}`
+	pub fn preview_payout_percent(
+		&self,
+		policy_type: u8,
+		version: Option<u32>,
+		triggering_values: HashMap<String, u32>,
+	) -> u8 {
+		let product_option = self.products.get(&policy_type);
+		assert!(product_option.is_some(), "PRODUCT_NOT_FOUND");
+		let version = version.unwrap_or_else(|| product_option.unwrap().latest_version());
+		self.payout_percent(policy_type, version, &triggering_values)
+	}
+
+	pub fn add_issuer(&mut self, issuer: AccountId) {
+		assert_eq!(env::predecessor_account_id(), self.owner, "OWNER_RESTRICTED");
+		if !self.issuers.contains(&issuer) {
+			self.issuers.push(issuer);
+		}
+	}
+
+	pub fn remove_issuer(&mut self, issuer: AccountId) {
+		assert_eq!(env::predecessor_account_id(), self.owner, "OWNER_RESTRICTED");
+		let index = self.issuers.iter().position(|account| *account == issuer);
+		assert!(index.is_some(), "ISSUER_NOT_FOUND");
+		self.issuers.swap_remove(index.unwrap());
+	}
+
+	pub fn add_policy_manager(&mut self, policy_manager: AccountId) {
+		assert_eq!(env::predecessor_account_id(), self.owner, "OWNER_RESTRICTED");
+		if !self.policy_managers.contains(&policy_manager) {
+			self.policy_managers.push(policy_manager);
+		}
+	}
+
+	pub fn remove_policy_manager(&mut self, policy_manager: AccountId) {
+		assert_eq!(env::predecessor_account_id(), self.owner, "OWNER_RESTRICTED");
+		let index = self
+				.policy_managers
+				.iter()
+				.position(|manager| *manager == policy_manager);
+		assert!(index.is_some(), "POLICY_MANAGER_NOT_FOUND");
+		self.policy_managers.swap_remove(index.unwrap());
+	}
+
+	pub fn change_owner(&mut self, new_owner: AccountId) {
+		assert_eq!(env::predecessor_account_id(), self.owner, "OWNER_RESTRICTED");
+		self.owner = new_owner;
+	}
+}
+
+impl ClaimsManager {
+	fn payout_percent(&self, policy_type: u8, version: u32, triggering_values: &HashMap<String, u32>) -> u8 {
+		let product_option = self.products.get(&policy_type);
+		assert!(product_option.is_some(), "PRODUCT_NOT_FOUND");
+		let product = product_option.unwrap();
+		let payout_table_option = product.table(version);
+		assert!(payout_table_option.is_some(), "PAYOUT_TABLE_VERSION_NOT_FOUND");
+		payout_table_option.unwrap().payout_percent(triggering_values)
+	}
+}
+
+/// a deterministic loss id: the hex encoded sha256 of the policy id, event id and payout percent
+fn loss_id(identity: &LossIdentity, payout_percent: u8) -> String {
+	let preimage = (
+		identity.policy_id.clone(),
+		identity.event_id.clone(),
+		payout_percent,
+	)
+			.try_to_vec()
+			.unwrap_or_else(|_| env::panic(b"ERR_LOSS_ID_SERIALIZATION"));
+	env::sha256(&preimage)
+			.iter()
+			.map(|byte| format!("{:02x}", byte))
+			.collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use near_sdk::MockedBlockchain;
+	use near_sdk::{testing_env, VMContext};
+
+	const POLICY_MANAGER: &str = "policy_manager.near";
+	const ISSUER: &str = "issuer.near";
+
+	/// a claims manager with a category step function: 3 → 30%, 4 → 50%, 5 → 100%
+	fn claims_manager() -> ClaimsManager {
+		testing_env!(get_context("owner.near".to_string()));
+		let mut claims_manager = ClaimsManager::new();
+		claims_manager.add_issuer(ISSUER.to_string());
+		claims_manager.add_policy_manager(POLICY_MANAGER.to_string());
+		testing_env!(get_context(ISSUER.to_string()));
+		claims_manager.register_payout_table(
+			1,
+			PayoutTable {
+				dimensions: vec![TriggerDimension {
+					trigger: "category".to_string(),
+					thresholds: vec![3, 4, 5],
+				}],
+				percents: vec![30, 50, 100],
+			},
+		);
+		testing_env!(get_context(POLICY_MANAGER.to_string()));
+		claims_manager
+	}
+
+	fn identity(policy_id: &str, event_id: &str) -> LossIdentity {
+		LossIdentity {
+			id: String::new(),
+			event_id: event_id.to_string(),
+			policy_id: policy_id.to_string(),
+			client_id: "client".to_string(),
+			issuer_id: ISSUER.to_string(),
+		}
+	}
+
+	fn loss_context(event_id: &str, category: u32, current_percent: u8) -> LossContext {
+		let mut triggering_values = HashMap::new();
+		triggering_values.insert("category".to_string(), category);
+		LossContext {
+			identity: identity("policy", event_id),
+			oracle_data: OracleMetadata {
+				triggering_values,
+				claims_manager: "claims_manager.near".to_string(),
+				oracle: "oracle.near".to_string(),
+			},
+			policy_type: 1,
+			product_version: 1,
+			balance_snapshot: Amount::new(10_000, "USD".to_string()),
+			current_percent,
+		}
+	}
+
+	fn payouts(computed_losses: &[ComputedLoss]) -> Vec<(u8, u128)> {
+		computed_losses
+				.iter()
+				.map(|loss| (loss.calculations.payout_percent, loss.calculations.amount_due.minor_units()))
+				.collect()
+	}
+
+	#[test]
+	fn only_the_increase_over_the_current_percent_is_due() {
+		let claims_manager = claims_manager();
+		let computed_losses = claims_manager.compute_loss(vec![loss_context("storm", 4, 30)]);
+		assert_eq!(payouts(&computed_losses), vec![(50, 2_000)]);
+	}
+
+	#[test]
+	fn no_loss_unless_the_percent_is_raised() {
+		let claims_manager = claims_manager();
+		let computed_losses = claims_manager.compute_loss(vec![
+			loss_context("storm", 4, 50),
+			loss_context("storm", 3, 50),
+			loss_context("storm", 2, 0),
+		]);
+		assert!(computed_losses.is_empty());
+	}
+
+	#[test]
+	fn escalation_within_a_batch_does_not_stack() {
+		let claims_manager = claims_manager();
+		let computed_losses = claims_manager.compute_loss(vec![
+			loss_context("storm", 3, 0),
+			loss_context("storm", 5, 0),
+			loss_context("storm", 4, 0),
+			loss_context("other_storm", 3, 0),
+		]);
+		// 30% then the 70% increase to 100%; the category 4 report adds nothing
+		assert_eq!(payouts(&computed_losses), vec![(30, 3_000), (100, 7_000), (30, 3_000)]);
+	}
+
+	#[test]
+	#[should_panic(expected = "POLICY_MANAGER_RESTRICTED")]
+	fn compute_loss_is_restricted_to_policy_managers() {
+		let claims_manager = claims_manager();
+		testing_env!(get_context(ISSUER.to_string()));
+		claims_manager.compute_loss(vec![loss_context("storm", 3, 0)]);
+	}
+
+	#[test]
+	fn loss_ids_are_deterministic() {
+		let claims_manager = claims_manager();
+		let first = claims_manager.compute_loss(vec![loss_context("storm", 3, 0)]);
+		let second = claims_manager.compute_loss(vec![loss_context("storm", 3, 0)]);
+		assert_eq!(first[0].identity.id, second[0].identity.id);
+		assert_eq!(first[0].identity.id, loss_id(&identity("policy", "storm"), 30));
+		assert_eq!(first[0].identity.id.len(), 64);
+	}
+
+	#[test]
+	fn loss_ids_differ_by_policy_event_and_percent() {
+		testing_env!(get_context(POLICY_MANAGER.to_string()));
+		let id = loss_id(&identity("policy", "storm"), 30);
+		assert_ne!(id, loss_id(&identity("policy", "storm"), 50));
+		assert_ne!(id, loss_id(&identity("other_policy", "storm"), 30));
+		assert_ne!(id, loss_id(&identity("policy", "other_storm"), 30));
+		// the client and issuer are not part of the id
+		let mut other_client = identity("policy", "storm");
+		other_client.client_id = "other_client".to_string();
+		assert_eq!(id, loss_id(&other_client, 30));
+	}
+
+	fn get_context(predecessor_account_id: String) -> VMContext {
+		VMContext {
+			current_account_id: "claims_manager.near".to_string(),
+			signer_account_id: predecessor_account_id.clone(),
+			signer_account_pk: vec![0, 1, 2],
+			predecessor_account_id,
+			input: vec![],
+			block_index: 0,
+			block_timestamp: 0,
+			account_balance: 0,
+			account_locked_balance: 0,
+			storage_usage: 0,
+			attached_deposit: 0,
+			prepaid_gas: 10u64.pow(18),
+			random_seed: vec![0, 1, 2],
+			is_view: false,
+			output_data_receivers: vec![],
+			epoch_height: 19,
+		}
+	}
+}
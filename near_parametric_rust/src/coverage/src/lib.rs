@@ -0,0 +1,148 @@
+use amount::Amount;
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::serde::{Deserialize, Serialize};
+
+///# description
+///
+/// the terms that reduce an `Event`'s loss to the amount owed. carried from the `Quote` into the `Policy`
+/// and applied whenever a `ComputedLoss` is recorded. shared by the `QuoteManager` and `PolicyManager`
+///
+///# fields
+/// * `deductible` {`Option<Amount>`} retained by the client on every event
+/// * `event_limit` {`Option<Amount>`} the most a single event can pay after the deductible
+/// * `aggregate_limit` {`Option<Amount>`} the most all events of a policy year can pay
+/// * `reinstatements` {`u8`} how many times an exhausted `aggregate_limit` is restored over the life of
+///   the policy. each restores the full limit for the policy year that exhausted it
+///
+///# notes
+/// `max_payout` still caps the total of the policy whatever the terms
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct CoverageTerms {
+	pub deductible: Option<Amount>,
+	pub event_limit: Option<Amount>,
+	pub aggregate_limit: Option<Amount>,
+	pub reinstatements: u8,
+}
+
+impl CoverageTerms {
+	/// why the terms cannot be used with `max_payout`, if they cannot
+	pub fn validation_error(&self, max_payout: &Amount) -> Option<&'static str> {
+		let amounts = [&self.deductible, &self.event_limit, &self.aggregate_limit];
+		if amounts
+				.iter()
+				.filter_map(|amount| amount.as_ref())
+				.any(|amount| amount.currency() != max_payout.currency())
+		{
+			return Some("TERMS_CURRENCY_MISMATCH");
+		}
+		if matches!(&self.event_limit, Some(event_limit) if event_limit.is_zero()) {
+			return Some("INVALID_EVENT_LIMIT");
+		}
+		if matches!(&self.aggregate_limit, Some(aggregate_limit) if aggregate_limit.is_zero()) {
+			return Some("INVALID_AGGREGATE_LIMIT");
+		}
+		if self.reinstatements > 0 && self.aggregate_limit.is_none() {
+			return Some("REINSTATEMENTS_WITHOUT_AGGREGATE_LIMIT");
+		}
+		None
+	}
+
+	/// the part of an event's `gross_loss` retained by the deductible
+	pub fn deductible_on(&self, gross_loss: u128) -> u128 {
+		self.deductible
+				.as_ref()
+				.map_or(0, |deductible| gross_loss.min(deductible.minor_units()))
+	}
+
+	/// the part of an event's `gross_loss` above the event limit once the deductible is taken
+	pub fn event_limit_excess(&self, gross_loss: u128) -> u128 {
+		let after_deductible = gross_loss - self.deductible_on(gross_loss);
+		self.event_limit
+				.as_ref()
+				.map_or(0, |event_limit| after_deductible.saturating_sub(event_limit.minor_units()))
+	}
+
+	/// an event's `gross_loss` after the deductible and the event limit
+	pub fn net_loss(&self, gross_loss: u128) -> u128 {
+		gross_loss - self.deductible_on(gross_loss) - self.event_limit_excess(gross_loss)
+	}
+
+	/// the reinstatements a policy year that owes `year_owed` has used
+	pub fn reinstatements_used(&self, year_owed: u128) -> u32 {
+		match &self.aggregate_limit {
+			Some(aggregate_limit) if year_owed > 0 => {
+				let limits_touched = (year_owed - 1) / aggregate_limit.minor_units() + 1;
+				(limits_touched - 1) as u32
+			}
+			_ => 0,
+		}
+	}
+
+	/// how much more a policy year that owes `year_owed` can pay, given the reinstatements the other
+	/// policy years have used. `None` without an aggregate limit
+	pub fn aggregate_remaining(&self, year_owed: u128, reinstatements_elsewhere: u32) -> Option<u128> {
+		self.aggregate_limit.as_ref().map(|aggregate_limit| {
+			let limits = 1 + (self.reinstatements as u32).saturating_sub(reinstatements_elsewhere) as u128;
+			aggregate_limit
+					.minor_units()
+					.saturating_mul(limits)
+					.saturating_sub(year_owed)
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn usd(minor_units: u128) -> Option<Amount> {
+		Some(Amount::new(minor_units, "USD".to_string()))
+	}
+
+	#[test]
+	fn deductible_then_event_limit() {
+		let terms = CoverageTerms {
+			deductible: usd(1_000),
+			event_limit: usd(5_000),
+			..Default::default()
+		};
+		assert_eq!(terms.net_loss(800), 0);
+		assert_eq!(terms.net_loss(4_000), 3_000);
+		assert_eq!(terms.event_limit_excess(10_000), 4_000);
+		assert_eq!(terms.net_loss(10_000), 5_000);
+		assert_eq!(CoverageTerms::default().net_loss(10_000), 10_000);
+	}
+
+	#[test]
+	fn reinstatements_restore_the_aggregate() {
+		let terms = CoverageTerms {
+			aggregate_limit: usd(1_000),
+			reinstatements: 1,
+			..Default::default()
+		};
+		assert_eq!(terms.reinstatements_used(1_000), 0);
+		assert_eq!(terms.reinstatements_used(1_001), 1);
+		assert_eq!(terms.aggregate_remaining(400, 0), Some(1_600));
+		// another policy year used the only reinstatement
+		assert_eq!(terms.aggregate_remaining(400, 1), Some(600));
+		assert_eq!(terms.aggregate_remaining(1_500, 1), Some(0));
+		assert_eq!(CoverageTerms::default().aggregate_remaining(1_500, 0), None);
+	}
+
+	#[test]
+	fn invalid_terms() {
+		let max_payout = Amount::new(10_000, "USD".to_string());
+		let mut terms = CoverageTerms {
+			reinstatements: 2,
+			..Default::default()
+		};
+		assert_eq!(terms.validation_error(&max_payout), Some("REINSTATEMENTS_WITHOUT_AGGREGATE_LIMIT"));
+		terms.aggregate_limit = usd(0);
+		assert_eq!(terms.validation_error(&max_payout), Some("INVALID_AGGREGATE_LIMIT"));
+		terms.aggregate_limit = Some(Amount::new(5_000, "EUR".to_string()));
+		assert_eq!(terms.validation_error(&max_payout), Some("TERMS_CURRENCY_MISMATCH"));
+		terms.aggregate_limit = usd(5_000);
+		assert_eq!(terms.validation_error(&max_payout), None);
+	}
+}
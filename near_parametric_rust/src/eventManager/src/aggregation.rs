@@ -0,0 +1,180 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+///#Description
+///
+/// how the numeric measurements of several nodes are combined into one value
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub enum AggregationMethod {
+    Median,
+    ///the mean after dropping `trim_percent` of the samples from each end
+    TrimmedMean { trim_percent: u8 },
+}
+
+///#Description
+///
+/// `HurricaneOracle` aggregation settings
+///
+/// #Fields
+///
+/// *`method` the `AggregationMethod`
+/// *`max_deviation_percent` samples that deviate from the median by more than this percent
+/// of the median are discarded as outliers before aggregating. `None` keeps every sample
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct AggregationConfig {
+    pub method: AggregationMethod,
+    pub max_deviation_percent: Option<u32>,
+}
+
+impl Default for AggregationConfig {
+    fn default() -> Self {
+        Self {
+            method: AggregationMethod::Median,
+            max_deviation_percent: None,
+        }
+    }
+}
+
+impl AggregationConfig {
+    pub fn is_valid(&self) -> bool {
+        match self.method {
+            AggregationMethod::Median => true,
+            AggregationMethod::TrimmedMean { trim_percent } => trim_percent < 50,
+        }
+    }
+
+    ///#Description
+    ///
+    /// aggregate the samples of one trigger. `None` when no sample survives outlier rejection
+    pub fn aggregate(&self, samples: &[u32]) -> Option<u32> {
+        self.aggregate_inliers(&self.inliers(samples))
+    }
+
+    ///#Description
+    ///
+    /// aggregate every trigger of several node measurements. triggers with fewer than
+    /// `min_samples` samples left after outlier rejection are left out
+    pub fn aggregate_measurements(
+        &self,
+        measurements: &[&HashMap<String, u32>],
+        min_samples: u64,
+    ) -> HashMap<String, u32> {
+        let mut samples: BTreeMap<&String, Vec<u32>> = BTreeMap::new();
+        for node_measurements in measurements.iter() {
+            for (trigger, value) in node_measurements.iter() {
+                samples.entry(trigger).or_default().push(*value);
+            }
+        }
+        samples
+            .into_iter()
+            .filter_map(|(trigger, values)| {
+                let inliers = self.inliers(&values);
+                if (inliers.len() as u64) < min_samples {
+                    return None;
+                }
+                self.aggregate_inliers(&inliers)
+                    .map(|value| (trigger.clone(), value))
+            })
+            .collect()
+    }
+
+    ///the samples that survive outlier rejection, sorted
+    fn inliers(&self, samples: &[u32]) -> Vec<u32> {
+        let mut sorted: Vec<u32> = samples.to_vec();
+        sorted.sort_unstable();
+        if let (Some(median), Some(max_deviation_percent)) =
+            (median(&sorted), self.max_deviation_percent)
+        {
+            sorted.retain(|sample| {
+                let deviation = (*sample as i64 - median as i64).unsigned_abs();
+                deviation * 100 <= max_deviation_percent as u64 * median as u64
+            });
+        }
+        sorted
+    }
+
+    fn aggregate_inliers(&self, sorted: &[u32]) -> Option<u32> {
+        match self.method {
+            AggregationMethod::Median => median(sorted),
+            AggregationMethod::TrimmedMean { trim_percent } => {
+                let trim = sorted.len() * trim_percent as usize / 100;
+                let kept = &sorted[trim..sorted.len() - trim];
+                if kept.is_empty() {
+                    return None;
+                }
+                let sum: u64 = kept.iter().map(|sample| *sample as u64).sum();
+                Some((sum / kept.len() as u64) as u32)
+            }
+        }
+    }
+}
+
+fn median(sorted: &[u32]) -> Option<u32> {
+    if sorted.is_empty() {
+        return None;
+    }
+    let middle = sorted.len() / 2;
+    if sorted.len() % 2 == 1 {
+        Some(sorted[middle])
+    } else {
+        Some(((sorted[middle - 1] as u64 + sorted[middle] as u64) / 2) as u32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn median_config(max_deviation_percent: Option<u32>) -> AggregationConfig {
+        AggregationConfig {
+            method: AggregationMethod::Median,
+            max_deviation_percent,
+        }
+    }
+
+    #[test]
+    fn median_of_odd_and_even_samples() {
+        assert_eq!(median_config(None).aggregate(&[4, 1, 3]), Some(3));
+        assert_eq!(median_config(None).aggregate(&[4, 1, 3, 2]), Some(2));
+        assert_eq!(median_config(None).aggregate(&[]), None);
+    }
+
+    #[test]
+    fn outliers_are_rejected() {
+        // 40 deviates from the median (95) by 58%
+        let config = AggregationConfig {
+            method: AggregationMethod::TrimmedMean { trim_percent: 0 },
+            max_deviation_percent: Some(20),
+        };
+        assert_eq!(config.aggregate(&[100, 110, 90, 40]), Some(100));
+    }
+
+    #[test]
+    fn trimmed_mean() {
+        let config = AggregationConfig {
+            method: AggregationMethod::TrimmedMean { trim_percent: 25 },
+            max_deviation_percent: None,
+        };
+        assert_eq!(config.aggregate(&[1, 10, 12, 1000]), Some(11));
+        assert!(!AggregationConfig {
+            method: AggregationMethod::TrimmedMean { trim_percent: 50 },
+            max_deviation_percent: None,
+        }
+        .is_valid());
+    }
+
+    #[test]
+    fn triggers_below_min_samples_are_dropped() {
+        let mut first = HashMap::new();
+        first.insert("category".to_string(), 4);
+        first.insert("wind_speed".to_string(), 120);
+        let mut second = HashMap::new();
+        second.insert("category".to_string(), 4);
+        let aggregate = median_config(None).aggregate_measurements(&[&first, &second], 2);
+        assert_eq!(aggregate.get("category"), Some(&4));
+        assert_eq!(aggregate.get("wind_speed"), None);
+    }
+}
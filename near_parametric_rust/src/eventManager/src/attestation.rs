@@ -0,0 +1,164 @@
+use crate::consensus::Observation;
+use crate::HurricaneOracleError;
+use ed25519_dalek::{PublicKey, Signature, PUBLIC_KEY_LENGTH};
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::json_types::Base64VecU8;
+use near_sdk::serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::convert::TryFrom;
+
+///#Description
+///
+/// a data provider's ed25519 public key, registered by an admin
+///
+/// #Fields
+///
+/// *`public_key` the 32 byte public key
+/// *`added_at` block timestamp of the registration
+/// *`revoked_at` block timestamp of the revocation. reports signed with a revoked key are
+/// refused, but the key is kept so earlier signatures can still be verified
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ProviderKey {
+    pub public_key: Base64VecU8,
+    pub added_at: u64,
+    pub revoked_at: Option<u64>,
+}
+
+impl ProviderKey {
+    pub fn is_revoked(&self) -> bool {
+        self.revoked_at.is_some()
+    }
+}
+
+///#Description
+///
+/// a data provider's signature over a report's `ProviderPayload`
+///
+/// #Fields
+///
+/// *`key_id` the id the provider's `ProviderKey` is registered under
+/// *`signature` the 64 byte ed25519 signature
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Attestation {
+    pub key_id: String,
+    pub signature: Base64VecU8,
+}
+
+///#Description
+///
+/// the event data a provider signs. the signed message is the Borsh encoding of this struct:
+/// the event id as a length prefixed string, the date as a little endian u64 and the
+/// measurements as a u32 count followed by (name, value) pairs in ascending name order
+#[derive(BorshSerialize, Debug, PartialEq)]
+pub struct ProviderPayload {
+    pub event_id: String,
+    pub date: u64,
+    pub measurements: BTreeMap<String, u32>,
+}
+
+impl ProviderPayload {
+    pub fn new(observation: &Observation, measurements: &HashMap<String, u32>) -> Self {
+        Self {
+            event_id: observation.event_id.clone(),
+            date: observation.date,
+            measurements: measurements
+                .iter()
+                .map(|(name, value)| (name.clone(), *value))
+                .collect(),
+        }
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.try_to_vec().expect("ERR_PAYLOAD_SERIALIZATION")
+    }
+}
+
+///check that `public_key` is a valid ed25519 public key
+pub fn validate_public_key(public_key: &[u8]) -> Result<(), HurricaneOracleError> {
+    if public_key.len() != PUBLIC_KEY_LENGTH {
+        return Err(HurricaneOracleError::InvalidProviderKey);
+    }
+    PublicKey::from_bytes(public_key)
+        .map(|_| ())
+        .map_err(|_| HurricaneOracleError::InvalidProviderKey)
+}
+
+///#Description
+///
+/// verify an ed25519 `signature` of `payload`. malleable signatures and weak keys are refused
+pub fn verify(
+    public_key: &[u8],
+    payload: &ProviderPayload,
+    signature: &[u8],
+) -> Result<(), HurricaneOracleError> {
+    let public_key =
+        PublicKey::from_bytes(public_key).map_err(|_| HurricaneOracleError::InvalidProviderKey)?;
+    let signature =
+        Signature::try_from(signature).map_err(|_| HurricaneOracleError::InvalidSignature)?;
+    public_key
+        .verify_strict(&payload.to_bytes(), &signature)
+        .map_err(|_| HurricaneOracleError::InvalidSignature)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_dalek::{ExpandedSecretKey, SecretKey};
+
+    fn sign(secret: &[u8; 32], payload: &ProviderPayload) -> (Vec<u8>, Vec<u8>) {
+        let secret = SecretKey::from_bytes(secret).unwrap();
+        let public = PublicKey::from(&secret);
+        let signature = ExpandedSecretKey::from(&secret).sign(&payload.to_bytes(), &public);
+        (public.to_bytes().to_vec(), signature.to_bytes().to_vec())
+    }
+
+    fn payload(wind_speed: u32) -> ProviderPayload {
+        let mut measurements = HashMap::new();
+        measurements.insert("wind_speed".to_string(), wind_speed);
+        measurements.insert("category".to_string(), 4);
+        ProviderPayload::new(
+            &Observation {
+                event_id: "al092022".to_string(),
+                date: 1_664_323_200_000_000_000,
+            },
+            &measurements,
+        )
+    }
+
+    #[test]
+    fn canonical_encoding() {
+        let bytes = payload(130).to_bytes();
+        // "al092022", the date, then two measurements with "category" first
+        assert_eq!(&bytes[0..4], &8u32.to_le_bytes());
+        assert_eq!(&bytes[12..20], &1_664_323_200_000_000_000u64.to_le_bytes());
+        assert_eq!(&bytes[20..24], &2u32.to_le_bytes());
+        assert_eq!(&bytes[28..36], b"category");
+        assert_eq!(bytes, payload(130).to_bytes());
+    }
+
+    #[test]
+    fn signatures() {
+        let (public_key, signature) = sign(&[7; 32], &payload(130));
+        assert_eq!(validate_public_key(&public_key), Ok(()));
+        assert_eq!(verify(&public_key, &payload(130), &signature), Ok(()));
+        assert_eq!(
+            verify(&public_key, &payload(131), &signature),
+            Err(HurricaneOracleError::InvalidSignature)
+        );
+        let (other_key, _) = sign(&[8; 32], &payload(130));
+        assert_eq!(
+            verify(&other_key, &payload(130), &signature),
+            Err(HurricaneOracleError::InvalidSignature)
+        );
+        assert_eq!(
+            verify(&public_key, &payload(130), &signature[1..]),
+            Err(HurricaneOracleError::InvalidSignature)
+        );
+        assert_eq!(
+            validate_public_key(&public_key[1..]),
+            Err(HurricaneOracleError::InvalidProviderKey)
+        );
+    }
+}
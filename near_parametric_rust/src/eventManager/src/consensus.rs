@@ -0,0 +1,186 @@
+use crate::attestation::Attestation;
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::AccountId;
+use std::collections::HashMap;
+
+///#Description
+///
+/// what a node observed for a `Request`
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Observation {
+    ///the event's unique id
+    pub event_id: String,
+    ///the date of the event in nanoseconds
+    pub date: u64,
+}
+
+///#Description
+///
+/// one node's report in a `ReportingRound`
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct NodeReport {
+    ///the reporting node
+    pub node: AccountId,
+    pub observation: Observation,
+    ///numeric readings keyed by trigger name (e.g. `category`, `wind_speed`, `distance`)
+    pub measurements: HashMap<String, u32>,
+    ///block timestamp of the report
+    pub submitted_at: u64,
+    ///the data provider's signature over `observation` and `measurements`
+    pub attestation: Attestation,
+}
+
+///#Description
+///
+/// the reports of the authorized nodes for one `Request`. the round is decided once `quorum`
+/// nodes agree on the same `Observation`, and is kept afterwards for audits. the measurements
+/// of the agreeing nodes are then aggregated into `triggering_values`
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Debug, PartialEq, Clone, Default)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ReportingRound {
+    pub reports: Vec<NodeReport>,
+    ///the agreed observation, once quorum is reached
+    pub outcome: Option<Observation>,
+    ///the aggregated measurements of the nodes that agree with `outcome`
+    pub triggering_values: HashMap<String, u32>,
+    ///whether the nodes that missed the report deadline were slashed
+    pub deadline_enforced: bool,
+}
+
+impl ReportingRound {
+    pub fn has_reported(&self, node: &AccountId) -> bool {
+        self.reports.iter().any(|report| report.node == *node)
+    }
+
+    ///block timestamp of the first report
+    pub fn started_at(&self) -> Option<u64> {
+        self.reports.iter().map(|report| report.submitted_at).min()
+    }
+
+    ///the nodes whose reports agree with `observation`
+    pub fn agreeing_nodes(&self, observation: &Observation) -> Vec<AccountId> {
+        self.reports
+            .iter()
+            .filter(|report| report.observation == *observation)
+            .map(|report| report.node.clone())
+            .collect()
+    }
+
+    ///the measurements of the reports that agree with `observation`
+    pub fn agreeing_measurements(&self, observation: &Observation) -> Vec<&HashMap<String, u32>> {
+        self.reports
+            .iter()
+            .filter(|report| report.observation == *observation)
+            .map(|report| &report.measurements)
+            .collect()
+    }
+
+    ///#Description
+    ///
+    /// the observation that at least `quorum` of the `eligible` nodes reported, if any
+    pub fn agreed_observation(&self, quorum: u64, eligible: &[AccountId]) -> Option<Observation> {
+        let eligible_reports: Vec<&NodeReport> = self
+            .reports
+            .iter()
+            .filter(|report| eligible.contains(&report.node))
+            .collect();
+        eligible_reports
+            .iter()
+            .map(|report| &report.observation)
+            .find(|observation| {
+                eligible_reports
+                    .iter()
+                    .filter(|report| report.observation == **observation)
+                    .count() as u64
+                    >= quorum
+            })
+            .cloned()
+    }
+
+    ///#Description
+    ///
+    /// the reports that differ from the outcome. while undecided, every report when the nodes
+    /// do not all agree
+    pub fn disagreements(&self) -> Vec<NodeReport> {
+        let reference = match &self.outcome {
+            Some(outcome) => outcome.clone(),
+            None => match self.reports.first() {
+                Some(report) => report.observation.clone(),
+                None => return Vec::new(),
+            },
+        };
+        let dissenting = self
+            .reports
+            .iter()
+            .filter(|report| report.observation != reference);
+        if self.outcome.is_some() {
+            dissenting.cloned().collect()
+        } else if dissenting.count() > 0 {
+            self.reports.clone()
+        } else {
+            Vec::new()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report(node: &str, event_id: &str) -> NodeReport {
+        NodeReport {
+            node: node.parse().unwrap(),
+            observation: Observation {
+                event_id: event_id.to_string(),
+                date: 1,
+            },
+            measurements: HashMap::new(),
+            submitted_at: 0,
+            attestation: Attestation {
+                key_id: "provider".to_string(),
+                signature: Vec::new().into(),
+            },
+        }
+    }
+
+    fn nodes(names: &[&str]) -> Vec<AccountId> {
+        names.iter().map(|name| name.parse().unwrap()).collect()
+    }
+
+    #[test]
+    fn quorum_of_agreeing_reports() {
+        let mut round = ReportingRound::default();
+        round.reports.push(report("a.near", "ian"));
+        round.reports.push(report("b.near", "ida"));
+        let eligible = nodes(&["a.near", "b.near", "c.near"]);
+        assert_eq!(round.agreed_observation(2, &eligible), None);
+        round.reports.push(report("c.near", "ian"));
+        assert_eq!(
+            round.agreed_observation(2, &eligible).unwrap().event_id,
+            "ian".to_string()
+        );
+    }
+
+    #[test]
+    fn removed_nodes_do_not_count() {
+        let mut round = ReportingRound::default();
+        round.reports.push(report("a.near", "ian"));
+        round.reports.push(report("b.near", "ian"));
+        assert_eq!(round.agreed_observation(2, &nodes(&["a.near"])), None);
+    }
+
+    #[test]
+    fn disagreements() {
+        let mut round = ReportingRound::default();
+        round.reports.push(report("a.near", "ian"));
+        assert!(round.disagreements().is_empty());
+        round.reports.push(report("b.near", "ida"));
+        assert_eq!(round.disagreements().len(), 2);
+        round.reports.push(report("c.near", "ian"));
+        round.outcome = round.agreed_observation(2, &nodes(&["a.near", "b.near", "c.near"]));
+        assert_eq!(round.disagreements(), vec![report("b.near", "ida")]);
+    }
+}
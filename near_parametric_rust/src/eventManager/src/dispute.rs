@@ -0,0 +1,117 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::json_types::U128;
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::AccountId;
+
+///#Description
+///
+/// optimistic mode settings. an agreed outcome waits `liveness` nanoseconds before the
+/// `PolicyManager` is called back, and can be disputed in the meantime
+///
+/// #Fields
+///
+/// *`liveness` how long an outcome can be disputed, in nanoseconds
+/// *`dispute_bond` the yoctoNEAR a disputer other than an admin must attach
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct OptimisticConfig {
+    pub liveness: u64,
+    pub dispute_bond: U128,
+}
+
+impl OptimisticConfig {
+    pub fn is_valid(&self) -> bool {
+        self.liveness > 0
+    }
+}
+
+///#Description
+///
+/// a challenge of a pending outcome
+///
+/// #Fields
+///
+/// *`disputer` the account that challenged the outcome
+/// *`bond` the yoctoNEAR it attached. refunded if the outcome is overturned, slashed otherwise
+/// *`reason` why the outcome is wrong
+/// *`disputed_at` block timestamp of the dispute
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Dispute {
+    pub disputer: AccountId,
+    pub bond: U128,
+    pub reason: String,
+    pub disputed_at: u64,
+}
+
+///#Description
+///
+/// an agreed outcome waiting out its dispute window. the outcome and triggering values are
+/// in the request's `ReportingRound`
+///
+/// #Fields
+///
+/// *`reports` how many node reports the round had when quorum was reached
+/// *`nodes` the bonded nodes that backed the outcome, paid or slashed once it is settled
+/// *`proposed_at` block timestamp of the agreement
+/// *`finalizes_at` when the outcome can be finalized if it is not disputed
+/// *`dispute_bond` the bond required to dispute it, fixed when it was proposed
+/// *`dispute` the challenge, if any
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct PendingOutcome {
+    pub reports: u64,
+    pub nodes: Vec<AccountId>,
+    pub proposed_at: u64,
+    pub finalizes_at: u64,
+    pub dispute_bond: U128,
+    pub dispute: Option<Dispute>,
+}
+
+impl PendingOutcome {
+    pub fn new(config: &OptimisticConfig, reports: u64, nodes: Vec<AccountId>, now: u64) -> Self {
+        Self {
+            reports,
+            nodes,
+            proposed_at: now,
+            finalizes_at: now.saturating_add(config.liveness),
+            dispute_bond: config.dispute_bond,
+            dispute: None,
+        }
+    }
+
+    pub fn is_disputable(&self, now: u64) -> bool {
+        self.dispute.is_none() && now < self.finalizes_at
+    }
+
+    pub fn is_final(&self, now: u64) -> bool {
+        self.dispute.is_none() && now >= self.finalizes_at
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dispute_window() {
+        let config = OptimisticConfig {
+            liveness: 100,
+            dispute_bond: U128(10),
+        };
+        let mut pending = PendingOutcome::new(&config, 2, Vec::new(), 1_000);
+        assert_eq!(pending.finalizes_at, 1_100);
+        assert!(pending.is_disputable(1_099));
+        assert!(!pending.is_final(1_099));
+        assert!(!pending.is_disputable(1_100));
+        assert!(pending.is_final(1_100));
+        pending.dispute = Some(Dispute {
+            disputer: "disputer.near".parse().unwrap(),
+            bond: U128(10),
+            reason: String::new(),
+            disputed_at: 1_050,
+        });
+        assert!(!pending.is_disputable(1_060));
+        assert!(!pending.is_final(1_200));
+    }
+}
@@ -0,0 +1,54 @@
+use crate::peril::{at_least, PerilTrigger, ProtectedArea, TimeWindow};
+use crate::HurricaneOracleError;
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+///the most severe drought category (US Drought Monitor D4)
+pub const MAX_DROUGHT_INDEX: u32 = 4;
+
+///#Description
+///
+/// the conditions under which a drought triggers a policy. nodes report the `drought_index`
+/// of the area, where larger is drier, and for how many days it has held (`duration_days`)
+///
+/// #Fields
+///
+/// *`area` the protected `ProtectedArea`
+/// *`min_index` the lowest drought category that triggers, 0 (D0) to 4 (D4)
+/// *`min_duration_days` how long the drought must last
+/// *`window` when the drought must occur
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct DroughtTrigger {
+    pub area: ProtectedArea,
+    pub min_index: u32,
+    pub min_duration_days: u32,
+    pub window: TimeWindow,
+}
+
+impl PerilTrigger for DroughtTrigger {
+    fn validate(&self) -> Result<(), HurricaneOracleError> {
+        if self.min_index > MAX_DROUGHT_INDEX {
+            return Err(HurricaneOracleError::InvalidDroughtIndex);
+        }
+        if self.min_duration_days == 0 {
+            return Err(HurricaneOracleError::InvalidThreshold);
+        }
+        self.area.validate()?;
+        self.window.validate()
+    }
+
+    fn window(&self) -> &TimeWindow {
+        &self.window
+    }
+
+    fn area(&self) -> &ProtectedArea {
+        &self.area
+    }
+
+    fn matches(&self, measurements: &HashMap<String, u32>) -> bool {
+        at_least(measurements, "drought_index", self.min_index)
+            && at_least(measurements, "duration_days", self.min_duration_days)
+    }
+}
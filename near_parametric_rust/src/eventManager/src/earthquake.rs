@@ -0,0 +1,62 @@
+use crate::peril::{at_least, at_most, validate_radius, PerilTrigger, ProtectedArea, TimeWindow};
+use crate::HurricaneOracleError;
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+///the highest moment magnitude accepted, in tenths
+pub const MAX_MAGNITUDE: u32 = 100;
+
+///#Description
+///
+/// the conditions under which an earthquake triggers a policy. nodes report the `magnitude`
+/// in tenths, the peak ground acceleration `pga` in milli-g and the `distance` from the
+/// epicenter to the area in meters
+///
+/// #Fields
+///
+/// *`min_magnitude` the lowest moment magnitude that triggers, in tenths (65 is M6.5)
+/// *`min_pga` the lowest peak ground acceleration in milli-g that triggers. `None` relies on
+/// `min_magnitude` alone
+/// *`area` the protected `ProtectedArea`
+/// *`radius` how close to `area` the epicenter must be, in meters
+/// *`window` when the earthquake must occur
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct EarthquakeTrigger {
+    pub min_magnitude: u32,
+    pub min_pga: Option<u32>,
+    pub area: ProtectedArea,
+    pub radius: u32,
+    pub window: TimeWindow,
+}
+
+impl PerilTrigger for EarthquakeTrigger {
+    fn validate(&self) -> Result<(), HurricaneOracleError> {
+        if self.min_magnitude == 0 || self.min_magnitude > MAX_MAGNITUDE {
+            return Err(HurricaneOracleError::InvalidMagnitude);
+        }
+        if self.min_pga == Some(0) {
+            return Err(HurricaneOracleError::InvalidThreshold);
+        }
+        self.area.validate()?;
+        validate_radius(self.radius)?;
+        self.window.validate()
+    }
+
+    fn window(&self) -> &TimeWindow {
+        &self.window
+    }
+
+    fn area(&self) -> &ProtectedArea {
+        &self.area
+    }
+
+    fn matches(&self, measurements: &HashMap<String, u32>) -> bool {
+        at_least(measurements, "magnitude", self.min_magnitude)
+            && at_most(measurements, "distance", self.radius)
+            && self
+                .min_pga
+                .is_none_or(|min_pga| at_least(measurements, "pga", min_pga))
+    }
+}
@@ -0,0 +1,237 @@
+use near_sdk::json_types::{Base64VecU8, U128};
+use near_sdk::serde::Serialize;
+use near_sdk::{env, AccountId};
+use std::collections::HashMap;
+
+use crate::{
+    AggregationConfig, NodeConfig, OptimisticConfig, Peril, RequestStatus, SlashReason,
+    SubmissionRules,
+};
+
+/// the NEP-297 `standard` of every event emitted by `HurricaneOracle`
+pub const EVENT_STANDARD: &str = "parametric_hurricane_oracle";
+/// bumped whenever the shape of an existing event's `data` changes
+pub const EVENT_STANDARD_VERSION: &str = "1.5.0";
+
+///#Description
+///
+/// a state change in `HurricaneOracle` that is logged for indexers as `EVENT_JSON:{...}`
+/// following NEP-297. `event` is the snake case name of the variant and `data` a one element array
+#[derive(Serialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+#[serde(tag = "event", content = "data", rename_all = "snake_case")]
+pub enum HurricaneOracleEvent {
+    RequestCreated([RequestData; 1]),
+    ReportSubmitted([ReportData; 1]),
+    RequestFulfilled([RequestFulfilledData; 1]),
+    ObservationDismissed([ObservationDismissedData; 1]),
+    OutcomeProposed([OutcomeProposedData; 1]),
+    OutcomeDisputed([OutcomeDisputedData; 1]),
+    DisputeResolved([DisputeResolvedData; 1]),
+    EventRegistered([EventRegistryData; 1]),
+    EventUpdated([EventRegistryData; 1]),
+    RequestStatusChanged([RequestStatusData; 1]),
+    RequestsSwept([SweepData; 1]),
+    QuorumChanged([QuorumData; 1]),
+    AggregationChanged([AggregationData; 1]),
+    OptimisticConfigChanged([OptimisticConfigData; 1]),
+    ArbiterChanged([ArbiterData; 1]),
+    SubmissionRulesChanged([SubmissionRulesData; 1]),
+    ProviderKeyAdded([ProviderKeyData; 1]),
+    ProviderKeyRevoked([ProviderKeyData; 1]),
+    NodeAdded([AccountData; 1]),
+    NodeBonded([NodeStakeData; 1]),
+    NodeUnbonded([NodeStakeData; 1]),
+    StakeWithdrawn([NodeStakeData; 1]),
+    EarningsClaimed([NodeStakeData; 1]),
+    NodeSlashed([NodeSlashData; 1]),
+    NodeConfigChanged([NodeConfigData; 1]),
+    NodeRemoved([AccountData; 1]),
+    PolicyManagerAdded([AccountData; 1]),
+    PolicyManagerRemoved([AccountData; 1]),
+    AdminAdded([AccountData; 1]),
+    AdminRemoved([AccountData; 1]),
+    MasterAdminChanged([AccountData; 1]),
+}
+
+#[derive(Serialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct RequestData {
+    pub policy_id: String,
+    pub policy_manager: AccountId,
+    pub peril: Peril,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ReportData {
+    pub policy_id: String,
+    pub node: AccountId,
+    pub event_id: String,
+    pub date: u64,
+    pub measurements: HashMap<String, u32>,
+    ///the `ProviderKey` the report was signed with
+    pub key_id: String,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct RequestFulfilledData {
+    pub policy_id: String,
+    pub policy_manager: AccountId,
+    pub event_id: String,
+    pub date: u64,
+    ///number of node reports in the round when quorum was reached
+    pub reports: u64,
+    pub triggering_values: HashMap<String, u32>,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ObservationDismissedData {
+    pub policy_id: String,
+    pub event_id: String,
+    pub triggering_values: HashMap<String, u32>,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct OutcomeProposedData {
+    pub policy_id: String,
+    pub event_id: String,
+    pub finalizes_at: u64,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct OutcomeDisputedData {
+    pub policy_id: String,
+    pub disputer: AccountId,
+    pub bond: U128,
+    pub reason: String,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct DisputeResolvedData {
+    pub policy_id: String,
+    pub resolver: AccountId,
+    ///whether the outcome stood, in which case the disputer's bond was slashed
+    pub outcome_upheld: bool,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct EventRegistryData {
+    pub event_id: String,
+    pub peril: Peril,
+    pub name: String,
+    pub start: u64,
+    pub end: u64,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct RequestStatusData {
+    pub policy_id: String,
+    pub status: RequestStatus,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct SweepData {
+    pub policy_ids: Vec<String>,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct QuorumData {
+    pub quorum: u64,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct AggregationData {
+    pub aggregation: AggregationConfig,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct OptimisticConfigData {
+    pub optimistic: Option<OptimisticConfig>,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct SubmissionRulesData {
+    pub rules: SubmissionRules,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ArbiterData {
+    pub arbiter: Option<AccountId>,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct NodeStakeData {
+    pub account_id: AccountId,
+    pub amount: U128,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct NodeSlashData {
+    pub account_id: AccountId,
+    pub amount: U128,
+    pub policy_id: String,
+    pub reason: SlashReason,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct NodeConfigData {
+    pub config: NodeConfig,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ProviderKeyData {
+    pub key_id: String,
+    pub public_key: Base64VecU8,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct AccountData {
+    pub account_id: AccountId,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+struct EventLog<'a> {
+    standard: &'static str,
+    version: &'static str,
+    #[serde(flatten)]
+    event: &'a HurricaneOracleEvent,
+}
+
+impl HurricaneOracleEvent {
+    /// log the event as `EVENT_JSON:{...}`
+    pub fn emit(&self) {
+        env::log(self.to_log_string().as_bytes());
+    }
+
+    fn to_log_string(&self) -> String {
+        let log = EventLog {
+            standard: EVENT_STANDARD,
+            version: EVENT_STANDARD_VERSION,
+            event: self,
+        };
+        format!(
+            "EVENT_JSON:{}",
+            near_sdk::serde_json::to_string(&log).expect("ERR_EVENT_SERIALIZATION")
+        )
+    }
+}
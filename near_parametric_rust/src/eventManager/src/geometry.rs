@@ -0,0 +1,284 @@
+use crate::peril::{Coordinate, ProtectedArea};
+use crate::registry::TrackPoint;
+use near_sdk::serde::{Deserialize, Serialize};
+use std::f64::consts::PI;
+
+///mean earth radius in meters
+const EARTH_RADIUS: f64 = 6_371_008.8;
+
+///#Description
+///
+/// where a track passes closest to a protected area
+///
+/// #Fields
+///
+/// *`distance` the great-circle distance in meters. 0 when the track crosses the area
+/// *`timestamp` when the track was closest, interpolated between its points
+/// *`point_index` the index of the track point nearest to the closest approach
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Approach {
+    pub distance: u32,
+    pub timestamp: u64,
+    pub point_index: usize,
+}
+
+///the great-circle distance between two coordinates in meters
+pub fn distance(first: &Coordinate, second: &Coordinate) -> u32 {
+    (angular_distance(first, second) * EARTH_RADIUS).round() as u32
+}
+
+///#Description
+///
+/// the closest great-circle approach of a track to a protected area. the track and the edges
+/// of a polygon are followed along great circles between consecutive points. `None` for an
+/// empty track
+pub fn closest_approach(track: &[TrackPoint], area: &ProtectedArea) -> Option<Approach> {
+    match area {
+        ProtectedArea::Point(point) => closest_approach_to_point(track, point),
+        ProtectedArea::Polygon(vertices) => {
+            if let Some(index) = track
+                .iter()
+                .position(|track_point| contains(vertices, &track_point.position))
+            {
+                return Some(Approach {
+                    distance: 0,
+                    timestamp: track[index].timestamp,
+                    point_index: index,
+                });
+            }
+            if let Some(index) = (1..track.len()).find(|index| {
+                crosses_polygon(
+                    &track[index - 1].position,
+                    &track[*index].position,
+                    vertices,
+                )
+            }) {
+                return Some(Approach {
+                    distance: 0,
+                    timestamp: track[index - 1].timestamp,
+                    point_index: index - 1,
+                });
+            }
+            // two segments that do not cross are closest at an end point of one of them
+            let to_vertices = vertices
+                .iter()
+                .filter_map(|vertex| closest_approach_to_point(track, vertex));
+            let to_edges = track.iter().enumerate().flat_map(|(index, track_point)| {
+                edges(vertices).map(move |(start, end)| {
+                    let (angular, _) = segment_distance(start, end, &track_point.position);
+                    Approach {
+                        distance: (angular * EARTH_RADIUS).round() as u32,
+                        timestamp: track_point.timestamp,
+                        point_index: index,
+                    }
+                })
+            });
+            to_vertices
+                .chain(to_edges)
+                .min_by_key(|approach| approach.distance)
+        }
+    }
+}
+
+///the edges of a polygon, the last closing it back to the first vertex
+fn edges(vertices: &[Coordinate]) -> impl Iterator<Item = (&Coordinate, &Coordinate)> {
+    vertices.iter().zip(vertices.iter().cycle().skip(1))
+}
+
+fn closest_approach_to_point(track: &[TrackPoint], point: &Coordinate) -> Option<Approach> {
+    let first = track.first()?;
+    let mut closest = Approach {
+        distance: distance(&first.position, point),
+        timestamp: first.timestamp,
+        point_index: 0,
+    };
+    for index in 1..track.len() {
+        let start = &track[index - 1];
+        let end = &track[index];
+        let (angular, fraction) = segment_distance(&start.position, &end.position, point);
+        let distance = (angular * EARTH_RADIUS).round() as u32;
+        if distance < closest.distance {
+            let elapsed = end.timestamp.saturating_sub(start.timestamp) as f64 * fraction;
+            closest = Approach {
+                distance,
+                timestamp: start.timestamp + elapsed as u64,
+                point_index: if fraction < 0.5 { index - 1 } else { index },
+            };
+        }
+    }
+    Some(closest)
+}
+
+///#Description
+///
+/// the angular distance from `point` to the great-circle segment from `start` to `end`, and
+/// how far along the segment (0 to 1) the closest point lies
+fn segment_distance(start: &Coordinate, end: &Coordinate, point: &Coordinate) -> (f64, f64) {
+    let to_point = angular_distance(start, point);
+    let length = angular_distance(start, end);
+    if length == 0.0 {
+        return (to_point, 0.0);
+    }
+    let relative_bearing = bearing(start, point) - bearing(start, end);
+    if relative_bearing.cos() < 0.0 {
+        return (to_point, 0.0);
+    }
+    let cross_track = (to_point.sin() * relative_bearing.sin()).asin();
+    let along_track = (to_point.cos() / cross_track.cos()).clamp(-1.0, 1.0).acos();
+    if along_track > length {
+        return (angular_distance(end, point), 1.0);
+    }
+    (cross_track.abs(), along_track / length)
+}
+
+fn radians(microdegrees: i32) -> f64 {
+    microdegrees as f64 / 1_000_000.0 * PI / 180.0
+}
+
+fn angular_distance(first: &Coordinate, second: &Coordinate) -> f64 {
+    let (latitude_1, latitude_2) = (radians(first.latitude), radians(second.latitude));
+    let delta_latitude = latitude_2 - latitude_1;
+    let delta_longitude = radians(second.longitude) - radians(first.longitude);
+    let haversine = (delta_latitude / 2.0).sin().powi(2)
+        + latitude_1.cos() * latitude_2.cos() * (delta_longitude / 2.0).sin().powi(2);
+    2.0 * haversine.sqrt().min(1.0).asin()
+}
+
+fn bearing(from: &Coordinate, to: &Coordinate) -> f64 {
+    let (latitude_1, latitude_2) = (radians(from.latitude), radians(to.latitude));
+    let delta_longitude = radians(to.longitude) - radians(from.longitude);
+    let y = delta_longitude.sin() * latitude_2.cos();
+    let x = latitude_1.cos() * latitude_2.sin()
+        - latitude_1.sin() * latitude_2.cos() * delta_longitude.cos();
+    y.atan2(x)
+}
+
+///is `point` inside the polygon, by ray casting on the microdegree grid
+fn contains(vertices: &[Coordinate], point: &Coordinate) -> bool {
+    let mut inside = false;
+    let mut previous = match vertices.last() {
+        Some(vertex) => vertex,
+        None => return false,
+    };
+    for vertex in vertices.iter() {
+        if (vertex.latitude > point.latitude) != (previous.latitude > point.latitude) {
+            let crossing = vertex.longitude as i64
+                + (point.latitude as i64 - vertex.latitude as i64)
+                    * (previous.longitude as i64 - vertex.longitude as i64)
+                    / (previous.latitude as i64 - vertex.latitude as i64);
+            if (point.longitude as i64) < crossing {
+                inside = !inside;
+            }
+        }
+        previous = vertex;
+    }
+    inside
+}
+
+fn crosses_polygon(start: &Coordinate, end: &Coordinate, vertices: &[Coordinate]) -> bool {
+    let mut previous = match vertices.last() {
+        Some(vertex) => vertex,
+        None => return false,
+    };
+    for vertex in vertices.iter() {
+        if segments_intersect(start, end, previous, vertex) {
+            return true;
+        }
+        previous = vertex;
+    }
+    false
+}
+
+fn segments_intersect(a: &Coordinate, b: &Coordinate, c: &Coordinate, d: &Coordinate) -> bool {
+    let orientation = |p: &Coordinate, q: &Coordinate, r: &Coordinate| -> i64 {
+        let value = (q.longitude as i64 - p.longitude as i64)
+            * (r.latitude as i64 - p.latitude as i64)
+            - (q.latitude as i64 - p.latitude as i64) * (r.longitude as i64 - p.longitude as i64);
+        value.signum()
+    };
+    orientation(a, b, c) * orientation(a, b, d) < 0
+        && orientation(c, d, a) * orientation(c, d, b) < 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn coordinate(latitude: f64, longitude: f64) -> Coordinate {
+        Coordinate {
+            latitude: (latitude * 1_000_000.0) as i32,
+            longitude: (longitude * 1_000_000.0) as i32,
+        }
+    }
+
+    fn track(points: &[(u64, f64, f64)]) -> Vec<TrackPoint> {
+        points
+            .iter()
+            .map(|(timestamp, latitude, longitude)| TrackPoint {
+                timestamp: *timestamp,
+                position: coordinate(*latitude, *longitude),
+                measurements: HashMap::new(),
+                reporters: Vec::new(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn distance_between_cities() {
+        // Miami to Havana is about 368 km
+        let distance = distance(
+            &coordinate(25.7617, -80.1918),
+            &coordinate(23.1136, -82.3666),
+        );
+        assert!((360_000..375_000).contains(&distance), "{}", distance);
+    }
+
+    #[test]
+    fn closest_approach_between_fixes() {
+        // a track along the equator passes one degree (about 111 km) south of the point
+        let track = track(&[(0, 0.0, -1.0), (100, 0.0, 1.0)]);
+        let approach =
+            closest_approach(&track, &ProtectedArea::Point(coordinate(1.0, 0.0))).unwrap();
+        assert!((110_000..112_500).contains(&approach.distance));
+        assert_eq!(approach.timestamp, 50);
+        // past the end of the track the end point is closest
+        let approach =
+            closest_approach(&track, &ProtectedArea::Point(coordinate(0.0, 3.0))).unwrap();
+        assert_eq!(approach.point_index, 1);
+        assert_eq!(approach.timestamp, 100);
+    }
+
+    #[test]
+    fn tracks_crossing_a_polygon() {
+        let square = vec![
+            coordinate(-1.0, -1.0),
+            coordinate(-1.0, 1.0),
+            coordinate(1.0, 1.0),
+            coordinate(1.0, -1.0),
+        ];
+        let area = ProtectedArea::Polygon(square);
+        let through = track(&[(0, 0.0, -3.0), (10, 0.0, 3.0)]);
+        assert_eq!(closest_approach(&through, &area).unwrap().distance, 0);
+        let inside = track(&[(0, 0.5, 0.5)]);
+        assert_eq!(closest_approach(&inside, &area).unwrap().distance, 0);
+        let outside = track(&[(0, 3.0, -3.0), (10, 3.0, 3.0)]);
+        assert!(closest_approach(&outside, &area).unwrap().distance > 200_000);
+        assert!(closest_approach(&[], &area).is_none());
+    }
+
+    #[test]
+    fn closest_approach_to_the_middle_of_a_polygon_edge() {
+        // the track passes a degree north of a 10° long edge, 5° from its vertices
+        let strip = vec![
+            coordinate(-1.0, -5.0),
+            coordinate(-1.0, 5.0),
+            coordinate(1.0, 5.0),
+            coordinate(1.0, -5.0),
+        ];
+        let passing = track(&[(0, 2.0, -0.5), (10, 2.0, 0.5)]);
+        let approach = closest_approach(&passing, &ProtectedArea::Polygon(strip)).unwrap();
+        assert!((110_000..112_500).contains(&approach.distance), "{}", approach.distance);
+    }
+}
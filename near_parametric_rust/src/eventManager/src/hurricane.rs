@@ -0,0 +1,250 @@
+use crate::geometry::{closest_approach, Approach};
+use crate::peril::{
+    at_least, at_most, validate_radius, Coordinate, PerilTrigger, ProtectedArea, TimeWindow,
+};
+use crate::registry::TrackPoint;
+use crate::HurricaneOracleError;
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+///the highest Saffir-Simpson category
+pub const MAX_CATEGORY: u8 = 5;
+
+///#Description
+///
+/// the conditions under which a hurricane triggers a policy. nodes report the `category`,
+/// sustained `wind_speed` in knots and closest `distance` to the area in meters
+///
+/// #Fields
+///
+/// *`min_category` the lowest Saffir-Simpson category that triggers, 1 to 5
+/// *`area` the protected `ProtectedArea`
+/// *`radius` how close to `area` the storm must pass, in meters
+/// *`min_wind_speed` the lowest sustained wind speed in knots that triggers. `None` relies on
+/// `min_category` alone
+/// *`window` when the storm must occur
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct HurricaneTrigger {
+    pub min_category: u8,
+    pub area: ProtectedArea,
+    pub radius: u32,
+    pub min_wind_speed: Option<u32>,
+    pub window: TimeWindow,
+}
+
+///#Description
+///
+/// one position fix of a storm track
+///
+/// #Fields
+///
+/// *`timestamp` when the fix was taken, in nanoseconds
+/// *`position` the center of the storm
+/// *`category` the Saffir-Simpson category at the time. 0 below hurricane strength
+/// *`max_wind` the maximum sustained wind in knots
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct StormFix {
+    pub timestamp: u64,
+    pub position: Coordinate,
+    pub category: u8,
+    pub max_wind: u32,
+}
+
+impl From<StormFix> for TrackPoint {
+    fn from(fix: StormFix) -> Self {
+        let mut measurements = HashMap::new();
+        measurements.insert("category".to_string(), fix.category as u32);
+        measurements.insert("wind_speed".to_string(), fix.max_wind);
+        TrackPoint {
+            timestamp: fix.timestamp,
+            position: fix.position,
+            measurements,
+            reporters: Vec::new(),
+        }
+    }
+}
+
+impl HurricaneTrigger {
+    ///#Description
+    ///
+    /// the triggering values of a storm track, computed on-chain. `distance` is the closest
+    /// great-circle approach to the area; `category` and `wind_speed` are the strongest of the
+    /// fixes within `radius` of the area, or of the fix nearest the closest approach when none is
+    ///
+    /// #Returns
+    ///
+    /// the triggering values and the closest `Approach`. `None` for an empty track
+    pub fn evaluate_track(&self, track: &[TrackPoint]) -> Option<(HashMap<String, u32>, Approach)> {
+        let approach = closest_approach(track, &self.area)?;
+        let mut nearby: Vec<&TrackPoint> = track
+            .iter()
+            .filter(|point| {
+                closest_approach(std::slice::from_ref(*point), &self.area)
+                    .is_some_and(|point_approach| point_approach.distance <= self.radius)
+            })
+            .collect();
+        if nearby.is_empty() {
+            nearby.push(&track[approach.point_index]);
+        }
+        let mut triggering_values = HashMap::new();
+        triggering_values.insert("distance".to_string(), approach.distance);
+        for measurement in ["category", "wind_speed"].iter() {
+            if let Some(peak) = nearby
+                .iter()
+                .filter_map(|point| point.measurements.get(*measurement))
+                .max()
+            {
+                triggering_values.insert(measurement.to_string(), *peak);
+            }
+        }
+        Some((triggering_values, approach))
+    }
+}
+
+impl PerilTrigger for HurricaneTrigger {
+    fn validate(&self) -> Result<(), HurricaneOracleError> {
+        if self.min_category == 0 || self.min_category > MAX_CATEGORY {
+            return Err(HurricaneOracleError::InvalidCategory);
+        }
+        self.area.validate()?;
+        validate_radius(self.radius)?;
+        if self.min_wind_speed == Some(0) {
+            return Err(HurricaneOracleError::InvalidThreshold);
+        }
+        self.window.validate()
+    }
+
+    fn window(&self) -> &TimeWindow {
+        &self.window
+    }
+
+    fn area(&self) -> &ProtectedArea {
+        &self.area
+    }
+
+    fn matches(&self, measurements: &HashMap<String, u32>) -> bool {
+        at_least(measurements, "category", self.min_category as u32)
+            && at_most(measurements, "distance", self.radius)
+            && self.min_wind_speed.is_none_or(|min_wind_speed| {
+                at_least(measurements, "wind_speed", min_wind_speed)
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::peril::Coordinate;
+
+    fn miami() -> Coordinate {
+        Coordinate {
+            latitude: 25_761_680,
+            longitude: -80_191_790,
+        }
+    }
+
+    fn trigger() -> HurricaneTrigger {
+        HurricaneTrigger {
+            min_category: 3,
+            area: ProtectedArea::Point(miami()),
+            radius: 50_000,
+            min_wind_speed: Some(96),
+            window: TimeWindow { start: 1, end: 2 },
+        }
+    }
+
+    #[test]
+    fn valid_trigger() {
+        assert!(trigger().validate().is_ok());
+        let mut polygon = trigger();
+        polygon.area = ProtectedArea::Polygon(vec![
+            miami(),
+            Coordinate {
+                latitude: 25_900_000,
+                longitude: -80_191_790,
+            },
+            Coordinate {
+                latitude: 25_900_000,
+                longitude: -80_000_000,
+            },
+        ]);
+        assert!(polygon.validate().is_ok());
+    }
+
+    #[test]
+    fn invalid_triggers() {
+        let mut invalid = trigger();
+        invalid.min_category = 6;
+        assert!(matches!(
+            invalid.validate(),
+            Err(HurricaneOracleError::InvalidCategory)
+        ));
+        let mut invalid = trigger();
+        invalid.area = ProtectedArea::Point(Coordinate {
+            latitude: 91_000_000,
+            longitude: 0,
+        });
+        assert!(matches!(
+            invalid.validate(),
+            Err(HurricaneOracleError::InvalidCoordinate)
+        ));
+        let mut invalid = trigger();
+        invalid.area = ProtectedArea::Polygon(vec![miami(), miami()]);
+        assert!(matches!(
+            invalid.validate(),
+            Err(HurricaneOracleError::InvalidPolygon)
+        ));
+        let mut invalid = trigger();
+        invalid.window = TimeWindow { start: 2, end: 2 };
+        assert!(matches!(
+            invalid.validate(),
+            Err(HurricaneOracleError::InvalidTimeWindow)
+        ));
+    }
+
+    #[test]
+    fn track_evaluation() {
+        // passes over miami as a category 4, weakening to a category 2 further north
+        let fixes = vec![
+            StormFix {
+                timestamp: 1,
+                position: Coordinate {
+                    latitude: 25_661_680,
+                    longitude: -80_191_790,
+                },
+                category: 4,
+                max_wind: 120,
+            },
+            StormFix {
+                timestamp: 2,
+                position: Coordinate {
+                    latitude: 28_000_000,
+                    longitude: -80_191_790,
+                },
+                category: 2,
+                max_wind: 90,
+            },
+        ];
+        let track: Vec<TrackPoint> = fixes.into_iter().map(TrackPoint::from).collect();
+        let (values, approach) = trigger().evaluate_track(&track).unwrap();
+        assert_eq!(approach.point_index, 0);
+        assert!(values["distance"] < 12_000);
+        assert_eq!(values["category"], 4);
+        assert_eq!(values["wind_speed"], 120);
+        assert!(trigger().matches(&values));
+    }
+
+    #[test]
+    fn matching() {
+        let mut measurements = HashMap::new();
+        measurements.insert("category".to_string(), 4);
+        measurements.insert("distance".to_string(), 30_000);
+        measurements.insert("wind_speed".to_string(), 120);
+        assert!(trigger().matches(&measurements));
+        measurements.insert("distance".to_string(), 60_000);
+        assert!(!trigger().matches(&measurements));
+    }
+}
@@ -1,76 +1,424 @@
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
-use near_sdk::collections::UnorderedMap;
+use near_sdk::collections::{LookupMap, UnorderedMap};
+use near_sdk::serde::{Deserialize, Serialize};
 use near_sdk::collections::Vector;
+use near_sdk::json_types::{Base64VecU8, U128};
 use near_sdk::{
-    env, ext_contract, init, near_bindgen, AccountId, BorshStorageKey, PanicOnDefault, Promise,
+    env, ext_contract, near_bindgen, AccountId, BorshStorageKey, PanicOnDefault, Promise,
     PromiseResult,
 };
 use std::collections::HashMap;
 use std::error::Error;
 use std::fmt;
+
+mod aggregation;
+mod attestation;
+mod consensus;
+mod dispute;
+mod drought;
+mod earthquake;
+mod events;
+mod geometry;
+mod hurricane;
+mod nodes;
+mod peril;
+mod rainfall;
+mod registry;
+mod spatial;
+mod submission;
+
+pub use crate::aggregation::{AggregationConfig, AggregationMethod};
+pub use crate::attestation::{Attestation, ProviderKey, ProviderPayload};
+pub use crate::consensus::{NodeReport, Observation, ReportingRound};
+pub use crate::dispute::{Dispute, OptimisticConfig, PendingOutcome};
+pub use crate::drought::DroughtTrigger;
+pub use crate::earthquake::EarthquakeTrigger;
+pub use crate::geometry::Approach;
+pub use crate::hurricane::{HurricaneTrigger, StormFix};
+pub use crate::nodes::{NodeAccount, NodeConfig, NodeStats, SlashReason};
+pub use crate::peril::{Coordinate, Peril, PerilTrigger, ProtectedArea, TimeWindow, Trigger};
+pub use crate::rainfall::RainfallTrigger;
+pub use crate::registry::{EventRecord, TrackPoint};
+pub use crate::submission::SubmissionRules;
+use crate::attestation::validate_public_key;
+use crate::nodes::split;
+use crate::spatial::{area_cells, Region, MAX_QUERY_CELLS};
+use crate::events::{
+    AccountData, AggregationData, ArbiterData, DisputeResolvedData, EventRegistryData,
+    HurricaneOracleEvent, NodeConfigData, NodeSlashData, NodeStakeData, ObservationDismissedData,
+    OptimisticConfigData, OutcomeDisputedData, OutcomeProposedData, ProviderKeyData, QuorumData,
+    ReportData, RequestData, RequestFulfilledData, RequestStatusData, SubmissionRulesData,
+    SweepData,
+};
 //#Description Stroage key enum for NEAR Protocol persistent storage
 #[derive(BorshStorageKey, BorshSerialize)]
 pub enum StorageKeys {
     Requests,
     AuthorizedNodes,
     Admins,
+    ReportingRounds,
+    Events,
+    EventDays,
+    RequestCells,
+    ProviderKeys,
+    PendingOutcomes,
+    Nodes,
+    PolicyManagers,
 }
 
+///nanoseconds in a day, the bucket size of the event registry's date index
+const DAY: u64 = 86_400_000_000_000;
+///the longest event the registry accepts
+const MAX_EVENT_DAYS: u64 = 90;
+///the longest period `get_events_by_date` searches
+const MAX_QUERY_DAYS: u64 = 366;
+///the most requests `match_event` examines in one call
+const MAX_FAN_OUT: u64 = 20;
+///how long after a request closes its pending or disputed outcome may still be finalized or
+/// resolved. the request then expires so its fee can be refunded
+const RESOLUTION_PERIOD: u64 = 30 * DAY;
+///gas attached to the `PolicyManager`'s `event_callback`. it has to cover the `compute_loss`
+/// call to the `ClaimsManager` and the `compute_loss_callback` that `event_callback` chains
+const EVENT_CALLBACK_GAS: u64 = 80_000_000_000_000;
+///gas attached to the `on_event_callback` callback
+const ON_EVENT_CALLBACK_GAS: u64 = 20_000_000_000_000;
+
 ///#Description
 ///
 /// this is a weather even as defined by an `Oracle`
-#[derive(BorshDeserialize, BorshSerialize, Debug, PartialEq)]
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
 pub struct Event {
     ///an events unique identification.
     id: String,
+    ///the kind of catastrophe
+    peril: Peril,
     ///the Oracle contract that notified the trigger contract of this event
     oracle: AccountId,
     ///date of occurence
     date: u64,
+    ///when the event began, for the `PolicyManager`'s `SubmissionRules`
+    start: u64,
+    ///when the event ended, or was last observed
+    end: u64,
+}
+
+///#Description
+///
+/// the aggregated oracle data delivered to a `PolicyManager`. mirrors `OracleMetadata` in `PolicyManager`
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct OracleMetadata {
+    ///aggregated numeric readings keyed by trigger name
+    triggering_values: HashMap<String, u32>,
+    ///the `ClaimsManager` of the policy
+    claims_manager: AccountId,
+    ///this oracle
+    oracle: AccountId,
+}
+
+///#Description
+///
+/// where a `Request` is in its lifecycle
+///
+/// #Variants
+///
+/// *`Open` waiting for node reports
+/// *`Reported` at least one node reported, quorum not reached yet
+/// *`Pending` the nodes agreed, and the outcome waits out its dispute window
+/// *`Disputed` the pending outcome was challenged and waits for resolution
+/// *`Fulfilled` the `PolicyManager` accepted the `event_callback`
+/// *`Failed` the `event_callback` failed in the `PolicyManager`
+/// *`Expired` the request's reporting window closed before it was fulfilled, or its outcome
+/// was still pending or disputed `RESOLUTION_PERIOD` after that
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub enum RequestStatus {
+    Open,
+    Reported,
+    Pending,
+    Disputed,
+    Fulfilled,
+    Failed,
+    Expired,
 }
 
 ///#Description
 ///
 /// a request for data from a `PolicyManager`
-#[derive(BorshDeserialize, BorshSerialize, Debug, PartialEq)]
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
 pub struct Request {
     ///the unique policy id
     policy_id: String,
     ///the `PolicyManager`
     policy_manager: AccountId,
-    ///data to be checked
-    triggers: (u8, Vec<i32>),
+    ///the `ClaimsManager` of the policy, passed back in `OracleMetadata`
+    claims_manager: AccountId,
+    ///selects the peril module that checks `trigger`
+    peril: Peril,
+    ///the conditions to check
+    trigger: Trigger,
+    status: RequestStatus,
+    ///block timestamp of the request
+    created_at: u64,
+    ///the start of the policy's coverage
+    starts_at: u64,
+    ///the end of the policy's coverage
+    expires_at: u64,
+    ///when the `SubmissionRules` in force at the request's creation stop accepting events for
+    ///it, after which the request expires
+    closes_at: u64,
+    ///the yoctoNEAR the `PolicyManager` attached for the nodes, until it is paid out
+    fee: U128,
+}
+
+impl Request {
+    ///the status, taking expiry into account
+    fn current_status(&self) -> RequestStatus {
+        match self.status {
+            RequestStatus::Open | RequestStatus::Reported | RequestStatus::Failed
+                if env::block_timestamp() > self.closes_at =>
+            {
+                RequestStatus::Expired
+            }
+            RequestStatus::Pending | RequestStatus::Disputed
+                if env::block_timestamp() > self.closes_at.saturating_add(RESOLUTION_PERIOD) =>
+            {
+                RequestStatus::Expired
+            }
+            _ => self.status.clone(),
+        }
+    }
+
+    fn with_current_status(mut self) -> Self {
+        self.status = self.current_status();
+        self
+    }
+
+    fn coverage(&self) -> [u64; 2] {
+        [self.starts_at, self.expires_at]
+    }
+
+    ///can the request be pruned by `sweep_requests`
+    fn is_prunable(&self) -> bool {
+        matches!(
+            self.current_status(),
+            RequestStatus::Fulfilled | RequestStatus::Expired
+        )
+    }
 }
 
 ///#Definition
 ///
 /// PolicyManagerError
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 pub enum HurricaneOracleError {
-    TriggerDataError,
+    InvalidCategory,
+    InvalidMagnitude,
+    InvalidDroughtIndex,
+    InvalidThreshold,
+    InvalidCoordinate,
+    InvalidPolygon,
+    InvalidRadius,
+    InvalidTimeWindow,
     RequestNotFound,
+    DuplicateReport,
+    RequestOwnedByAnotherManager,
+    PolicyManagerNotAuthorized,
+    RequestAlreadyFulfilled,
+    RequestFailed,
+    RequestExpired,
+    InvalidEvent,
+    EventNotFound,
+    EventPerilMismatch,
+    DuplicateEvent(String),
+    AreaTooLarge,
+    InvalidRegion,
+    RegionTooLarge,
+    EventNotConfirmed,
+    ProviderKeyNotFound,
+    ProviderKeyExists,
+    ProviderKeyRevoked,
+    InvalidProviderKey,
+    InvalidSignature,
+    OutcomePending,
+    CallbackPending,
+    NoPendingOutcome,
+    DisputeWindowClosed,
+    DisputeWindowOpen,
+    OutcomeDisputed,
+    NotDisputed,
+    InsufficientBond,
+    InsufficientFee,
+    InsufficientStake,
+    NodeNotFound,
+    InvalidAmount,
+    UnbondingLocked,
+    NothingToWithdraw,
+    DeadlineNotMissed,
+    EventBeforeCoverage,
+    EventAfterCoverage,
+    ObservationOutsideCoverage,
+    SubmissionTooLate,
 }
 
 impl Error for HurricaneOracleError {}
 
 impl fmt::Display for HurricaneOracleError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        match *self {
-            HurricaneOracleError::TriggerDataError => {
-                write!(f, "trigger data improperly formatted.")
+        match self {
+            HurricaneOracleError::InvalidCategory => {
+                write!(f, "the minimum category must be between 1 and 5")
+            }
+            HurricaneOracleError::InvalidMagnitude => {
+                write!(f, "the minimum magnitude must be between 0.1 and 10.0")
+            }
+            HurricaneOracleError::InvalidDroughtIndex => {
+                write!(f, "the minimum drought index must be between 0 and 4")
+            }
+            HurricaneOracleError::InvalidThreshold => {
+                write!(f, "trigger thresholds must be positive")
+            }
+            HurricaneOracleError::InvalidCoordinate => {
+                write!(f, "coordinates must be within ±90° latitude and ±180° longitude")
+            }
+            HurricaneOracleError::InvalidPolygon => {
+                write!(f, "a protected polygon needs between 3 and 64 vertices")
+            }
+            HurricaneOracleError::InvalidRadius => {
+                write!(f, "the radius must be at most 1000 km")
+            }
+            HurricaneOracleError::InvalidTimeWindow => {
+                write!(f, "the time window must end after it starts")
             }
             HurricaneOracleError::RequestNotFound => {
                 write!(f, "the request was not found")
             }
+            HurricaneOracleError::DuplicateReport => {
+                write!(f, "this node already reported on the request")
+            }
+            HurricaneOracleError::RequestOwnedByAnotherManager => {
+                write!(f, "another policy manager made the request for this policy")
+            }
+            HurricaneOracleError::PolicyManagerNotAuthorized => {
+                write!(f, "only authorized policy managers can request events")
+            }
+            HurricaneOracleError::RequestAlreadyFulfilled => {
+                write!(f, "the request has already been fulfilled")
+            }
+            HurricaneOracleError::RequestFailed => {
+                write!(f, "the policy manager rejected the request's event")
+            }
+            HurricaneOracleError::RequestExpired => {
+                write!(f, "the policy's coverage has ended")
+            }
+            HurricaneOracleError::InvalidEvent => {
+                write!(f, "an event needs an id and must end after it starts, within 90 days")
+            }
+            HurricaneOracleError::EventNotFound => {
+                write!(f, "the event is not in the registry")
+            }
+            HurricaneOracleError::EventPerilMismatch => {
+                write!(f, "the event is of a different peril")
+            }
+            HurricaneOracleError::DuplicateEvent(existing_id) => {
+                write!(f, "the event was already reported as {}", existing_id)
+            }
+            HurricaneOracleError::AreaTooLarge => {
+                write!(f, "the protected area spans too many index cells")
+            }
+            HurricaneOracleError::InvalidRegion => {
+                write!(f, "the south west corner must be south west of the north east one")
+            }
+            HurricaneOracleError::RegionTooLarge => {
+                write!(f, "the region spans too many index cells")
+            }
+            HurricaneOracleError::EventNotConfirmed => {
+                write!(f, "fewer than quorum nodes have reported the event")
+            }
+            HurricaneOracleError::ProviderKeyNotFound => {
+                write!(f, "no data provider key is registered under this id")
+            }
+            HurricaneOracleError::ProviderKeyExists => {
+                write!(f, "a data provider key is already registered under this id")
+            }
+            HurricaneOracleError::ProviderKeyRevoked => {
+                write!(f, "the data provider key has been revoked")
+            }
+            HurricaneOracleError::InvalidProviderKey => {
+                write!(f, "data provider keys must be 32 byte ed25519 public keys")
+            }
+            HurricaneOracleError::InvalidSignature => {
+                write!(f, "the data provider signature does not match the report")
+            }
+            HurricaneOracleError::OutcomePending => {
+                write!(f, "the request's outcome is waiting out its dispute window")
+            }
+            HurricaneOracleError::CallbackPending => {
+                write!(f, "the request's outcome is being sent to its policy manager")
+            }
+            HurricaneOracleError::NoPendingOutcome => {
+                write!(f, "the request has no outcome waiting out a dispute window")
+            }
+            HurricaneOracleError::DisputeWindowClosed => {
+                write!(f, "the outcome can no longer be disputed")
+            }
+            HurricaneOracleError::DisputeWindowOpen => {
+                write!(f, "the outcome can still be disputed")
+            }
+            HurricaneOracleError::OutcomeDisputed => {
+                write!(f, "the outcome is disputed and must be resolved first")
+            }
+            HurricaneOracleError::NotDisputed => {
+                write!(f, "the outcome is not disputed")
+            }
+            HurricaneOracleError::InsufficientBond => {
+                write!(f, "the attached deposit is less than the dispute bond")
+            }
+            HurricaneOracleError::InsufficientFee => {
+                write!(f, "the attached deposit is less than the fulfillment fee")
+            }
+            HurricaneOracleError::InsufficientStake => {
+                write!(f, "the node has bonded less than the minimum bond")
+            }
+            HurricaneOracleError::NodeNotFound => {
+                write!(f, "the node is not in the registry")
+            }
+            HurricaneOracleError::InvalidAmount => {
+                write!(f, "the amount must be positive and at most the bond")
+            }
+            HurricaneOracleError::UnbondingLocked => {
+                write!(f, "the unbonding period has not ended")
+            }
+            HurricaneOracleError::NothingToWithdraw => {
+                write!(f, "there is nothing to withdraw")
+            }
+            HurricaneOracleError::DeadlineNotMissed => {
+                write!(f, "the round was decided or its report deadline has not passed")
+            }
+            HurricaneOracleError::EventBeforeCoverage => {
+                write!(f, "the event began before the policy's coverage")
+            }
+            HurricaneOracleError::EventAfterCoverage => {
+                write!(f, "the event began after the policy's coverage ended")
+            }
+            HurricaneOracleError::ObservationOutsideCoverage => {
+                write!(f, "the event triggered outside the coverage period and its grace window")
+            }
+            HurricaneOracleError::SubmissionTooLate => {
+                write!(f, "the reporting deadline for the event has passed")
+            }
         }
     }
 }
 
 ///#Description
 ///
-/// this is aa sense an abstract contract that will be define per Event type
-/// (e.g hurrricane, tornado, drought, etc)
-/// and/or valid a `PolicyManager` state changes
+/// the oracle core shared by every `Peril`. it authorizes nodes, stores requests, collects
+/// reports and calls back the `PolicyManager`. each peril module only supplies its trigger
+/// schema and matching logic through `PerilTrigger`. the name is kept from when hurricanes
+/// were the only peril
 #[near_bindgen]
 #[derive(BorshDeserialize, BorshSerialize, PanicOnDefault)]
 pub struct HurricaneOracle {
@@ -82,8 +430,57 @@ pub struct HurricaneOracle {
     admins: Vector<AccountId>,
     /// master admin
     master_admin: AccountId,
+    ///how many authorized nodes must report the same observation before a request is fulfilled
+    quorum: u64,
+    ///the node reports of every request, indexed by policy id
+    reporting_rounds: UnorderedMap<String, ReportingRound>,
+    ///how node measurements are combined into triggering values
+    aggregation: AggregationConfig,
+    ///the event registry, indexed by event id
+    events: UnorderedMap<String, EventRecord>,
+    ///the ids of the events of a peril active on a day, indexed by (peril, day since epoch)
+    event_days: LookupMap<(Peril, u64), Vec<String>>,
+    ///the policy ids of the requests whose area overlaps a cell, indexed by geohash prefix
+    request_cells: LookupMap<String, Vec<String>>,
+    ///the data provider keys reports are signed with, indexed by key id
+    provider_keys: UnorderedMap<String, ProviderKey>,
+    ///optimistic mode settings. `None` calls the `PolicyManager` back as soon as nodes agree
+    optimistic: Option<OptimisticConfig>,
+    ///resolves disputes. `None` leaves them to the admins
+    arbiter: Option<AccountId>,
+    ///the outcomes waiting out their dispute window, indexed by policy id
+    pending_outcomes: UnorderedMap<String, PendingOutcome>,
+    ///the node registry's economics
+    node_config: NodeConfig,
+    ///the stake and track record of every node that bonded, indexed by account
+    nodes: UnorderedMap<AccountId, NodeAccount>,
+    ///how late events may be reported
+    submission_rules: SubmissionRules,
+    ///the `PolicyManager` accounts allowed to request events
+    policy_managers: Vector<AccountId>,
 }
 
+///#Description
+///
+/// the result of one `match_event` batch
+///
+/// #Fields
+///
+/// *`policy_ids` the requests the event triggered, each sent an `event_callback` or, in
+/// optimistic mode, waiting out its dispute window
+/// *`awaiting_reports` the requests of other perils than hurricanes the event's severity may
+/// trigger. nodes confirm them with signed reports through `fulfill_request`
+/// *`last_policy_id` the last candidate examined, the `after` of the next batch. `None` when
+/// every candidate was examined
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct FanOut {
+    pub policy_ids: Vec<String>,
+    pub awaiting_reports: Vec<String>,
+    pub last_policy_id: Option<String>,
+}
+
+#[near_bindgen]
 impl HurricaneOracle {
     #[init]
     pub fn new() -> Self {
@@ -93,6 +490,20 @@ impl HurricaneOracle {
             admins: Vector::new(StorageKeys::Admins),
             authorized_accounts: Vector::new(StorageKeys::AuthorizedNodes),
             requests: UnorderedMap::new(StorageKeys::Requests),
+            quorum: 1,
+            reporting_rounds: UnorderedMap::new(StorageKeys::ReportingRounds),
+            aggregation: AggregationConfig::default(),
+            events: UnorderedMap::new(StorageKeys::Events),
+            event_days: LookupMap::new(StorageKeys::EventDays),
+            request_cells: LookupMap::new(StorageKeys::RequestCells),
+            provider_keys: UnorderedMap::new(StorageKeys::ProviderKeys),
+            optimistic: None,
+            arbiter: None,
+            pending_outcomes: UnorderedMap::new(StorageKeys::PendingOutcomes),
+            node_config: NodeConfig::default(),
+            nodes: UnorderedMap::new(StorageKeys::Nodes),
+            submission_rules: SubmissionRules::default(),
+            policy_managers: Vector::new(StorageKeys::PolicyManagers),
         }
     }
 
@@ -103,41 +514,106 @@ impl HurricaneOracle {
     /// #Parameters
     ///
     /// *`policy_id` the policy unique identifier
-    /// *`triggers` the triggers of the respective policy
+    /// *`claims_manager` the `ClaimsManager` of the policy
+    /// *`coverage_start` the start of the policy's coverage in nanoseconds
+    /// *`coverage_end` the end of the policy's coverage in nanoseconds. the request expires once
+    /// the `SubmissionRules` grace window and reporting deadline after it have passed
+    /// *`trigger` the `Trigger` of the respective policy. its variant selects the `Peril`
+    ///
+    /// the attached deposit, at least the `fulfillment_fee`, pays the nodes that fulfill the
+    /// request. it is refunded when the request expires unfulfilled
+    ///
+    /// only authorized `PolicyManager`s can make requests, so no one else can take a policy id
+    /// before its `PolicyManager` does. only the `PolicyManager` of an existing request for the
+    /// policy can replace it, and not while its outcome is in flight. the old request's unpaid
+    /// fee is refunded first
+    #[payable]
     pub fn check_for_events(
         &mut self,
         policy_id: String,
-        triggers: HashMap<String, Vec<i32>>,
+        claims_manager: AccountId,
+        coverage_start: u64,
+        coverage_end: u64,
+        trigger: Trigger,
+    ) {
+        or_panic(self.try_check_for_events(
+            policy_id,
+            claims_manager,
+            coverage_start,
+            coverage_end,
+            trigger,
+        ))
+    }
+
+    fn try_check_for_events(
+        &mut self,
+        policy_id: String,
+        claims_manager: AccountId,
+        coverage_start: u64,
+        coverage_end: u64,
+        trigger: Trigger,
     ) -> Result<(), HurricaneOracleError> {
-        let category_option: Option<u8> = {
-            if let Some(category_vec) = triggers.get("category") {
-                assert!(category_vec.len() > 0);
-                let category: u8 = category_vec[0] as u8;
-                Some(category)
-            } else {
-                None
+        if !self
+            .policy_managers
+            .iter()
+            .any(|account| account == env::predecessor_account_id())
+        {
+            return Err(HurricaneOracleError::PolicyManagerNotAuthorized);
+        }
+        if coverage_start >= coverage_end {
+            return Err(HurricaneOracleError::InvalidTimeWindow);
+        }
+        trigger.validate()?;
+        let cells = area_cells(trigger.area())?;
+        let fee = env::attached_deposit();
+        if fee < self.node_config.fulfillment_fee.0 {
+            return Err(HurricaneOracleError::InsufficientFee);
+        }
+        if let Some(existing) = self.requests.get(&policy_id) {
+            if existing.policy_manager != env::predecessor_account_id() {
+                return Err(HurricaneOracleError::RequestOwnedByAnotherManager);
             }
-        };
-        let location_option: Option<Vec<i32>> = {
-            if let Some(location_vec) = triggers.get("location") {
-                assert!(location_vec.len() > 0);
-                Some(location_vec.to_vec())
-            } else {
-                None
+            let has_outcome = self
+                .reporting_rounds
+                .get(&policy_id)
+                .is_some_and(|round| round.outcome.is_some());
+            match existing.current_status() {
+                RequestStatus::Reported if has_outcome => {
+                    return Err(HurricaneOracleError::CallbackPending)
+                }
+                RequestStatus::Pending => return Err(HurricaneOracleError::OutcomePending),
+                RequestStatus::Disputed => return Err(HurricaneOracleError::OutcomeDisputed),
+                _ => self.discard_request(existing),
             }
+        }
+        let request: Request = Request {
+            policy_id: policy_id.clone(),
+            policy_manager: env::predecessor_account_id(),
+            claims_manager,
+            peril: trigger.peril(),
+            trigger,
+            status: RequestStatus::Open,
+            created_at: env::block_timestamp(),
+            starts_at: coverage_start,
+            expires_at: coverage_end,
+            closes_at: self.submission_rules.closes_at(coverage_end),
+            fee: U128(fee),
         };
-
-        if (category_option.clone(), location_option.clone()) != (None, None) {
-            let request: Request = Request {
-                policy_id: policy_id.clone(),
-                policy_manager: env::predecessor_account_id(),
-                triggers: (category_option.unwrap(), location_option.unwrap()),
-            };
-            self.requests.insert(&policy_id, &request);
-            Ok(())
-        } else {
-            Err(HurricaneOracleError::TriggerDataError)
+        self.requests.insert(&policy_id, &request);
+        for cell in cells.iter() {
+            let mut policy_ids = self.request_cells.get(cell).unwrap_or_default();
+            if !policy_ids.contains(&policy_id) {
+                policy_ids.push(policy_id.clone());
+                self.request_cells.insert(cell, &policy_ids);
+            }
         }
+        HurricaneOracleEvent::RequestCreated([RequestData {
+            policy_id: request.policy_id,
+            policy_manager: request.policy_manager,
+            peril: request.peril,
+        }])
+        .emit();
+        Ok(())
     }
 
     ///#Description
@@ -154,7 +630,7 @@ impl HurricaneOracle {
                 .contains(&env::predecessor_account_id()),
             "not authorized."
         );
-        self.requests.get(&policy_id)
+        self.requests.get(&policy_id).map(Request::with_current_status)
     }
 
     pub fn get_all_requests(&self) -> Vec<Request> {
@@ -164,95 +640,1911 @@ impl HurricaneOracle {
                 .contains(&env::predecessor_account_id()),
             "not authorized."
         );
-        self.requests.values().collect()
+        self.requests
+            .values()
+            .map(Request::with_current_status)
+            .collect()
     }
     ///#Description
     ///
-    /// authorized node calls this function to return data
+    /// authorized node calls this function to report what it observed. the node must have
+    /// bonded at least the `min_bond`. the request is fulfilled once `quorum` bonded
+    /// authorized nodes have reported the same event id and date.
+    /// the measurements of those nodes are then aggregated and delivered to the
+    /// `PolicyManager` as the `triggering_values` of `OracleMetadata`
     ///
     /// #Parameters
     ///
     /// *`policy_id` unique policy id
     /// *`event`
-    ///   *`event_id` a `String` of the event's unique id
+    ///   *`event_id` a `String` of the event's unique id. the event must be in the registry
     ///   *`date` the date of the event in nanoseconds
+    /// *`measurements` numeric readings keyed by trigger name (e.g. `category`, `distance`)
+    /// *`attestation` a registered data provider's signature over the `ProviderPayload` of
+    /// the event and measurements
+    ///
+    /// #Returns
+    ///
+    /// the `event_callback` promise when this report completed the quorum, unless the
+    /// outcome must first wait out a dispute window in optimistic mode
     pub fn fulfill_request(
         &mut self,
         policy_id: String,
         event_data: (String, u64),
-    ) -> Result<Promise, HurricaneOracleError> {
-        assert!(self
-            .authorized_accounts
-            .to_vec()
-            .contains(&env::predecessor_account_id()));
-        let oracle_account = env::predecessor_account_id();
-        if let Some(request) = self.requests.get(&policy_id) {
-            let promise = policy_manager::event_callback(
-                (event_data.0, oracle_account, policy_id, event_data.1),
-                &request.policy_manager,
-                0,
-                5_000_000_000_000,
-            );
-            Ok(promise)
-        } else {
-            Err(HurricaneOracleError::RequestNotFound)
+        measurements: HashMap<String, u32>,
+        attestation: Attestation,
+    ) -> Option<Promise> {
+        or_panic(self.try_fulfill_request(policy_id, event_data, measurements, attestation))
+    }
+
+    fn try_fulfill_request(
+        &mut self,
+        policy_id: String,
+        event_data: (String, u64),
+        measurements: HashMap<String, u32>,
+        attestation: Attestation,
+    ) -> Result<Option<Promise>, HurricaneOracleError> {
+        let node = env::predecessor_account_id();
+        assert!(self.authorized_accounts.to_vec().contains(&node));
+        if !self.is_bonded(&node) {
+            return Err(HurricaneOracleError::InsufficientStake);
+        }
+        let active_nodes = self.active_nodes();
+        let mut request = self
+            .requests
+            .get(&policy_id)
+            .ok_or(HurricaneOracleError::RequestNotFound)?;
+        match request.current_status() {
+            RequestStatus::Open | RequestStatus::Reported => (),
+            RequestStatus::Pending | RequestStatus::Disputed => {
+                return Err(HurricaneOracleError::OutcomePending)
+            }
+            RequestStatus::Fulfilled => return Err(HurricaneOracleError::RequestAlreadyFulfilled),
+            RequestStatus::Failed => return Err(HurricaneOracleError::RequestFailed),
+            RequestStatus::Expired => return Err(HurricaneOracleError::RequestExpired),
+        }
+        let mut round = self.reporting_rounds.get(&policy_id).unwrap_or_default();
+        if round.outcome.is_some() {
+            return Err(HurricaneOracleError::RequestAlreadyFulfilled);
+        }
+        if round.has_reported(&node) {
+            return Err(HurricaneOracleError::DuplicateReport);
+        }
+        let observation = Observation {
+            event_id: event_data.0,
+            date: event_data.1,
+        };
+        let event = self
+            .events
+            .get(&observation.event_id)
+            .ok_or(HurricaneOracleError::EventNotFound)?;
+        if event.peril != request.peril {
+            return Err(HurricaneOracleError::EventPerilMismatch);
+        }
+        self.submission_rules.check(
+            request.coverage(),
+            [event.start, event.end],
+            observation.date,
+            env::block_timestamp(),
+        )?;
+        let provider_key = self
+            .provider_keys
+            .get(&attestation.key_id)
+            .ok_or(HurricaneOracleError::ProviderKeyNotFound)?;
+        if provider_key.is_revoked() {
+            return Err(HurricaneOracleError::ProviderKeyRevoked);
+        }
+        attestation::verify(
+            &provider_key.public_key.0,
+            &ProviderPayload::new(&observation, &measurements),
+            &attestation.signature.0,
+        )?;
+        let key_id = attestation.key_id.clone();
+        round.reports.push(NodeReport {
+            node: node.clone(),
+            observation: observation.clone(),
+            measurements: measurements.clone(),
+            submitted_at: env::block_timestamp(),
+            attestation,
+        });
+        HurricaneOracleEvent::ReportSubmitted([ReportData {
+            policy_id: policy_id.clone(),
+            node,
+            event_id: observation.event_id,
+            date: observation.date,
+            measurements,
+            key_id,
+        }])
+        .emit();
+        round.outcome = round.agreed_observation(self.quorum, &active_nodes);
+        if let Some(outcome) = &round.outcome {
+            round.triggering_values = self
+                .aggregation
+                .aggregate_measurements(&round.agreeing_measurements(outcome), self.quorum);
+            // the part of a registered storm track a quorum of nodes agreed on takes precedence
+            // over the distances the nodes computed
+            if let Trigger::Hurricane(trigger) = &request.trigger {
+                let track = self
+                    .events
+                    .get(&outcome.event_id)
+                    .map(|event| event.confirmed_track(self.quorum, &active_nodes))
+                    .unwrap_or_default();
+                if let Some((track_values, _)) = trigger.evaluate_track(&track) {
+                    round.triggering_values.extend(track_values);
+                }
+            }
+        }
+        let dismissed = match &round.outcome {
+            Some(outcome) => !request
+                .trigger
+                .is_triggered(outcome.date, &round.triggering_values),
+            None => false,
+        };
+        if dismissed {
+            // the nodes agree on an event that does not trigger the policy. start over so they
+            // can report the next one
+            let outcome = round.outcome.unwrap();
+            self.reporting_rounds.remove(&policy_id);
+            self.set_request_status(&mut request, RequestStatus::Open);
+            HurricaneOracleEvent::ObservationDismissed([ObservationDismissedData {
+                policy_id,
+                event_id: outcome.event_id,
+                triggering_values: round.triggering_values,
+            }])
+            .emit();
+            return Ok(None);
+        }
+        self.reporting_rounds.insert(&policy_id, &round);
+        if request.status == RequestStatus::Open {
+            self.set_request_status(&mut request, RequestStatus::Reported);
+        }
+        match round.outcome.clone() {
+            Some(outcome) => {
+                let nodes: Vec<AccountId> = round
+                    .agreeing_nodes(&outcome)
+                    .into_iter()
+                    .filter(|node| active_nodes.contains(node))
+                    .collect();
+                Ok(self.propose_outcome(
+                    request,
+                    outcome,
+                    round.triggering_values,
+                    round.reports.len() as u64,
+                    nodes,
+                ))
+            }
+            None => Ok(None),
         }
     }
-    //administrative functions
 
-    pub fn add_authorized_account(&mut self, auth_account: AccountId) {
-        assert!(self
-            .admins
-            .to_vec()
-            .contains(&env::predecessor_account_id()));
-        self.authorized_accounts.push(&auth_account);
+    ///#Description
+    ///
+    /// records whether the `PolicyManager` accepted the `event_callback` of a fulfilled request,
+    /// and only then pays its fee to the nodes that backed the outcome
+    ///
+    /// #Parameters
+    ///
+    /// *`policy_id` the policy of the request
+    /// *`nodes` the nodes to pay, empty when they were paid once the dispute window ended
+    ///
+    /// #Returns
+    ///
+    /// whether the callback succeeded
+    pub fn on_event_callback(&mut self, policy_id: String, nodes: Vec<AccountId>) -> bool {
+        assert_eq!(
+            env::predecessor_account_id(),
+            env::current_account_id(),
+            "callback is private"
+        );
+        assert_eq!(env::promise_results_count(), 1, "ERR_TOO_MANY_RESULTS");
+        let succeeded = match env::promise_result(0) {
+            PromiseResult::NotReady => unreachable!(),
+            PromiseResult::Successful(_) => true,
+            PromiseResult::Failed => false,
+        };
+        if let Some(mut request) = self.requests.get(&policy_id) {
+            let status = if succeeded {
+                if !nodes.is_empty() {
+                    self.pay_nodes(&mut request, &nodes);
+                }
+                RequestStatus::Fulfilled
+            } else {
+                RequestStatus::Failed
+            };
+            self.set_request_status(&mut request, status);
+        }
+        succeeded
     }
 
-    pub fn remove_authorized_account(&mut self, auth_account: AccountId) {
-        assert!(self
-            .admins
-            .to_vec()
-            .contains(&env::predecessor_account_id()));
-        let index = self
-            .authorized_accounts
-            .iter()
-            .position(|account| account == auth_account)
-            .unwrap() as u64;
+    ///#Description
+    ///
+    /// the outcome of a request waiting out its dispute window in optimistic mode
+    pub fn get_pending_outcome(&self, policy_id: String) -> Option<PendingOutcome> {
+        self.pending_outcomes.get(&policy_id)
+    }
 
-        self.authorized_accounts.swap_remove(index);
+    ///#Description
+    ///
+    /// challenge a pending outcome before its dispute window ends. anyone but an admin must
+    /// attach the outcome's `dispute_bond`. the outcome then waits for `resolve_dispute`
+    ///
+    /// #Parameters
+    ///
+    /// *`policy_id` the policy of the request
+    /// *`reason` why the outcome is wrong, for the resolver
+    #[payable]
+    pub fn dispute_request(
+        &mut self,
+        policy_id: String,
+        reason: String,
+    ) {
+        or_panic(self.try_dispute_request(policy_id, reason))
     }
 
-    pub fn add_admin(&mut self, admin: AccountId) {
-        assert!(self.master_admin == env::predecessor_account_id());
-        self.admins.push(&admin);
+    fn try_dispute_request(
+        &mut self,
+        policy_id: String,
+        reason: String,
+    ) -> Result<(), HurricaneOracleError> {
+        let disputer = env::predecessor_account_id();
+        let bond = env::attached_deposit();
+        let mut pending = self
+            .pending_outcomes
+            .get(&policy_id)
+            .ok_or(HurricaneOracleError::NoPendingOutcome)?;
+        if !pending.is_disputable(env::block_timestamp()) {
+            return Err(HurricaneOracleError::DisputeWindowClosed);
+        }
+        if bond < pending.dispute_bond.0 && !self.admins.to_vec().contains(&disputer) {
+            return Err(HurricaneOracleError::InsufficientBond);
+        }
+        let mut request = self
+            .requests
+            .get(&policy_id)
+            .ok_or(HurricaneOracleError::RequestNotFound)?;
+        pending.dispute = Some(Dispute {
+            disputer: disputer.clone(),
+            bond: U128(bond),
+            reason: reason.clone(),
+            disputed_at: env::block_timestamp(),
+        });
+        self.pending_outcomes.insert(&policy_id, &pending);
+        self.set_request_status(&mut request, RequestStatus::Disputed);
+        HurricaneOracleEvent::OutcomeDisputed([OutcomeDisputedData {
+            policy_id,
+            disputer,
+            bond: U128(bond),
+            reason,
+        }])
+        .emit();
+        Ok(())
     }
 
-    pub fn remove_admin(&mut self, old_admin: AccountId) {
-        assert!(self.master_admin == env::predecessor_account_id());
-        let index = self
-            .admins
+    ///#Description
+    ///
+    /// call the `PolicyManager` back with an undisputed outcome once its dispute window has
+    /// ended. anyone can call it
+    pub fn finalize_request(&mut self, policy_id: String) -> Promise {
+        or_panic(self.try_finalize_request(policy_id))
+    }
+
+    fn try_finalize_request(&mut self, policy_id: String) -> Result<Promise, HurricaneOracleError> {
+        let pending = self
+            .pending_outcomes
+            .get(&policy_id)
+            .ok_or(HurricaneOracleError::NoPendingOutcome)?;
+        self.check_not_expired(&policy_id)?;
+        if pending.dispute.is_some() {
+            return Err(HurricaneOracleError::OutcomeDisputed);
+        }
+        if !pending.is_final(env::block_timestamp()) {
+            return Err(HurricaneOracleError::DisputeWindowOpen);
+        }
+        self.release_outcome(policy_id, pending)
+    }
+
+    ///#Description
+    ///
+    /// settle a disputed outcome. only the arbiter can, or the admins when there is none.
+    /// an upheld outcome slashes the disputer's bond to the master admin and calls the
+    /// `PolicyManager` back. an overturned one refunds the bond, slashes the nodes that backed
+    /// the outcome to the disputer and reopens the request so nodes can report again
+    ///
+    /// #Parameters
+    ///
+    /// *`policy_id` the policy of the request
+    /// *`outcome_upheld` whether the nodes' outcome stands
+    ///
+    /// #Returns
+    ///
+    /// the `event_callback` promise when the outcome was upheld
+    pub fn resolve_dispute(
+        &mut self,
+        policy_id: String,
+        outcome_upheld: bool,
+    ) -> Option<Promise> {
+        or_panic(self.try_resolve_dispute(policy_id, outcome_upheld))
+    }
+
+    fn try_resolve_dispute(
+        &mut self,
+        policy_id: String,
+        outcome_upheld: bool,
+    ) -> Result<Option<Promise>, HurricaneOracleError> {
+        let resolver = env::predecessor_account_id();
+        match &self.arbiter {
+            Some(arbiter) => assert!(*arbiter == resolver, "not the arbiter."),
+            None => assert!(self.admins.to_vec().contains(&resolver), "not authorized."),
+        }
+        let pending = self
+            .pending_outcomes
+            .get(&policy_id)
+            .ok_or(HurricaneOracleError::NoPendingOutcome)?;
+        self.check_not_expired(&policy_id)?;
+        let dispute = pending
+            .dispute
+            .clone()
+            .ok_or(HurricaneOracleError::NotDisputed)?;
+        HurricaneOracleEvent::DisputeResolved([DisputeResolvedData {
+            policy_id: policy_id.clone(),
+            resolver,
+            outcome_upheld,
+        }])
+        .emit();
+        if outcome_upheld {
+            if dispute.bond.0 > 0 {
+                Promise::new(self.master_admin.clone()).transfer(dispute.bond.0);
+            }
+            return self.release_outcome(policy_id, pending).map(Some);
+        }
+        let mut reward = dispute.bond.0;
+        for node in pending.nodes.iter() {
+            reward += self.slash_node(node, &policy_id, SlashReason::OverturnedReport);
+        }
+        if reward > 0 {
+            Promise::new(dispute.disputer).transfer(reward);
+        }
+        let mut request = self
+            .requests
+            .get(&policy_id)
+            .ok_or(HurricaneOracleError::RequestNotFound)?;
+        self.pending_outcomes.remove(&policy_id);
+        self.reporting_rounds.remove(&policy_id);
+        self.set_request_status(&mut request, RequestStatus::Open);
+        Ok(None)
+    }
+
+    ///#Description
+    ///
+    /// remove fulfilled and expired requests with their reporting rounds and pending outcomes,
+    /// refunding the fee of those never fulfilled and the bond of an unresolved dispute.
+    /// anyone can call it. at most `limit` requests,
+    /// starting at index `from_index`, are examined so the gas used stays bounded
+    ///
+    /// #Returns
+    ///
+    /// the number of requests removed
+    pub fn sweep_requests(&mut self, from_index: u64, limit: u64) -> u64 {
+        let prunable: Vec<String> = self
+            .requests
+            .keys_as_vector()
             .iter()
-            .position(|account| account == old_admin)
-            .unwrap() as u64;
+            .skip(from_index as usize)
+            .take(limit as usize)
+            .filter(|policy_id| {
+                self.requests
+                    .get(policy_id)
+                    .is_some_and(|request| request.is_prunable())
+            })
+            .collect();
+        for policy_id in prunable.iter() {
+            if let Some(request) = self.requests.get(policy_id) {
+                self.discard_request(request);
+            }
+        }
+        if !prunable.is_empty() {
+            HurricaneOracleEvent::RequestsSwept([SweepData {
+                policy_ids: prunable.clone(),
+            }])
+            .emit();
+        }
+        prunable.len() as u64
+    }
 
-        self.admins.swap_remove(index);
+    ///#Description
+    ///
+    /// authorized node fetches the open requests whose area overlaps a region, e.g. the
+    /// bounding box of an active storm, instead of every request
+    ///
+    /// #Parameters
+    ///
+    /// *`south_west` the south west corner of the region
+    /// *`north_east` the north east corner of the region
+    /// *`from_index` how many matching requests to skip
+    /// *`limit` the most requests to return
+    pub fn get_requests_in_region(
+        &self,
+        south_west: Coordinate,
+        north_east: Coordinate,
+        from_index: u64,
+        limit: u64,
+    ) -> Vec<Request> {
+        or_panic(self.try_get_requests_in_region(south_west, north_east, from_index, limit))
     }
 
-    pub fn change_master_admin(&mut self, new_admin: AccountId) {
-        assert!(self.master_admin == env::predecessor_account_id());
-        self.master_admin = new_admin;
+    fn try_get_requests_in_region(
+        &self,
+        south_west: Coordinate,
+        north_east: Coordinate,
+        from_index: u64,
+        limit: u64,
+    ) -> Result<Vec<Request>, HurricaneOracleError> {
+        assert!(
+            self.authorized_accounts
+                .to_vec()
+                .contains(&env::predecessor_account_id()),
+            "not authorized."
+        );
+        let region = Region::new(south_west, north_east)?;
+        Ok(self
+            .open_requests_in(&region)?
+            .into_iter()
+            .skip(from_index as usize)
+            .take(limit as usize)
+            .map(Request::with_current_status)
+            .collect())
     }
-}
 
-#[ext_contract(policy_manager)]
-trait PolicyManager {
-    //(event_data.0, oracle_account, policy_id, event_data.1)
-    fn event_callback(event: (String, AccountId, String, u64)) -> Option<Event>;
-}
+    ///#Description
+    ///
+    /// match one registered event against every open request of its peril in the cells its
+    /// track passes through. hurricanes are evaluated against the track a quorum of nodes
+    /// confirmed, and an `event_callback` is sent for each request they trigger. the severity of
+    /// other perils is the peak any single node reported, so the requests it triggers are only
+    /// listed for nodes to fulfill with signed reports. the event must have been reported by
+    /// `quorum` nodes
+    ///
+    /// #Parameters
+    ///
+    /// *`event_id` the registered event
+    /// *`padding` microdegrees added around the track, to reach requests with a radius
+    /// *`after` the `last_policy_id` of the previous batch. candidates are examined in policy id
+    /// order, so the requests a batch triggers leaving the open set do not shift the next one
+    /// *`limit` the most candidates to examine, at most 20
+    pub fn match_event(
+        &mut self,
+        event_id: String,
+        padding: u32,
+        after: Option<String>,
+        limit: u64,
+    ) -> FanOut {
+        or_panic(self.try_match_event(event_id, padding, after, limit))
+    }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use near_sdk::MockedBlockchain;
-    use near_sdk::{testing_env, VMContext};
+    fn try_match_event(
+        &mut self,
+        event_id: String,
+        padding: u32,
+        after: Option<String>,
+        limit: u64,
+    ) -> Result<FanOut, HurricaneOracleError> {
+        assert!(
+            self.authorized_accounts
+                .to_vec()
+                .contains(&env::predecessor_account_id()),
+            "not authorized."
+        );
+        let event = self
+            .events
+            .get(&event_id)
+            .ok_or(HurricaneOracleError::EventNotFound)?;
+        let active_nodes = self.active_nodes();
+        let confirming_nodes: Vec<AccountId> = event
+            .reporters
+            .iter()
+            .filter(|reporter| active_nodes.contains(reporter))
+            .cloned()
+            .collect();
+        let confirmations = confirming_nodes.len() as u64;
+        if confirmations < self.quorum {
+            return Err(HurricaneOracleError::EventNotConfirmed);
+        }
+        let region = Region::around_points(event.track.iter().map(|point| &point.position))
+            .padded(padding);
+        let mut candidates: Vec<Request> = self
+            .open_requests_in(&region)?
+            .into_iter()
+            .filter(|request| after.as_ref().is_none_or(|after| request.policy_id > *after))
+            .collect();
+        candidates.sort_by(|a, b| a.policy_id.cmp(&b.policy_id));
+        let limit = limit.clamp(1, MAX_FAN_OUT) as usize;
+        let last_policy_id = if candidates.len() > limit {
+            candidates.get(limit - 1).map(|request| request.policy_id.clone())
+        } else {
+            None
+        };
+        // only the fixes a quorum of active nodes reported are evaluated against triggers
+        let confirmed_track = event.confirmed_track(self.quorum, &active_nodes);
+        let mut policy_ids: Vec<String> = Vec::new();
+        let mut awaiting_reports: Vec<String> = Vec::new();
+        for request in candidates.into_iter().take(limit) {
+            if request.peril != event.peril
+                || self
+                    .reporting_rounds
+                    .get(&request.policy_id)
+                    .is_some_and(|round| round.outcome.is_some())
+            {
+                continue;
+            }
+            let (triggering_values, date) = match &request.trigger {
+                Trigger::Hurricane(trigger) => match trigger.evaluate_track(&confirmed_track) {
+                    Some((values, approach)) => (values, approach.timestamp),
+                    None => continue,
+                },
+                _ => (event.severity.clone(), event.start),
+            };
+            if !request.trigger.is_triggered(date, &triggering_values)
+                || self
+                    .submission_rules
+                    .check(
+                        request.coverage(),
+                        [event.start, event.end],
+                        date,
+                        env::block_timestamp(),
+                    )
+                    .is_err()
+            {
+                continue;
+            }
+            if request.peril != Peril::Hurricane {
+                awaiting_reports.push(request.policy_id);
+                continue;
+            }
+            let outcome = Observation {
+                event_id: event.id.clone(),
+                date,
+            };
+            let round = ReportingRound {
+                reports: Vec::new(),
+                outcome: Some(outcome.clone()),
+                triggering_values: triggering_values.clone(),
+                deadline_enforced: false,
+            };
+            self.reporting_rounds.insert(&request.policy_id, &round);
+            let mut request = request;
+            if request.status == RequestStatus::Open {
+                self.set_request_status(&mut request, RequestStatus::Reported);
+            }
+            policy_ids.push(request.policy_id.clone());
+            self.propose_outcome(
+                request,
+                outcome,
+                triggering_values,
+                confirmations,
+                confirming_nodes.clone(),
+            );
+        }
+        Ok(FanOut {
+            policy_ids,
+            awaiting_reports,
+            last_policy_id,
+        })
+    }
+
+    ///#Description
+    ///
+    /// every node report for a request, and the agreed outcome if quorum was reached
+    ///
+    /// #Parameter
+    ///
+    /// *`policy_id` the policy of the request
+    pub fn get_reporting_round(&self, policy_id: String) -> Option<ReportingRound> {
+        self.reporting_rounds.get(&policy_id)
+    }
+
+    ///#Description
+    ///
+    /// the node reports of a request that disagree with its outcome, or every report
+    /// while the nodes disagree and quorum has not been reached
+    ///
+    /// #Parameter
+    ///
+    /// *`policy_id` the policy of the request
+    pub fn get_disagreements(&self, policy_id: String) -> Vec<NodeReport> {
+        self.reporting_rounds
+            .get(&policy_id)
+            .map(|round| round.disagreements())
+            .unwrap_or_default()
+    }
+
+    ///#Description
+    ///
+    /// re-verify the data provider signature of a node's report. keys revoked since the report
+    /// still verify, as they were valid when it was made
+    ///
+    /// #Parameters
+    ///
+    /// *`policy_id` the policy of the request
+    /// *`node` the node that reported
+    ///
+    /// #Returns
+    ///
+    /// whether the report exists and its signature is valid
+    pub fn verify_report(&self, policy_id: String, node: AccountId) -> bool {
+        let round = match self.reporting_rounds.get(&policy_id) {
+            Some(round) => round,
+            None => return false,
+        };
+        round
+            .reports
+            .iter()
+            .find(|report| report.node == node)
+            .is_some_and(|report| {
+                self.provider_keys
+                    .get(&report.attestation.key_id)
+                    .is_some_and(|provider_key| {
+                        attestation::verify(
+                            &provider_key.public_key.0,
+                            &ProviderPayload::new(&report.observation, &report.measurements),
+                            &report.attestation.signature.0,
+                        )
+                        .is_ok()
+                    })
+            })
+    }
+
+    ///the data provider key registered under `key_id`, including revoked keys
+    pub fn get_provider_key(&self, key_id: String) -> Option<ProviderKey> {
+        self.provider_keys.get(&key_id)
+    }
+
+    ///every data provider key id and key, including revoked keys
+    pub fn get_provider_keys(&self) -> Vec<(String, ProviderKey)> {
+        self.provider_keys.to_vec()
+    }
+
+    ///#Description
+    ///
+    /// authorized node adds an event to the registry, or adds its latest observations if the
+    /// event id is already registered. a new id is rejected when it duplicates a registered
+    /// event of the same peril, i.e. they overlap in time and share a name or a nearby track
+    ///
+    /// #Parameters
+    ///
+    /// *`report` the `EventRecord` as observed by the node
+    ///
+    /// #Returns
+    ///
+    /// the registered event
+    pub fn report_event(
+        &mut self,
+        report: EventRecord,
+    ) -> EventRecord {
+        or_panic(self.try_report_event(report))
+    }
+
+    fn try_report_event(
+        &mut self,
+        report: EventRecord,
+    ) -> Result<EventRecord, HurricaneOracleError> {
+        assert!(
+            self.authorized_accounts
+                .to_vec()
+                .contains(&env::predecessor_account_id()),
+            "not authorized."
+        );
+        self.register_event(report)
+    }
+
+    ///#Description
+    ///
+    /// authorized node reports the track of a hurricane. the fixes are added to the registry as
+    /// the event's track, and distance triggers are then evaluated against it on-chain
+    ///
+    /// #Parameters
+    ///
+    /// *`event_id` the storm's unique id
+    /// *`name` the storm's public name, e.g. `Ian`
+    /// *`fixes` the `StormFix`es observed so far, in any order
+    ///
+    /// #Returns
+    ///
+    /// the registered event
+    pub fn report_storm_track(
+        &mut self,
+        event_id: String,
+        name: String,
+        fixes: Vec<StormFix>,
+    ) -> EventRecord {
+        or_panic(self.try_report_storm_track(event_id, name, fixes))
+    }
+
+    fn try_report_storm_track(
+        &mut self,
+        event_id: String,
+        name: String,
+        fixes: Vec<StormFix>,
+    ) -> Result<EventRecord, HurricaneOracleError> {
+        assert!(
+            self.authorized_accounts
+                .to_vec()
+                .contains(&env::predecessor_account_id()),
+            "not authorized."
+        );
+        let mut severity: HashMap<String, u32> = HashMap::new();
+        for fix in fixes.iter() {
+            let category = severity.entry("category".to_string()).or_insert(0);
+            *category = (*category).max(fix.category as u32);
+            let wind_speed = severity.entry("wind_speed".to_string()).or_insert(0);
+            *wind_speed = (*wind_speed).max(fix.max_wind);
+        }
+        let mut track: Vec<TrackPoint> = fixes.into_iter().map(TrackPoint::from).collect();
+        track.sort_by_key(|point| point.timestamp);
+        let report = EventRecord {
+            id: event_id,
+            peril: Peril::Hurricane,
+            name,
+            start: track.first().map_or(0, |point| point.timestamp),
+            end: track.last().map_or(0, |point| point.timestamp),
+            severity,
+            track,
+            reporters: Vec::new(),
+        };
+        if report.track.is_empty() {
+            return Err(HurricaneOracleError::InvalidEvent);
+        }
+        self.register_event(report)
+    }
+
+    ///#Description
+    ///
+    /// where the confirmed track of a registered event passes closest to an area
+    ///
+    /// #Parameters
+    ///
+    /// *`event_id` the registered event
+    /// *`area` e.g. the `ProtectedArea` of a policy
+    pub fn get_closest_approach(&self, event_id: String, area: ProtectedArea) -> Option<Approach> {
+        self.events
+            .get(&event_id)
+            .and_then(|event| {
+                let track = event.confirmed_track(self.quorum, &self.active_nodes());
+                geometry::closest_approach(&track, &area)
+            })
+    }
+
+    fn register_event(
+        &mut self,
+        mut report: EventRecord,
+    ) -> Result<EventRecord, HurricaneOracleError> {
+        report.validate()?;
+        report.reporters = vec![env::predecessor_account_id()];
+        for point in report.track.iter_mut() {
+            point.reporters = vec![env::predecessor_account_id()];
+        }
+        let (event, is_new) = match self.events.get(&report.id) {
+            Some(mut event) => {
+                event.merge(report)?;
+                (event, false)
+            }
+            None => {
+                if let Some(existing) = self.duplicate_of(&report) {
+                    return Err(HurricaneOracleError::DuplicateEvent(existing));
+                }
+                (report, true)
+            }
+        };
+        if (event.end - event.start) / DAY >= MAX_EVENT_DAYS {
+            return Err(HurricaneOracleError::InvalidEvent);
+        }
+        self.events.insert(&event.id, &event);
+        self.index_event_days(&event);
+        let data = EventRegistryData {
+            event_id: event.id.clone(),
+            peril: event.peril,
+            name: event.name.clone(),
+            start: event.start,
+            end: event.end,
+        };
+        if is_new {
+            HurricaneOracleEvent::EventRegistered([data]).emit();
+        } else {
+            HurricaneOracleEvent::EventUpdated([data]).emit();
+        }
+        Ok(event)
+    }
+
+    pub fn get_event(&self, event_id: String) -> Option<EventRecord> {
+        self.events.get(&event_id)
+    }
+
+    ///#Description
+    ///
+    /// the registered events that were active at any time from `from` to `to`
+    ///
+    /// #Parameters
+    ///
+    /// *`from` start of the period in nanoseconds
+    /// *`to` end of the period in nanoseconds, at most 366 days after `from`
+    /// *`peril` only events of this `Peril`. every peril when `None`
+    pub fn get_events_by_date(&self, from: u64, to: u64, peril: Option<Peril>) -> Vec<EventRecord> {
+        assert!(from <= to, "invalid date range.");
+        assert!((to - from) / DAY < MAX_QUERY_DAYS, "date range too long.");
+        let perils = match peril {
+            Some(peril) => vec![peril],
+            None => vec![
+                Peril::Hurricane,
+                Peril::Earthquake,
+                Peril::Rainfall,
+                Peril::Drought,
+            ],
+        };
+        let mut event_ids: Vec<String> = Vec::new();
+        for peril in perils.into_iter() {
+            for day in from / DAY..=to / DAY {
+                for event_id in self.event_days.get(&(peril, day)).unwrap_or_default() {
+                    if !event_ids.contains(&event_id) {
+                        event_ids.push(event_id);
+                    }
+                }
+            }
+        }
+        event_ids
+            .iter()
+            .filter_map(|event_id| self.events.get(event_id))
+            .filter(|event| event.overlaps(from, to))
+            .collect()
+    }
+
+    ///#Description
+    ///
+    /// the registered events with an observation inside a bounding box. at most `limit`
+    /// events, starting at index `from_index` of the registry, are examined
+    ///
+    /// #Parameters
+    ///
+    /// *`south_west` the south west corner of the box
+    /// *`north_east` the north east corner of the box
+    pub fn get_events_in_region(
+        &self,
+        south_west: Coordinate,
+        north_east: Coordinate,
+        from_index: u64,
+        limit: u64,
+    ) -> Vec<EventRecord> {
+        self.events
+            .values()
+            .skip(from_index as usize)
+            .take(limit as usize)
+            .filter(|event| event.is_in_region(&south_west, &north_east))
+            .collect()
+    }
+
+    ///the open requests indexed under the cells of `region` whose area overlaps it
+    fn open_requests_in(&self, region: &Region) -> Result<Vec<Request>, HurricaneOracleError> {
+        let cells = region
+            .cells(MAX_QUERY_CELLS)
+            .ok_or(HurricaneOracleError::RegionTooLarge)?;
+        let mut policy_ids: Vec<String> = Vec::new();
+        for cell in cells.iter() {
+            for policy_id in self.request_cells.get(cell).unwrap_or_default() {
+                if !policy_ids.contains(&policy_id) {
+                    policy_ids.push(policy_id);
+                }
+            }
+        }
+        Ok(policy_ids
+            .iter()
+            .filter_map(|policy_id| self.requests.get(policy_id))
+            .filter(|request| {
+                matches!(
+                    request.current_status(),
+                    RequestStatus::Open | RequestStatus::Reported
+                ) && Region::around(request.trigger.area()).intersects(region)
+            })
+            .collect())
+    }
+
+    ///remove a request and everything kept for it, refunding its unpaid fee to its
+    /// `PolicyManager` and the bond of a dispute that was never resolved to the disputer
+    fn discard_request(&mut self, request: Request) {
+        self.requests.remove(&request.policy_id);
+        self.unindex_request(&request);
+        self.reporting_rounds.remove(&request.policy_id);
+        if let Some(dispute) = self
+            .pending_outcomes
+            .remove(&request.policy_id)
+            .and_then(|pending| pending.dispute)
+        {
+            if dispute.bond.0 > 0 {
+                Promise::new(dispute.disputer).transfer(dispute.bond.0);
+            }
+        }
+        if request.fee.0 > 0 {
+            Promise::new(request.policy_manager).transfer(request.fee.0);
+        }
+    }
+
+    ///a pending outcome can no longer be finalized or resolved once its request expired
+    fn check_not_expired(&self, policy_id: &str) -> Result<(), HurricaneOracleError> {
+        match self.requests.get(&policy_id.to_string()).map(|request| request.current_status()) {
+            Some(RequestStatus::Expired) => Err(HurricaneOracleError::RequestExpired),
+            _ => Ok(()),
+        }
+    }
+
+    fn unindex_request(&mut self, request: &Request) {
+        for cell in area_cells(request.trigger.area()).unwrap_or_default() {
+            let mut policy_ids = self.request_cells.get(&cell).unwrap_or_default();
+            policy_ids.retain(|policy_id| *policy_id != request.policy_id);
+            if policy_ids.is_empty() {
+                self.request_cells.remove(&cell);
+            } else {
+                self.request_cells.insert(&cell, &policy_ids);
+            }
+        }
+    }
+
+    ///the id of a registered event that `report` duplicates, if any
+    fn duplicate_of(&self, report: &EventRecord) -> Option<String> {
+        (report.start / DAY..=report.end / DAY)
+            .flat_map(|day| {
+                self.event_days
+                    .get(&(report.peril, day))
+                    .unwrap_or_default()
+            })
+            .filter_map(|event_id| self.events.get(&event_id))
+            .find(|event| report.is_duplicate_of(event))
+            .map(|event| event.id)
+    }
+
+    fn index_event_days(&mut self, event: &EventRecord) {
+        for day in event.start / DAY..=event.end / DAY {
+            let key = (event.peril, day);
+            let mut event_ids = self.event_days.get(&key).unwrap_or_default();
+            if !event_ids.contains(&event.id) {
+                event_ids.push(event.id.clone());
+                self.event_days.insert(&key, &event_ids);
+            }
+        }
+    }
+
+    ///#Description
+    ///
+    /// call the `PolicyManager` back with an agreed outcome, or in optimistic mode hold it
+    /// for its dispute window
+    fn propose_outcome(
+        &mut self,
+        mut request: Request,
+        outcome: Observation,
+        triggering_values: HashMap<String, u32>,
+        reports: u64,
+        nodes: Vec<AccountId>,
+    ) -> Option<Promise> {
+        let pending = match &self.optimistic {
+            Some(config) => PendingOutcome::new(config, reports, nodes, env::block_timestamp()),
+            None => {
+                let event_period = self.event_period(&outcome);
+                return Some(dispatch_event_callback(
+                    request,
+                    outcome,
+                    event_period,
+                    triggering_values,
+                    reports,
+                    nodes,
+                ));
+            }
+        };
+        self.pending_outcomes.insert(&request.policy_id, &pending);
+        self.set_request_status(&mut request, RequestStatus::Pending);
+        HurricaneOracleEvent::OutcomeProposed([OutcomeProposedData {
+            policy_id: request.policy_id,
+            event_id: outcome.event_id,
+            finalizes_at: pending.finalizes_at,
+        }])
+        .emit();
+        None
+    }
+
+    ///call the `PolicyManager` back with a pending outcome that is no longer disputable
+    fn release_outcome(
+        &mut self,
+        policy_id: String,
+        pending: PendingOutcome,
+    ) -> Result<Promise, HurricaneOracleError> {
+        let mut request = self
+            .requests
+            .get(&policy_id)
+            .ok_or(HurricaneOracleError::RequestNotFound)?;
+        let round = self
+            .reporting_rounds
+            .get(&policy_id)
+            .ok_or(HurricaneOracleError::NoPendingOutcome)?;
+        let outcome = round
+            .outcome
+            .ok_or(HurricaneOracleError::NoPendingOutcome)?;
+        self.pending_outcomes.remove(&policy_id);
+        self.pay_nodes(&mut request, &pending.nodes);
+        self.set_request_status(&mut request, RequestStatus::Reported);
+        let event_period = self.event_period(&outcome);
+        Ok(dispatch_event_callback(
+            request,
+            outcome,
+            event_period,
+            round.triggering_values,
+            pending.reports,
+            Vec::new(),
+        ))
+    }
+
+    ///the start and end of an outcome's event in the registry
+    fn event_period(&self, outcome: &Observation) -> [u64; 2] {
+        self.events
+            .get(&outcome.event_id)
+            .map_or([outcome.date, outcome.date], |event| [event.start, event.end])
+    }
+
+    ///#Description
+    ///
+    /// share the request's fee between the nodes that backed its outcome, and record the
+    /// settled reports of every node in the round
+    fn pay_nodes(&mut self, request: &mut Request, nodes: &[AccountId]) {
+        let shares = split(request.fee.0, nodes.len());
+        for (node, share) in nodes.iter().zip(shares) {
+            let mut account = self.nodes.get(node).unwrap_or_default();
+            account.credit(share);
+            account.settled_reports += 1;
+            account.accurate_reports += 1;
+            self.nodes.insert(node, &account);
+        }
+        if let Some(round) = self.reporting_rounds.get(&request.policy_id) {
+            for report in round.reports.iter().filter(|report| !nodes.contains(&report.node)) {
+                if let Some(mut account) = self.nodes.get(&report.node) {
+                    account.settled_reports += 1;
+                    self.nodes.insert(&report.node, &account);
+                }
+            }
+        }
+        if !nodes.is_empty() {
+            request.fee = U128(0);
+            self.requests.insert(&request.policy_id, request);
+        }
+    }
+
+    ///#Description
+    ///
+    /// slash a node's stake by the `slash_percent`
+    ///
+    /// #Returns
+    ///
+    /// the yoctoNEAR slashed, for the caller to transfer
+    fn slash_node(&mut self, node: &AccountId, policy_id: &str, reason: SlashReason) -> u128 {
+        let mut account = match self.nodes.get(node) {
+            Some(account) => account,
+            None => return 0,
+        };
+        let amount = account.slash(self.node_config.slash_percent);
+        match reason {
+            SlashReason::OverturnedReport => account.settled_reports += 1,
+            SlashReason::MissedDeadline => account.missed_deadlines += 1,
+        }
+        self.nodes.insert(node, &account);
+        HurricaneOracleEvent::NodeSlashed([NodeSlashData {
+            account_id: node.clone(),
+            amount: U128(amount),
+            policy_id: policy_id.to_string(),
+            reason,
+        }])
+        .emit();
+        amount
+    }
+
+    ///has the node bonded at least the `min_bond`
+    fn is_bonded(&self, node: &AccountId) -> bool {
+        let bond = self.nodes.get(node).map_or(0, |account| account.bond.0);
+        bond >= self.node_config.min_bond.0
+    }
+
+    ///the authorized nodes that are bonded and so can report
+    fn active_nodes(&self) -> Vec<AccountId> {
+        self.authorized_accounts
+            .iter()
+            .filter(|node| self.is_bonded(node))
+            .collect()
+    }
+
+    fn node_stats(&self, account_id: AccountId, account: NodeAccount) -> NodeStats {
+        NodeStats {
+            active: self.authorized_accounts.to_vec().contains(&account_id)
+                && self.is_bonded(&account_id),
+            accuracy_percent: account.accuracy_percent(),
+            account_id,
+            account,
+        }
+    }
+
+    fn set_request_status(&mut self, request: &mut Request, status: RequestStatus) {
+        request.status = status;
+        self.requests.insert(&request.policy_id, request);
+        HurricaneOracleEvent::RequestStatusChanged([RequestStatusData {
+            policy_id: request.policy_id.clone(),
+            status: request.status.clone(),
+        }])
+        .emit();
+    }
+
+    //node registry
+
+    ///#Description
+    ///
+    /// authorized node adds the attached deposit to its bond
+    #[payable]
+    pub fn bond_node(&mut self) {
+        or_panic(self.try_bond_node())
+    }
+
+    fn try_bond_node(&mut self) -> Result<(), HurricaneOracleError> {
+        let node = env::predecessor_account_id();
+        assert!(self.authorized_accounts.to_vec().contains(&node), "not authorized.");
+        let amount = env::attached_deposit();
+        if amount == 0 {
+            return Err(HurricaneOracleError::InvalidAmount);
+        }
+        let mut account = self.nodes.get(&node).unwrap_or_default();
+        account.bond = U128(account.bond.0 + amount);
+        self.nodes.insert(&node, &account);
+        HurricaneOracleEvent::NodeBonded([NodeStakeData {
+            account_id: node,
+            amount: U128(amount),
+        }])
+        .emit();
+        Ok(())
+    }
+
+    ///#Description
+    ///
+    /// start unbonding part of the caller's bond. it stays slashable until it is withdrawn
+    /// with `withdraw_unbonded` after the `unbonding_period`
+    pub fn unbond_node(&mut self, amount: U128) {
+        or_panic(self.try_unbond_node(amount))
+    }
+
+    fn try_unbond_node(&mut self, amount: U128) -> Result<(), HurricaneOracleError> {
+        let node = env::predecessor_account_id();
+        let mut account = self
+            .nodes
+            .get(&node)
+            .ok_or(HurricaneOracleError::NodeNotFound)?;
+        if !account.unbond(
+            amount.0,
+            env::block_timestamp(),
+            self.node_config.unbonding_period,
+        ) {
+            return Err(HurricaneOracleError::InvalidAmount);
+        }
+        self.nodes.insert(&node, &account);
+        HurricaneOracleEvent::NodeUnbonded([NodeStakeData {
+            account_id: node,
+            amount,
+        }])
+        .emit();
+        Ok(())
+    }
+
+    ///transfer the caller's unbonded stake once the `unbonding_period` has ended
+    pub fn withdraw_unbonded(&mut self) -> Promise {
+        or_panic(self.try_withdraw_unbonded())
+    }
+
+    fn try_withdraw_unbonded(&mut self) -> Result<Promise, HurricaneOracleError> {
+        let node = env::predecessor_account_id();
+        let mut account = self
+            .nodes
+            .get(&node)
+            .ok_or(HurricaneOracleError::NodeNotFound)?;
+        if account.unbonding.0 == 0 {
+            return Err(HurricaneOracleError::NothingToWithdraw);
+        }
+        let amount = account.withdraw_unbonded(env::block_timestamp());
+        if amount == 0 {
+            return Err(HurricaneOracleError::UnbondingLocked);
+        }
+        self.nodes.insert(&node, &account);
+        HurricaneOracleEvent::StakeWithdrawn([NodeStakeData {
+            account_id: node.clone(),
+            amount: U128(amount),
+        }])
+        .emit();
+        Ok(Promise::new(node).transfer(amount))
+    }
+
+    ///transfer the caller's unclaimed fees
+    pub fn claim_earnings(&mut self) -> Promise {
+        or_panic(self.try_claim_earnings())
+    }
+
+    fn try_claim_earnings(&mut self) -> Result<Promise, HurricaneOracleError> {
+        let node = env::predecessor_account_id();
+        let mut account = self
+            .nodes
+            .get(&node)
+            .ok_or(HurricaneOracleError::NodeNotFound)?;
+        let amount = account.earnings.0;
+        if amount == 0 {
+            return Err(HurricaneOracleError::NothingToWithdraw);
+        }
+        account.earnings = U128(0);
+        self.nodes.insert(&node, &account);
+        HurricaneOracleEvent::EarningsClaimed([NodeStakeData {
+            account_id: node.clone(),
+            amount: U128(amount),
+        }])
+        .emit();
+        Ok(Promise::new(node).transfer(amount))
+    }
+
+    ///#Description
+    ///
+    /// slash the active nodes that have not reported in a round that is still undecided
+    /// `report_deadline` after its first report. the slashed stake goes to the master admin.
+    /// anyone can call it, once per round
+    ///
+    /// #Returns
+    ///
+    /// the nodes that were slashed
+    pub fn enforce_report_deadline(
+        &mut self,
+        policy_id: String,
+    ) -> Vec<AccountId> {
+        or_panic(self.try_enforce_report_deadline(policy_id))
+    }
+
+    fn try_enforce_report_deadline(
+        &mut self,
+        policy_id: String,
+    ) -> Result<Vec<AccountId>, HurricaneOracleError> {
+        let mut round = self
+            .reporting_rounds
+            .get(&policy_id)
+            .ok_or(HurricaneOracleError::RequestNotFound)?;
+        let deadline = round
+            .started_at()
+            .map(|started_at| started_at.saturating_add(self.node_config.report_deadline));
+        match deadline {
+            Some(deadline)
+                if round.outcome.is_none()
+                    && !round.deadline_enforced
+                    && env::block_timestamp() > deadline => {}
+            _ => return Err(HurricaneOracleError::DeadlineNotMissed),
+        }
+        let missing: Vec<AccountId> = self
+            .active_nodes()
+            .into_iter()
+            .filter(|node| !round.has_reported(node))
+            .collect();
+        let mut slashed = 0;
+        for node in missing.iter() {
+            slashed += self.slash_node(node, &policy_id, SlashReason::MissedDeadline);
+        }
+        round.deadline_enforced = true;
+        self.reporting_rounds.insert(&policy_id, &round);
+        if slashed > 0 {
+            Promise::new(self.master_admin.clone()).transfer(slashed);
+        }
+        Ok(missing)
+    }
+
+    ///a node's stake, accuracy and earnings
+    pub fn get_node(&self, account_id: AccountId) -> Option<NodeStats> {
+        self.nodes
+            .get(&account_id)
+            .map(|account| self.node_stats(account_id, account))
+    }
+
+    ///#Description
+    ///
+    /// the stake, accuracy and earnings of the registered nodes, `limit` of them starting at
+    /// `from_index`
+    pub fn get_nodes(&self, from_index: u64, limit: u64) -> Vec<NodeStats> {
+        let keys = self.nodes.keys_as_vector();
+        let values = self.nodes.values_as_vector();
+        (from_index..std::cmp::min(from_index.saturating_add(limit), keys.len()))
+            .filter_map(|index| Some(self.node_stats(keys.get(index)?, values.get(index)?)))
+            .collect()
+    }
+
+    pub fn get_node_config(&self) -> NodeConfig {
+        self.node_config.clone()
+    }
+
+    //administrative functions
+
+    pub fn add_authorized_account(&mut self, auth_account: AccountId) {
+        assert!(self
+            .admins
+            .to_vec()
+            .contains(&env::predecessor_account_id()));
+        self.authorized_accounts.push(&auth_account);
+        HurricaneOracleEvent::NodeAdded([AccountData {
+            account_id: auth_account,
+        }])
+        .emit();
+    }
+
+    pub fn remove_authorized_account(&mut self, auth_account: AccountId) {
+        assert!(self
+            .admins
+            .to_vec()
+            .contains(&env::predecessor_account_id()));
+        let index = self
+            .authorized_accounts
+            .iter()
+            .position(|account| account == auth_account)
+            .unwrap() as u64;
+
+        self.authorized_accounts.swap_remove(index);
+        HurricaneOracleEvent::NodeRemoved([AccountData {
+            account_id: auth_account,
+        }])
+        .emit();
+    }
+
+    pub fn add_policy_manager(&mut self, policy_manager: AccountId) {
+        assert!(self
+            .admins
+            .to_vec()
+            .contains(&env::predecessor_account_id()));
+        self.policy_managers.push(&policy_manager);
+        HurricaneOracleEvent::PolicyManagerAdded([AccountData {
+            account_id: policy_manager,
+        }])
+        .emit();
+    }
+
+    ///#Description
+    ///
+    /// stop a `PolicyManager` from making requests. its existing requests are still fulfilled
+    pub fn remove_policy_manager(&mut self, policy_manager: AccountId) {
+        assert!(self
+            .admins
+            .to_vec()
+            .contains(&env::predecessor_account_id()));
+        let index = self
+            .policy_managers
+            .iter()
+            .position(|account| account == policy_manager)
+            .unwrap() as u64;
+
+        self.policy_managers.swap_remove(index);
+        HurricaneOracleEvent::PolicyManagerRemoved([AccountData {
+            account_id: policy_manager,
+        }])
+        .emit();
+    }
+
+    pub fn get_policy_managers(&self) -> Vec<AccountId> {
+        self.policy_managers.to_vec()
+    }
+
+    ///#Description
+    ///
+    /// set how many authorized nodes must agree before a request is fulfilled
+    pub fn set_quorum(&mut self, quorum: u64) {
+        assert!(self
+            .admins
+            .to_vec()
+            .contains(&env::predecessor_account_id()));
+        assert!(quorum > 0, "quorum must be at least one");
+        self.quorum = quorum;
+        HurricaneOracleEvent::QuorumChanged([QuorumData { quorum }]).emit();
+    }
+
+    ///#Description
+    ///
+    /// set how node measurements are aggregated
+    pub fn set_aggregation(&mut self, aggregation: AggregationConfig) {
+        assert!(self
+            .admins
+            .to_vec()
+            .contains(&env::predecessor_account_id()));
+        assert!(aggregation.is_valid(), "invalid aggregation config");
+        self.aggregation = aggregation.clone();
+        HurricaneOracleEvent::AggregationChanged([AggregationData { aggregation }]).emit();
+    }
+
+    ///#Description
+    ///
+    /// switch optimistic mode on with `Some` settings, or off with `None`. outcomes already
+    /// pending keep the window and bond they were proposed with
+    pub fn set_optimistic(&mut self, optimistic: Option<OptimisticConfig>) {
+        assert!(self
+            .admins
+            .to_vec()
+            .contains(&env::predecessor_account_id()));
+        assert!(
+            optimistic.as_ref().is_none_or(|config| config.is_valid()),
+            "invalid optimistic config"
+        );
+        self.optimistic = optimistic.clone();
+        HurricaneOracleEvent::OptimisticConfigChanged([OptimisticConfigData { optimistic }]).emit();
+    }
+
+    pub fn get_optimistic(&self) -> Option<OptimisticConfig> {
+        self.optimistic.clone()
+    }
+
+    ///#Description
+    ///
+    /// set the account that resolves disputes. `None` leaves them to the admins
+    pub fn set_arbiter(&mut self, arbiter: Option<AccountId>) {
+        assert!(self.master_admin == env::predecessor_account_id());
+        self.arbiter = arbiter.clone();
+        HurricaneOracleEvent::ArbiterChanged([ArbiterData { arbiter }]).emit();
+    }
+
+    pub fn get_arbiter(&self) -> Option<AccountId> {
+        self.arbiter.clone()
+    }
+
+    ///#Description
+    ///
+    /// set how late events may be reported. requests made earlier keep the reporting window
+    /// they were created with
+    pub fn set_submission_rules(&mut self, rules: SubmissionRules) {
+        assert!(self
+            .admins
+            .to_vec()
+            .contains(&env::predecessor_account_id()));
+        self.submission_rules = rules.clone();
+        HurricaneOracleEvent::SubmissionRulesChanged([SubmissionRulesData { rules }]).emit();
+    }
+
+    pub fn get_submission_rules(&self) -> SubmissionRules {
+        self.submission_rules.clone()
+    }
+
+    ///#Description
+    ///
+    /// set the bond, fee, slashing and unbonding rules of the node registry
+    pub fn set_node_config(&mut self, config: NodeConfig) {
+        assert!(self
+            .admins
+            .to_vec()
+            .contains(&env::predecessor_account_id()));
+        assert!(config.is_valid(), "invalid node config");
+        self.node_config = config.clone();
+        HurricaneOracleEvent::NodeConfigChanged([NodeConfigData { config }]).emit();
+    }
+
+    ///#Description
+    ///
+    /// register a data provider's ed25519 public key. keys are never replaced, rotate a key by
+    /// registering the new one under a new id and revoking the old one
+    ///
+    /// #Parameters
+    ///
+    /// *`key_id` the id reports will name the key by
+    /// *`public_key` the 32 byte public key
+    pub fn add_provider_key(
+        &mut self,
+        key_id: String,
+        public_key: Base64VecU8,
+    ) {
+        or_panic(self.try_add_provider_key(key_id, public_key))
+    }
+
+    fn try_add_provider_key(
+        &mut self,
+        key_id: String,
+        public_key: Base64VecU8,
+    ) -> Result<(), HurricaneOracleError> {
+        assert!(self
+            .admins
+            .to_vec()
+            .contains(&env::predecessor_account_id()));
+        if self.provider_keys.get(&key_id).is_some() {
+            return Err(HurricaneOracleError::ProviderKeyExists);
+        }
+        validate_public_key(&public_key.0)?;
+        let provider_key = ProviderKey {
+            public_key: public_key.clone(),
+            added_at: env::block_timestamp(),
+            revoked_at: None,
+        };
+        self.provider_keys.insert(&key_id, &provider_key);
+        HurricaneOracleEvent::ProviderKeyAdded([ProviderKeyData { key_id, public_key }]).emit();
+        Ok(())
+    }
+
+    ///#Description
+    ///
+    /// stop accepting reports signed with a data provider key. the key is kept so the
+    /// signatures of earlier reports can still be verified
+    pub fn revoke_provider_key(&mut self, key_id: String) {
+        or_panic(self.try_revoke_provider_key(key_id))
+    }
+
+    fn try_revoke_provider_key(&mut self, key_id: String) -> Result<(), HurricaneOracleError> {
+        assert!(self
+            .admins
+            .to_vec()
+            .contains(&env::predecessor_account_id()));
+        let mut provider_key = self
+            .provider_keys
+            .get(&key_id)
+            .ok_or(HurricaneOracleError::ProviderKeyNotFound)?;
+        if provider_key.is_revoked() {
+            return Err(HurricaneOracleError::ProviderKeyRevoked);
+        }
+        provider_key.revoked_at = Some(env::block_timestamp());
+        self.provider_keys.insert(&key_id, &provider_key);
+        HurricaneOracleEvent::ProviderKeyRevoked([ProviderKeyData {
+            key_id,
+            public_key: provider_key.public_key,
+        }])
+        .emit();
+        Ok(())
+    }
+
+    pub fn add_admin(&mut self, admin: AccountId) {
+        assert!(self.master_admin == env::predecessor_account_id());
+        self.admins.push(&admin);
+        HurricaneOracleEvent::AdminAdded([AccountData { account_id: admin }]).emit();
+    }
+
+    pub fn remove_admin(&mut self, old_admin: AccountId) {
+        assert!(self.master_admin == env::predecessor_account_id());
+        let index = self
+            .admins
+            .iter()
+            .position(|account| account == old_admin)
+            .unwrap() as u64;
+
+        self.admins.swap_remove(index);
+        HurricaneOracleEvent::AdminRemoved([AccountData {
+            account_id: old_admin,
+        }])
+        .emit();
+    }
+
+    pub fn change_master_admin(&mut self, new_admin: AccountId) {
+        assert!(self.master_admin == env::predecessor_account_id());
+        self.master_admin = new_admin;
+        HurricaneOracleEvent::MasterAdminChanged([AccountData {
+            account_id: self.master_admin.clone(),
+        }])
+        .emit();
+    }
+}
+
+///panic with the message of a refused call, so the transaction fails and its changes roll back
+fn or_panic<T>(result: Result<T, HurricaneOracleError>) -> T {
+    result.unwrap_or_else(|error| env::panic(error.to_string().as_bytes()))
+}
+
+///#Description
+///
+/// send the `event_callback` of a triggered request to its `PolicyManager`, and record the
+/// result in `on_event_callback`
+///
+/// #Parameters
+///
+/// *`event_period` the start and end of the event
+/// *`reports` how many nodes backed the outcome, for the `RequestFulfilled` log
+/// *`nodes` the nodes `on_event_callback` pays if the `PolicyManager` accepts the callback
+fn dispatch_event_callback(
+    request: Request,
+    outcome: Observation,
+    event_period: [u64; 2],
+    triggering_values: HashMap<String, u32>,
+    reports: u64,
+    nodes: Vec<AccountId>,
+) -> Promise {
+    HurricaneOracleEvent::RequestFulfilled([RequestFulfilledData {
+        policy_id: request.policy_id.clone(),
+        policy_manager: request.policy_manager.clone(),
+        event_id: outcome.event_id.clone(),
+        date: outcome.date,
+        reports,
+        triggering_values: triggering_values.clone(),
+    }])
+    .emit();
+    policy_manager::event_callback(
+        Event {
+            id: outcome.event_id,
+            peril: request.peril,
+            oracle: env::current_account_id(),
+            date: outcome.date,
+            start: event_period[0],
+            end: event_period[1],
+        },
+        request.policy_id.clone(),
+        OracleMetadata {
+            triggering_values,
+            claims_manager: request.claims_manager,
+            oracle: env::current_account_id(),
+        },
+        &request.policy_manager,
+        0,
+        EVENT_CALLBACK_GAS,
+    )
+    .then(ext_self::on_event_callback(
+        request.policy_id,
+        nodes,
+        &env::current_account_id(),
+        0,
+        ON_EVENT_CALLBACK_GAS,
+    ))
+}
+
+#[ext_contract(policy_manager)]
+trait PolicyManager {
+    fn event_callback(event: Event, policy_id: String, oracle_data: OracleMetadata);
+}
+
+#[ext_contract(ext_self)]
+trait HurricaneOracleCallbacks {
+    fn on_event_callback(policy_id: String, nodes: Vec<AccountId>) -> bool;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_sdk::MockedBlockchain;
+    use near_sdk::{testing_env, VMContext};
+
+    const POLICY_MANAGER: &str = "policy_manager.near";
+
+    fn oracle() -> HurricaneOracle {
+        testing_env!(get_context("admin.near".to_string(), 0, 0));
+        let mut oracle = HurricaneOracle::new();
+        oracle.add_admin("admin.near".to_string());
+        oracle.add_policy_manager(POLICY_MANAGER.to_string());
+        oracle
+    }
+
+    fn trigger() -> Trigger {
+        Trigger::Hurricane(HurricaneTrigger {
+            min_category: 3,
+            area: ProtectedArea::Point(Coordinate {
+                latitude: 25_761_680,
+                longitude: -80_191_790,
+            }),
+            radius: 50_000,
+            min_wind_speed: None,
+            window: TimeWindow { start: 1, end: 2 },
+        })
+    }
+
+    fn request_for(oracle: &mut HurricaneOracle, policy_manager: &str, policy_id: &str) {
+        testing_env!(get_context(policy_manager.to_string(), 0, 10));
+        oracle.check_for_events(
+            policy_id.to_string(),
+            "claims_manager.near".to_string(),
+            0,
+            DAY,
+            trigger(),
+        );
+    }
+
+    fn decided_round() -> ReportingRound {
+        ReportingRound {
+            outcome: Some(Observation {
+                event_id: "storm".to_string(),
+                date: 1,
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "another policy manager made the request for this policy")]
+    fn only_the_policy_manager_replaces_a_request() {
+        let mut oracle = oracle();
+        oracle.add_policy_manager("other_policy_manager.near".to_string());
+        request_for(&mut oracle, POLICY_MANAGER, "policy");
+        request_for(&mut oracle, "other_policy_manager.near", "policy");
+    }
+
+    #[test]
+    #[should_panic(expected = "only authorized policy managers can request events")]
+    fn a_policy_id_cannot_be_squatted() {
+        let mut oracle = oracle();
+        request_for(&mut oracle, "intruder.near", "policy");
+    }
+
+    #[test]
+    #[should_panic(expected = "the request's outcome is being sent to its policy manager")]
+    fn a_request_is_not_replaced_while_its_callback_is_in_flight() {
+        let mut oracle = oracle();
+        request_for(&mut oracle, POLICY_MANAGER, "policy");
+        let mut request = oracle.requests.get(&"policy".to_string()).unwrap();
+        request.status = RequestStatus::Reported;
+        oracle.requests.insert(&request.policy_id, &request);
+        oracle.reporting_rounds.insert(&request.policy_id, &decided_round());
+        request_for(&mut oracle, POLICY_MANAGER, "policy");
+    }
+
+    #[test]
+    fn replacing_a_request_starts_a_new_round() {
+        let mut oracle = oracle();
+        request_for(&mut oracle, POLICY_MANAGER, "policy");
+        let mut request = oracle.requests.get(&"policy".to_string()).unwrap();
+        request.status = RequestStatus::Fulfilled;
+        oracle.requests.insert(&request.policy_id, &request);
+        oracle.reporting_rounds.insert(&request.policy_id, &decided_round());
+        request_for(&mut oracle, POLICY_MANAGER, "policy");
+        let request = oracle.requests.get(&"policy".to_string()).unwrap();
+        assert_eq!(request.status, RequestStatus::Open);
+        assert_eq!(request.fee, U128(10));
+        assert!(oracle.reporting_rounds.get(&request.policy_id).is_none());
+    }
+
+    #[test]
+    fn sweeping_removes_rounds_and_pending_outcomes() {
+        let mut oracle = oracle();
+        request_for(&mut oracle, POLICY_MANAGER, "fulfilled");
+        request_for(&mut oracle, POLICY_MANAGER, "pending");
+        let config = OptimisticConfig {
+            liveness: DAY,
+            dispute_bond: U128(0),
+        };
+        for (policy_id, status) in [
+            ("fulfilled", RequestStatus::Fulfilled),
+            ("pending", RequestStatus::Pending),
+        ] {
+            let mut request = oracle.requests.get(&policy_id.to_string()).unwrap();
+            request.status = status;
+            oracle.requests.insert(&request.policy_id, &request);
+            oracle.reporting_rounds.insert(&request.policy_id, &decided_round());
+            oracle.pending_outcomes.insert(
+                &request.policy_id,
+                &PendingOutcome::new(&config, 1, Vec::new(), 0),
+            );
+        }
+        assert_eq!(oracle.sweep_requests(0, 10), 1);
+        assert!(oracle.reporting_rounds.get(&"fulfilled".to_string()).is_none());
+        assert!(oracle.pending_outcomes.get(&"fulfilled".to_string()).is_none());
+        assert!(oracle.pending_outcomes.get(&"pending".to_string()).is_some());
+
+        // an outcome that is never finalized or resolved expires with its request
+        let closes_at = oracle.requests.get(&"pending".to_string()).unwrap().closes_at;
+        let expired_at = closes_at + RESOLUTION_PERIOD + 1;
+        testing_env!(get_context("anyone.near".to_string(), expired_at, 0));
+        assert_eq!(oracle.sweep_requests(0, 10), 1);
+        assert!(oracle.requests.get(&"pending".to_string()).is_none());
+        assert!(oracle.reporting_rounds.get(&"pending".to_string()).is_none());
+        assert!(oracle.pending_outcomes.get(&"pending".to_string()).is_none());
+    }
+
+    #[test]
+    fn match_event_fans_out_over_every_candidate() {
+        let mut oracle = oracle();
+        let request_count = MAX_FAN_OUT as usize + 5;
+        for index in 0..request_count {
+            request_for(&mut oracle, POLICY_MANAGER, &format!("policy-{:02}", index));
+        }
+        let node = "node.near".to_string();
+        oracle.authorized_accounts.push(&node);
+        // optimistic mode moves each triggered request out of the open set right away
+        oracle.optimistic = Some(OptimisticConfig {
+            liveness: DAY,
+            dispute_bond: U128(0),
+        });
+        let mut measurements = HashMap::new();
+        measurements.insert("category".to_string(), 4);
+        let position = match trigger().area() {
+            ProtectedArea::Point(point) => point.clone(),
+            _ => unreachable!(),
+        };
+        oracle.events.insert(
+            &"storm".to_string(),
+            &EventRecord {
+                id: "storm".to_string(),
+                peril: Peril::Hurricane,
+                name: String::new(),
+                start: 1,
+                end: 1,
+                severity: measurements.clone(),
+                track: vec![TrackPoint {
+                    timestamp: 1,
+                    position,
+                    measurements,
+                    reporters: vec![node.clone()],
+                }],
+                reporters: vec![node.clone()],
+            },
+        );
+        testing_env!(get_context(node, 0, 0));
+        let first = oracle.match_event("storm".to_string(), 0, None, MAX_FAN_OUT);
+        assert_eq!(first.policy_ids.len(), MAX_FAN_OUT as usize);
+        assert_eq!(first.last_policy_id, Some("policy-19".to_string()));
+        let second = oracle.match_event("storm".to_string(), 0, first.last_policy_id, MAX_FAN_OUT);
+        assert_eq!(second.policy_ids.len(), 5);
+        assert_eq!(second.last_policy_id, None);
+        for index in 0..request_count {
+            let policy_id = format!("policy-{:02}", index);
+            assert!(oracle.pending_outcomes.get(&policy_id).is_some(), "{} was skipped", policy_id);
+        }
+    }
+
+    #[test]
+    fn one_node_cannot_inflate_the_severity_into_a_payout() {
+        let mut oracle = oracle();
+        let epicenter = Coordinate {
+            latitude: 35_689_500,
+            longitude: 139_691_700,
+        };
+        testing_env!(get_context(POLICY_MANAGER.to_string(), 0, 10));
+        oracle.check_for_events(
+            "policy".to_string(),
+            "claims_manager.near".to_string(),
+            0,
+            DAY,
+            Trigger::Earthquake(EarthquakeTrigger {
+                min_magnitude: 70,
+                min_pga: None,
+                area: ProtectedArea::Point(epicenter.clone()),
+                radius: 50_000,
+                window: TimeWindow { start: 1, end: 2 },
+            }),
+        );
+        oracle.quorum = 2;
+        // the honest node reports M5.0, the other M9.0. the registry keeps the peak
+        for (node, magnitude) in [("honest.near", 50), ("inflating.near", 90)] {
+            oracle.authorized_accounts.push(&node.to_string());
+            let mut severity = HashMap::new();
+            severity.insert("magnitude".to_string(), magnitude);
+            severity.insert("distance".to_string(), 10_000);
+            testing_env!(get_context(node.to_string(), 0, 0));
+            oracle.report_event(EventRecord {
+                id: "quake".to_string(),
+                peril: Peril::Earthquake,
+                name: String::new(),
+                start: 1,
+                end: 1,
+                severity,
+                track: vec![TrackPoint {
+                    timestamp: 1,
+                    position: epicenter.clone(),
+                    measurements: HashMap::new(),
+                    reporters: Vec::new(),
+                }],
+                reporters: Vec::new(),
+            });
+        }
+        assert_eq!(oracle.events.get(&"quake".to_string()).unwrap().severity["magnitude"], 90);
+        let fan_out = oracle.match_event("quake".to_string(), 0, None, MAX_FAN_OUT);
+        assert!(fan_out.policy_ids.is_empty());
+        assert_eq!(fan_out.awaiting_reports, vec!["policy".to_string()]);
+        assert!(oracle.reporting_rounds.get(&"policy".to_string()).is_none());
+        let request = oracle.requests.get(&"policy".to_string()).unwrap();
+        assert_eq!(request.status, RequestStatus::Open);
+    }
+
+    fn get_context(
+        predecessor_account_id: String,
+        block_timestamp: u64,
+        attached_deposit: u128,
+    ) -> VMContext {
+        VMContext {
+            current_account_id: "oracle.near".to_string(),
+            signer_account_id: predecessor_account_id.clone(),
+            signer_account_pk: vec![0, 1, 2],
+            predecessor_account_id,
+            input: vec![],
+            block_index: 0,
+            block_timestamp,
+            account_balance: 10u128.pow(24),
+            account_locked_balance: 0,
+            // storage written under an earlier context is still there
+            storage_usage: 10u64.pow(6),
+            attached_deposit,
+            prepaid_gas: 10u64.pow(18),
+            random_seed: vec![0, 1, 2],
+            is_view: false,
+            output_data_receivers: vec![],
+            epoch_height: 19,
+        }
+    }
 }
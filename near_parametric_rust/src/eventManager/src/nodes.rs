@@ -0,0 +1,224 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::json_types::U128;
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::AccountId;
+
+///#Description
+///
+/// the economics of the node registry
+///
+/// #Fields
+///
+/// *`min_bond` the yoctoNEAR a node must have bonded to report
+/// *`fulfillment_fee` the yoctoNEAR a `PolicyManager` attaches to each request. it is shared by
+/// the nodes that backed the request's outcome
+/// *`slash_percent` the share of a node's stake slashed for an overturned report or a
+/// missed deadline
+/// *`unbonding_period` how long unbonded stake stays locked, in nanoseconds
+/// *`report_deadline` how long after a request's first report the other nodes have to
+/// report, in nanoseconds, unless quorum is reached first
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct NodeConfig {
+    pub min_bond: U128,
+    pub fulfillment_fee: U128,
+    pub slash_percent: u8,
+    pub unbonding_period: u64,
+    pub report_deadline: u64,
+}
+
+impl Default for NodeConfig {
+    fn default() -> Self {
+        Self {
+            min_bond: U128(0),
+            fulfillment_fee: U128(0),
+            slash_percent: 10,
+            unbonding_period: 7 * 86_400_000_000_000,
+            report_deadline: 86_400_000_000_000,
+        }
+    }
+}
+
+impl NodeConfig {
+    pub fn is_valid(&self) -> bool {
+        self.slash_percent <= 100 && self.report_deadline > 0
+    }
+}
+
+///#Description
+///
+/// a node's stake and track record
+///
+/// #Fields
+///
+/// *`bond` the active stake in yoctoNEAR
+/// *`unbonding` stake waiting out the unbonding period
+/// *`unbonded_at` when `unbonding` can be withdrawn
+/// *`earnings` fees not yet claimed
+/// *`total_earned` every fee credited so far
+/// *`slashed` every yoctoNEAR slashed so far
+/// *`settled_reports` reports on outcomes that were settled, i.e. paid or overturned
+/// *`accurate_reports` settled reports that backed an outcome that stood
+/// *`missed_deadlines` rounds the node did not report in before the deadline
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct NodeAccount {
+    pub bond: U128,
+    pub unbonding: U128,
+    pub unbonded_at: u64,
+    pub earnings: U128,
+    pub total_earned: U128,
+    pub slashed: U128,
+    pub settled_reports: u64,
+    pub accurate_reports: u64,
+    pub missed_deadlines: u64,
+}
+
+impl Default for NodeAccount {
+    fn default() -> Self {
+        Self {
+            bond: U128(0),
+            unbonding: U128(0),
+            unbonded_at: 0,
+            earnings: U128(0),
+            total_earned: U128(0),
+            slashed: U128(0),
+            settled_reports: 0,
+            accurate_reports: 0,
+            missed_deadlines: 0,
+        }
+    }
+}
+
+impl NodeAccount {
+    ///accurate settled reports in percent. `None` before any report was settled
+    pub fn accuracy_percent(&self) -> Option<u64> {
+        if self.settled_reports == 0 {
+            return None;
+        }
+        Some(self.accurate_reports * 100 / self.settled_reports)
+    }
+
+    pub fn stake(&self) -> u128 {
+        self.bond.0 + self.unbonding.0
+    }
+
+    pub fn credit(&mut self, fee: u128) {
+        self.earnings = U128(self.earnings.0 + fee);
+        self.total_earned = U128(self.total_earned.0 + fee);
+    }
+
+    ///#Description
+    ///
+    /// take `percent` of the stake, from the bond first and then from unbonding stake so a
+    /// node cannot escape slashing by unbonding
+    ///
+    /// #Returns
+    ///
+    /// the yoctoNEAR slashed
+    pub fn slash(&mut self, percent: u8) -> u128 {
+        let amount =
+            self.stake() / 100 * percent as u128 + self.stake() % 100 * percent as u128 / 100;
+        let from_bond = amount.min(self.bond.0);
+        self.bond = U128(self.bond.0 - from_bond);
+        self.unbonding = U128(self.unbonding.0 - (amount - from_bond));
+        self.slashed = U128(self.slashed.0 + amount);
+        amount
+    }
+
+    ///move `amount` of the bond to unbonding. the lock restarts for all unbonding stake
+    pub fn unbond(&mut self, amount: u128, now: u64, unbonding_period: u64) -> bool {
+        if amount == 0 || amount > self.bond.0 {
+            return false;
+        }
+        self.bond = U128(self.bond.0 - amount);
+        self.unbonding = U128(self.unbonding.0 + amount);
+        self.unbonded_at = now.saturating_add(unbonding_period);
+        true
+    }
+
+    ///take the unbonding stake once its lock has ended
+    pub fn withdraw_unbonded(&mut self, now: u64) -> u128 {
+        if now < self.unbonded_at {
+            return 0;
+        }
+        let amount = self.unbonding.0;
+        self.unbonding = U128(0);
+        amount
+    }
+}
+
+///#Description
+///
+/// why a node was slashed
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(crate = "near_sdk::serde")]
+#[serde(rename_all = "snake_case")]
+pub enum SlashReason {
+    ///it backed an outcome that was overturned in a dispute
+    OverturnedReport,
+    ///it did not report before the round's deadline
+    MissedDeadline,
+}
+
+///#Description
+///
+/// a node's registry entry as returned by the view methods
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct NodeStats {
+    pub account_id: AccountId,
+    ///whether the node is authorized and bonded enough to report
+    pub active: bool,
+    pub accuracy_percent: Option<u64>,
+    #[serde(flatten)]
+    pub account: NodeAccount,
+}
+
+///#Description
+///
+/// split `amount` between `shares` recipients. the first gets the remainder
+pub fn split(amount: u128, shares: usize) -> Vec<u128> {
+    if shares == 0 {
+        return Vec::new();
+    }
+    let share = amount / shares as u128;
+    let mut amounts = vec![share; shares];
+    amounts[0] += amount - share * shares as u128;
+    amounts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn slashing_reaches_unbonding_stake() {
+        let mut node = NodeAccount {
+            bond: U128(1_000),
+            ..Default::default()
+        };
+        assert!(node.unbond(950, 0, 100));
+        assert_eq!(node.slash(10), 100);
+        assert_eq!((node.bond.0, node.unbonding.0), (0, 900));
+        assert_eq!(node.slashed.0, 100);
+        assert_eq!(node.withdraw_unbonded(99), 0);
+        assert_eq!(node.withdraw_unbonded(100), 900);
+        assert_eq!(node.stake(), 0);
+        assert!(!node.unbond(1, 100, 100));
+    }
+
+    #[test]
+    fn accuracy_and_fees() {
+        let mut node = NodeAccount::default();
+        assert_eq!(node.accuracy_percent(), None);
+        node.settled_reports = 3;
+        node.accurate_reports = 2;
+        assert_eq!(node.accuracy_percent(), Some(66));
+        node.credit(5);
+        node.credit(7);
+        assert_eq!((node.earnings.0, node.total_earned.0), (12, 12));
+        assert_eq!(split(10, 3), vec![4, 3, 3]);
+        assert!(split(10, 0).is_empty());
+    }
+}
@@ -0,0 +1,284 @@
+use crate::drought::DroughtTrigger;
+use crate::earthquake::EarthquakeTrigger;
+use crate::hurricane::HurricaneTrigger;
+use crate::rainfall::RainfallTrigger;
+use crate::HurricaneOracleError;
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+///the largest protected radius in meters
+pub const MAX_RADIUS: u32 = 1_000_000;
+///the most vertices a protected polygon may have
+pub const MAX_POLYGON_VERTICES: usize = 64;
+
+const MAX_LATITUDE: i32 = 90_000_000;
+const MAX_LONGITUDE: i32 = 180_000_000;
+
+///#Description
+///
+/// the kind of natural catastrophe a `Request` or `Event` is about
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Debug, PartialEq, Clone, Copy)]
+#[serde(crate = "near_sdk::serde")]
+pub enum Peril {
+    Hurricane,
+    Earthquake,
+    Rainfall,
+    Drought,
+}
+
+///#Description
+///
+/// what each peril supplies to the oracle core: its trigger schema and the logic that decides
+/// whether the nodes' aggregated measurements trigger a policy
+pub trait PerilTrigger {
+    ///check the trigger before it is stored in a `Request`
+    fn validate(&self) -> Result<(), HurricaneOracleError>;
+    ///when the event must occur
+    fn window(&self) -> &TimeWindow;
+    ///where the event must occur
+    fn area(&self) -> &ProtectedArea;
+    ///do the aggregated measurements meet the trigger's thresholds
+    fn matches(&self, measurements: &HashMap<String, u32>) -> bool;
+
+    ///#Description
+    ///
+    /// does an event on `date` with the aggregated `measurements` trigger the policy
+    fn is_triggered(&self, date: u64, measurements: &HashMap<String, u32>) -> bool {
+        self.window().contains(date) && self.matches(measurements)
+    }
+}
+
+///#Description
+///
+/// the trigger of a `Request`, one variant per `Peril`
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub enum Trigger {
+    Hurricane(HurricaneTrigger),
+    Earthquake(EarthquakeTrigger),
+    Rainfall(RainfallTrigger),
+    Drought(DroughtTrigger),
+}
+
+impl Trigger {
+    pub fn peril(&self) -> Peril {
+        match self {
+            Trigger::Hurricane(_) => Peril::Hurricane,
+            Trigger::Earthquake(_) => Peril::Earthquake,
+            Trigger::Rainfall(_) => Peril::Rainfall,
+            Trigger::Drought(_) => Peril::Drought,
+        }
+    }
+
+    fn as_peril_trigger(&self) -> &dyn PerilTrigger {
+        match self {
+            Trigger::Hurricane(trigger) => trigger,
+            Trigger::Earthquake(trigger) => trigger,
+            Trigger::Rainfall(trigger) => trigger,
+            Trigger::Drought(trigger) => trigger,
+        }
+    }
+}
+
+impl PerilTrigger for Trigger {
+    fn validate(&self) -> Result<(), HurricaneOracleError> {
+        self.as_peril_trigger().validate()
+    }
+
+    fn window(&self) -> &TimeWindow {
+        self.as_peril_trigger().window()
+    }
+
+    fn area(&self) -> &ProtectedArea {
+        self.as_peril_trigger().area()
+    }
+
+    fn matches(&self, measurements: &HashMap<String, u32>) -> bool {
+        self.as_peril_trigger().matches(measurements)
+    }
+}
+
+///#Description
+///
+/// a point on earth in microdegrees (degrees × 1_000_000)
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Coordinate {
+    pub latitude: i32,
+    pub longitude: i32,
+}
+
+impl Coordinate {
+    pub fn is_valid(&self) -> bool {
+        (-MAX_LATITUDE..=MAX_LATITUDE).contains(&self.latitude)
+            && (-MAX_LONGITUDE..=MAX_LONGITUDE).contains(&self.longitude)
+    }
+}
+
+///#Description
+///
+/// the area a policy protects
+///
+/// #Variants
+///
+/// *`Point` a single location, e.g. an insured property
+/// *`Polygon` the vertices of a region, in order. the last vertex connects to the first
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub enum ProtectedArea {
+    Point(Coordinate),
+    Polygon(Vec<Coordinate>),
+}
+
+impl ProtectedArea {
+    pub fn validate(&self) -> Result<(), HurricaneOracleError> {
+        match self {
+            ProtectedArea::Point(point) => {
+                if !point.is_valid() {
+                    return Err(HurricaneOracleError::InvalidCoordinate);
+                }
+            }
+            ProtectedArea::Polygon(vertices) => {
+                if vertices.len() < 3 || vertices.len() > MAX_POLYGON_VERTICES {
+                    return Err(HurricaneOracleError::InvalidPolygon);
+                }
+                if !vertices.iter().all(Coordinate::is_valid) {
+                    return Err(HurricaneOracleError::InvalidCoordinate);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+///#Description
+///
+/// the period in which an event must occur, in nanoseconds. both ends are inclusive
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct TimeWindow {
+    pub start: u64,
+    pub end: u64,
+}
+
+impl TimeWindow {
+    pub fn contains(&self, timestamp: u64) -> bool {
+        self.start <= timestamp && timestamp <= self.end
+    }
+
+    pub fn validate(&self) -> Result<(), HurricaneOracleError> {
+        if self.start >= self.end {
+            return Err(HurricaneOracleError::InvalidTimeWindow);
+        }
+        Ok(())
+    }
+}
+
+///#Description
+///
+/// check a protected radius shared by the perils that measure distance to the area
+pub fn validate_radius(radius: u32) -> Result<(), HurricaneOracleError> {
+    if radius > MAX_RADIUS {
+        return Err(HurricaneOracleError::InvalidRadius);
+    }
+    Ok(())
+}
+
+///#Description
+///
+/// is the measurement named `key` at least `threshold`. a missing measurement never matches
+pub fn at_least(measurements: &HashMap<String, u32>, key: &str, threshold: u32) -> bool {
+    measurements.get(key).is_some_and(|value| *value >= threshold)
+}
+
+///#Description
+///
+/// is the measurement named `key` at most `threshold`. a missing measurement never matches
+pub fn at_most(measurements: &HashMap<String, u32>, key: &str, threshold: u32) -> bool {
+    measurements.get(key).is_some_and(|value| *value <= threshold)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn point() -> ProtectedArea {
+        ProtectedArea::Point(Coordinate {
+            latitude: 35_689_500,
+            longitude: 139_691_700,
+        })
+    }
+
+    fn measurements(values: &[(&str, u32)]) -> HashMap<String, u32> {
+        values
+            .iter()
+            .map(|(key, value)| (key.to_string(), *value))
+            .collect()
+    }
+
+    #[test]
+    fn dispatches_to_the_peril() {
+        let trigger = Trigger::Earthquake(EarthquakeTrigger {
+            min_magnitude: 65,
+            min_pga: None,
+            area: point(),
+            radius: 100_000,
+            window: TimeWindow { start: 10, end: 20 },
+        });
+        assert_eq!(trigger.peril(), Peril::Earthquake);
+        assert!(trigger.validate().is_ok());
+        let strong = measurements(&[("magnitude", 70), ("distance", 40_000)]);
+        assert!(trigger.is_triggered(15, &strong));
+        assert!(!trigger.is_triggered(25, &strong));
+        let weak = measurements(&[("magnitude", 60), ("distance", 40_000)]);
+        assert!(!trigger.is_triggered(15, &weak));
+    }
+
+    #[test]
+    fn rainfall_and_drought() {
+        let window = TimeWindow { start: 10, end: 20 };
+        let rainfall = Trigger::Rainfall(RainfallTrigger {
+            area: point(),
+            min_rainfall: 200,
+            accumulation_hours: 24,
+            window: window.clone(),
+        });
+        assert!(rainfall.is_triggered(15, &measurements(&[("rainfall", 250)])));
+        assert!(!rainfall.is_triggered(15, &measurements(&[("rainfall", 150)])));
+        let drought = Trigger::Drought(DroughtTrigger {
+            area: point(),
+            min_index: 3,
+            min_duration_days: 60,
+            window,
+        });
+        assert!(drought.is_triggered(
+            15,
+            &measurements(&[("drought_index", 3), ("duration_days", 90)])
+        ));
+        assert!(!drought.is_triggered(15, &measurements(&[("drought_index", 4)])));
+    }
+
+    #[test]
+    fn invalid_areas() {
+        let polygon = ProtectedArea::Polygon(vec![
+            Coordinate {
+                latitude: 0,
+                longitude: 0,
+            };
+            2
+        ]);
+        assert!(matches!(
+            polygon.validate(),
+            Err(HurricaneOracleError::InvalidPolygon)
+        ));
+        let point = ProtectedArea::Point(Coordinate {
+            latitude: 0,
+            longitude: 181_000_000,
+        });
+        assert!(matches!(
+            point.validate(),
+            Err(HurricaneOracleError::InvalidCoordinate)
+        ));
+    }
+}
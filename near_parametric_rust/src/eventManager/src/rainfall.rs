@@ -0,0 +1,47 @@
+use crate::peril::{at_least, PerilTrigger, ProtectedArea, TimeWindow};
+use crate::HurricaneOracleError;
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+///#Description
+///
+/// the conditions under which excess rainfall triggers a policy. nodes report the `rainfall`
+/// accumulated over the area in millimeters
+///
+/// #Fields
+///
+/// *`area` the protected `ProtectedArea`
+/// *`min_rainfall` the lowest accumulated rainfall in millimeters that triggers
+/// *`accumulation_hours` the period the nodes accumulate rainfall over
+/// *`window` when the rainfall must occur
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct RainfallTrigger {
+    pub area: ProtectedArea,
+    pub min_rainfall: u32,
+    pub accumulation_hours: u32,
+    pub window: TimeWindow,
+}
+
+impl PerilTrigger for RainfallTrigger {
+    fn validate(&self) -> Result<(), HurricaneOracleError> {
+        if self.min_rainfall == 0 || self.accumulation_hours == 0 {
+            return Err(HurricaneOracleError::InvalidThreshold);
+        }
+        self.area.validate()?;
+        self.window.validate()
+    }
+
+    fn window(&self) -> &TimeWindow {
+        &self.window
+    }
+
+    fn area(&self) -> &ProtectedArea {
+        &self.area
+    }
+
+    fn matches(&self, measurements: &HashMap<String, u32>) -> bool {
+        at_least(measurements, "rainfall", self.min_rainfall)
+    }
+}
@@ -0,0 +1,323 @@
+use crate::peril::{Coordinate, Peril};
+use crate::HurricaneOracleError;
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::AccountId;
+use std::collections::HashMap;
+
+///how far apart, in microdegrees of latitude or longitude, two tracks of the same peril may be
+///and still be the same event
+pub const DEDUP_DISTANCE: i32 = 1_000_000;
+
+///#Description
+///
+/// one observation of an event's position
+///
+/// #Fields
+///
+/// *`timestamp` when it was observed, in nanoseconds
+/// *`position` the center of the event, e.g. the eye of a hurricane or an epicenter
+/// *`measurements` readings at that time, e.g. `wind_speed` or `pressure`
+/// *`reporters` the nodes that reported this exact observation. set by the oracle, not by the
+/// report
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct TrackPoint {
+    pub timestamp: u64,
+    pub position: Coordinate,
+    pub measurements: HashMap<String, u32>,
+    #[serde(default)]
+    pub reporters: Vec<AccountId>,
+}
+
+impl TrackPoint {
+    ///is `other` the same observation, whoever reported it
+    fn is_same_observation(&self, other: &TrackPoint) -> bool {
+        self.timestamp == other.timestamp
+            && self.position == other.position
+            && self.measurements == other.measurements
+    }
+}
+
+///#Description
+///
+/// a catastrophe the oracle has reported, as kept in the event registry
+///
+/// #Fields
+///
+/// *`id` the event's unique id
+/// *`peril` the kind of catastrophe
+/// *`name` the public name, e.g. `Ian`. empty for unnamed events
+/// *`start` the first observation in nanoseconds
+/// *`end` the last observation in nanoseconds
+/// *`severity` the peak value of each severity attribute, e.g. `category` or `magnitude`
+/// *`track` the observations ordered by time. nodes that disagree on an observation each add
+/// their own point for its timestamp
+/// *`reporters` the nodes that reported the event. set by the oracle, not by the report
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct EventRecord {
+    pub id: String,
+    pub peril: Peril,
+    pub name: String,
+    pub start: u64,
+    pub end: u64,
+    pub severity: HashMap<String, u32>,
+    pub track: Vec<TrackPoint>,
+    #[serde(default)]
+    pub reporters: Vec<AccountId>,
+}
+
+impl EventRecord {
+    pub fn validate(&self) -> Result<(), HurricaneOracleError> {
+        if self.id.is_empty() || self.start > self.end {
+            return Err(HurricaneOracleError::InvalidEvent);
+        }
+        if !self.track.iter().all(|point| point.position.is_valid()) {
+            return Err(HurricaneOracleError::InvalidCoordinate);
+        }
+        Ok(())
+    }
+
+    ///#Description
+    ///
+    /// is `other`, reported under a different id, the same event. both must be of the same peril
+    /// and overlap in time, and then either share a name or have tracks that come close
+    pub fn is_duplicate_of(&self, other: &EventRecord) -> bool {
+        if self.peril != other.peril || !self.overlaps(other.start, other.end) {
+            return false;
+        }
+        let name = normalized_name(&self.name);
+        if !name.is_empty() && name == normalized_name(&other.name) {
+            return true;
+        }
+        self.track.iter().any(|point| {
+            other
+                .track
+                .iter()
+                .any(|other_point| is_near(&point.position, &other_point.position))
+        })
+    }
+
+    ///does the event overlap the period from `start` to `end`, both inclusive
+    pub fn overlaps(&self, start: u64, end: u64) -> bool {
+        self.start <= end && start <= self.end
+    }
+
+    ///does any track point fall in the box from `south_west` to `north_east`
+    pub fn is_in_region(&self, south_west: &Coordinate, north_east: &Coordinate) -> bool {
+        self.track.iter().any(|point| {
+            (south_west.latitude..=north_east.latitude).contains(&point.position.latitude)
+                && (south_west.longitude..=north_east.longitude).contains(&point.position.longitude)
+        })
+    }
+
+    ///#Description
+    ///
+    /// the track points that at least `quorum` of the `eligible` nodes reported, ordered by time.
+    /// only these are evaluated against requests
+    pub fn confirmed_track(&self, quorum: u64, eligible: &[AccountId]) -> Vec<TrackPoint> {
+        self.track
+            .iter()
+            .filter(|point| {
+                let confirmations = point
+                    .reporters
+                    .iter()
+                    .filter(|reporter| eligible.contains(reporter))
+                    .count() as u64;
+                confirmations >= quorum
+            })
+            .cloned()
+            .collect()
+    }
+
+    ///#Description
+    ///
+    /// fold a later report of the same event into this record. the period widens, severities
+    /// keep their peak and track points are added in time order. a point that repeats an
+    /// observation adds its reporters to it, and a node backs one observation per timestamp
+    pub fn merge(&mut self, report: EventRecord) -> Result<(), HurricaneOracleError> {
+        if report.peril != self.peril {
+            return Err(HurricaneOracleError::EventPerilMismatch);
+        }
+        if self.name.is_empty() {
+            self.name = report.name;
+        }
+        self.start = self.start.min(report.start);
+        self.end = self.end.max(report.end);
+        for (attribute, value) in report.severity.into_iter() {
+            let peak = self.severity.entry(attribute).or_insert(value);
+            *peak = (*peak).max(value);
+        }
+        for mut point in report.track.into_iter() {
+            let timestamp = point.timestamp;
+            point.reporters.retain(|reporter| {
+                !self.track.iter().any(|existing| {
+                    existing.timestamp == timestamp && existing.reporters.contains(reporter)
+                })
+            });
+            if point.reporters.is_empty() {
+                continue;
+            }
+            match self
+                .track
+                .iter_mut()
+                .find(|existing| existing.is_same_observation(&point))
+            {
+                Some(existing) => existing.reporters.extend(point.reporters),
+                None => self.track.push(point),
+            }
+        }
+        self.track.sort_by_key(|point| point.timestamp);
+        for reporter in report.reporters.into_iter() {
+            if !self.reporters.contains(&reporter) {
+                self.reporters.push(reporter);
+            }
+        }
+        Ok(())
+    }
+}
+
+fn normalized_name(name: &str) -> String {
+    name.trim().to_lowercase()
+}
+
+fn is_near(first: &Coordinate, second: &Coordinate) -> bool {
+    (first.latitude - second.latitude).abs() <= DEDUP_DISTANCE
+        && (first.longitude - second.longitude).abs() <= DEDUP_DISTANCE
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn point(timestamp: u64, latitude: i32, longitude: i32) -> TrackPoint {
+        TrackPoint {
+            timestamp,
+            position: Coordinate {
+                latitude,
+                longitude,
+            },
+            measurements: HashMap::new(),
+            reporters: vec!["node_a.near".to_string()],
+        }
+    }
+
+    fn record(id: &str, name: &str, track: Vec<TrackPoint>) -> EventRecord {
+        let mut severity = HashMap::new();
+        severity.insert("category".to_string(), 3);
+        EventRecord {
+            id: id.to_string(),
+            peril: Peril::Hurricane,
+            name: name.to_string(),
+            start: track.first().unwrap().timestamp,
+            end: track.last().unwrap().timestamp,
+            severity,
+            track,
+            reporters: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn duplicates() {
+        let ian = record("al092022", "Ian", vec![point(10, 25_000_000, -82_000_000)]);
+        let renamed = record(
+            "ian-2022",
+            " IAN ",
+            vec![point(10, 30_000_000, -70_000_000)],
+        );
+        assert!(renamed.is_duplicate_of(&ian));
+        let unnamed = record("x", "", vec![point(10, 25_500_000, -81_500_000)]);
+        assert!(unnamed.is_duplicate_of(&ian));
+        let elsewhere = record("y", "", vec![point(10, 10_000_000, -50_000_000)]);
+        assert!(!elsewhere.is_duplicate_of(&ian));
+        let later = record("z", "Ian", vec![point(20, 25_000_000, -82_000_000)]);
+        assert!(!later.is_duplicate_of(&ian));
+    }
+
+    #[test]
+    fn merge_keeps_peaks_and_orders_track() {
+        let mut ian = record("al092022", "Ian", vec![point(20, 25_000_000, -82_000_000)]);
+        let mut update = record("al092022", "", vec![point(10, 24_000_000, -83_000_000)]);
+        update.severity.insert("category".to_string(), 4);
+        ian.merge(update).unwrap();
+        assert_eq!((ian.start, ian.end), (10, 20));
+        assert_eq!(ian.severity.get("category"), Some(&4));
+        assert_eq!(ian.track[0].timestamp, 10);
+        assert_eq!(ian.name, "Ian".to_string());
+    }
+
+    fn reported_by(mut point: TrackPoint, node: &str) -> TrackPoint {
+        point.reporters = vec![node.to_string()];
+        point
+    }
+
+    #[test]
+    fn merge_adds_reporters_to_the_same_observation() {
+        let mut ian = record("al092022", "Ian", vec![point(10, 25_000_000, -82_000_000)]);
+        let update = record(
+            "al092022",
+            "",
+            vec![reported_by(point(10, 25_000_000, -82_000_000), "node_b.near")],
+        );
+        ian.merge(update).unwrap();
+        assert_eq!(ian.track.len(), 1);
+        assert_eq!(
+            ian.track[0].reporters,
+            vec!["node_a.near".to_string(), "node_b.near".to_string()]
+        );
+    }
+
+    #[test]
+    fn a_node_backs_one_observation_per_timestamp() {
+        let mut ian = record("al092022", "Ian", vec![point(10, 25_000_000, -82_000_000)]);
+        let moved = record("al092022", "", vec![point(10, 20_000_000, -70_000_000)]);
+        ian.merge(moved).unwrap();
+        assert_eq!(ian.track.len(), 1);
+        let disputed = record(
+            "al092022",
+            "",
+            vec![reported_by(point(10, 20_000_000, -70_000_000), "node_b.near")],
+        );
+        ian.merge(disputed).unwrap();
+        assert_eq!(ian.track.len(), 2);
+        assert_eq!(ian.track[1].reporters, vec!["node_b.near".to_string()]);
+    }
+
+    #[test]
+    fn only_points_a_quorum_reported_are_confirmed() {
+        let mut ian = record("al092022", "Ian", vec![point(10, 25_000_000, -82_000_000)]);
+        let update = record(
+            "al092022",
+            "",
+            vec![
+                reported_by(point(10, 25_000_000, -82_000_000), "node_b.near"),
+                reported_by(point(20, 20_000_000, -70_000_000), "node_b.near"),
+            ],
+        );
+        ian.merge(update).unwrap();
+        let nodes = vec!["node_a.near".to_string(), "node_b.near".to_string()];
+        let confirmed = ian.confirmed_track(2, &nodes);
+        assert_eq!(confirmed.len(), 1);
+        assert_eq!(confirmed[0].timestamp, 10);
+        assert_eq!(ian.confirmed_track(1, &nodes).len(), 2);
+        assert!(ian.confirmed_track(2, &nodes[..1]).is_empty());
+    }
+
+    #[test]
+    fn queries() {
+        let ian = record("al092022", "Ian", vec![point(10, 25_000_000, -82_000_000)]);
+        assert!(ian.overlaps(0, 10));
+        assert!(!ian.overlaps(11, 20));
+        let south_west = Coordinate {
+            latitude: 24_000_000,
+            longitude: -83_000_000,
+        };
+        let north_east = Coordinate {
+            latitude: 26_000_000,
+            longitude: -80_000_000,
+        };
+        assert!(ian.is_in_region(&south_west, &north_east));
+        assert!(!ian.is_in_region(&north_east, &south_west));
+    }
+}
@@ -0,0 +1,190 @@
+use crate::peril::{Coordinate, ProtectedArea};
+use crate::HurricaneOracleError;
+use location::{geohash, Location};
+
+///the geohash length requests are indexed by, cells of about 156km × 156km
+pub const INDEX_PRECISION: usize = 3;
+///the most cells a request's area may cover
+pub const MAX_AREA_CELLS: usize = 16;
+///the most cells a region query may cover
+pub const MAX_QUERY_CELLS: usize = 256;
+
+///#Description
+///
+/// a latitude/longitude box in microdegrees
+#[derive(Debug, PartialEq, Clone)]
+pub struct Region {
+    pub south_west: Coordinate,
+    pub north_east: Coordinate,
+}
+
+impl Region {
+    ///#Description
+    ///
+    /// the region between two corners. boxes crossing the antimeridian are not supported
+    pub fn new(
+        south_west: Coordinate,
+        north_east: Coordinate,
+    ) -> Result<Self, HurricaneOracleError> {
+        if !south_west.is_valid() || !north_east.is_valid() {
+            return Err(HurricaneOracleError::InvalidCoordinate);
+        }
+        if south_west.latitude > north_east.latitude || south_west.longitude > north_east.longitude
+        {
+            return Err(HurricaneOracleError::InvalidRegion);
+        }
+        Ok(Self {
+            south_west,
+            north_east,
+        })
+    }
+
+    ///the smallest region containing an area
+    pub fn around(area: &ProtectedArea) -> Self {
+        let vertices: Vec<&Coordinate> = match area {
+            ProtectedArea::Point(point) => vec![point],
+            ProtectedArea::Polygon(vertices) => vertices.iter().collect(),
+        };
+        Self::around_points(vertices.into_iter())
+    }
+
+    ///the smallest region containing some points
+    pub fn around_points<'a>(mut points: impl Iterator<Item = &'a Coordinate>) -> Self {
+        let first = points.next().cloned().unwrap_or(Coordinate {
+            latitude: 0,
+            longitude: 0,
+        });
+        let mut region = Self {
+            south_west: first.clone(),
+            north_east: first,
+        };
+        for point in points {
+            region.south_west.latitude = region.south_west.latitude.min(point.latitude);
+            region.south_west.longitude = region.south_west.longitude.min(point.longitude);
+            region.north_east.latitude = region.north_east.latitude.max(point.latitude);
+            region.north_east.longitude = region.north_east.longitude.max(point.longitude);
+        }
+        region
+    }
+
+    ///the region grown by `padding` microdegrees on every side, within the valid bounds
+    pub fn padded(&self, padding: u32) -> Self {
+        let padding = padding.min(i32::MAX as u32) as i32;
+        Self {
+            south_west: Coordinate {
+                latitude: self.south_west.latitude.saturating_sub(padding).max(-90_000_000),
+                longitude: self.south_west.longitude.saturating_sub(padding).max(-180_000_000),
+            },
+            north_east: Coordinate {
+                latitude: self.north_east.latitude.saturating_add(padding).min(90_000_000),
+                longitude: self.north_east.longitude.saturating_add(padding).min(180_000_000),
+            },
+        }
+    }
+
+    pub fn intersects(&self, other: &Region) -> bool {
+        self.south_west.latitude <= other.north_east.latitude
+            && other.south_west.latitude <= self.north_east.latitude
+            && self.south_west.longitude <= other.north_east.longitude
+            && other.south_west.longitude <= self.north_east.longitude
+    }
+
+    ///#Description
+    ///
+    /// the `INDEX_PRECISION` geohash cells covering the region, south to north and west to
+    /// east. `None` when there are more than `max_cells`
+    pub fn cells(&self, max_cells: usize) -> Option<Vec<String>> {
+        let south_west = to_location(&self.south_west);
+        let north_east = to_location(&self.north_east);
+        let first_cell = geohash::encode(&south_west, INDEX_PRECISION).ok()?;
+        let first = geohash::decode(&first_cell).ok()?;
+        let height = first.north - first.south;
+        let width = first.east - first.west;
+        let rows = ((north_east.latitude() - first.south) / height).floor() as usize + 1;
+        let columns = ((north_east.longitude() - first.west) / width).floor() as usize + 1;
+        if rows.checked_mul(columns)? > max_cells {
+            return None;
+        }
+        let mut cells: Vec<String> = Vec::with_capacity(rows * columns);
+        for row in 0..rows {
+            let latitude = (first.south + (row as f64 + 0.5) * height).min(90.0);
+            for column in 0..columns {
+                let longitude = (first.west + (column as f64 + 0.5) * width).min(179.999_999);
+                let cell = Location::new(latitude, longitude)
+                    .and_then(|center| geohash::encode(&center, INDEX_PRECISION))
+                    .ok()?;
+                if !cells.contains(&cell) {
+                    cells.push(cell);
+                }
+            }
+        }
+        Some(cells)
+    }
+}
+
+///#Description
+///
+/// the cells a request for `area` is indexed under
+pub fn area_cells(area: &ProtectedArea) -> Result<Vec<String>, HurricaneOracleError> {
+    Region::around(area)
+        .cells(MAX_AREA_CELLS)
+        .ok_or(HurricaneOracleError::AreaTooLarge)
+}
+
+fn to_location(coordinate: &Coordinate) -> Location {
+    // coordinates are validated before they reach the index
+    Location::new(
+        coordinate.latitude as f64 / 1_000_000.0,
+        coordinate.longitude as f64 / 1_000_000.0,
+    )
+    .unwrap_or_else(|_| Location::new(0.0, 0.0).unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn coordinate(latitude: i32, longitude: i32) -> Coordinate {
+        Coordinate {
+            latitude,
+            longitude,
+        }
+    }
+
+    #[test]
+    fn point_is_one_cell() {
+        let miami = ProtectedArea::Point(coordinate(25_761_680, -80_191_790));
+        assert_eq!(area_cells(&miami).unwrap(), vec!["dhw".to_string()]);
+    }
+
+    #[test]
+    fn region_cells() {
+        // about 3° × 3° around florida's south east coast
+        let region = Region::new(
+            coordinate(24_000_000, -82_000_000),
+            coordinate(27_000_000, -79_000_000),
+        )
+        .unwrap();
+        let cells = region.cells(MAX_QUERY_CELLS).unwrap();
+        assert!(cells.contains(&"dhw".to_string()));
+        assert!(cells.len() >= 4 && cells.len() <= 16);
+        assert!(region.cells(2).is_none());
+        assert_eq!(
+            Region::new(coordinate(1, 0), coordinate(0, 0)),
+            Err(HurricaneOracleError::InvalidRegion)
+        );
+    }
+
+    #[test]
+    fn large_polygons_are_refused() {
+        let continent = ProtectedArea::Polygon(vec![
+            coordinate(25_000_000, -125_000_000),
+            coordinate(49_000_000, -125_000_000),
+            coordinate(49_000_000, -67_000_000),
+        ]);
+        assert_eq!(
+            area_cells(&continent),
+            Err(HurricaneOracleError::AreaTooLarge)
+        );
+    }
+}
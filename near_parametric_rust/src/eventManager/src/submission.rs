@@ -0,0 +1,133 @@
+use crate::HurricaneOracleError;
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::serde::{Deserialize, Serialize};
+
+const HOUR: u64 = 3_600_000_000_000;
+
+///#Description
+///
+/// how late an event may be reported for a policy. mirrors `SubmissionRules` in
+/// `PolicyManager`. an event must begin within the coverage period, and its triggering
+/// observation must fall within the coverage period or the grace window after it
+///
+/// #Fields
+///
+/// *`grace_period` how long after the coverage ends an event that began during it may still
+/// trigger the policy, in nanoseconds
+/// *`reporting_deadline` how long after the event ends, or the grace window closes if that
+/// is earlier, the event may still be reported, in nanoseconds
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct SubmissionRules {
+    pub grace_period: u64,
+    pub reporting_deadline: u64,
+}
+
+impl Default for SubmissionRules {
+    fn default() -> Self {
+        Self {
+            grace_period: 72 * HOUR,
+            reporting_deadline: 30 * 24 * HOUR,
+        }
+    }
+}
+
+impl SubmissionRules {
+    ///the last moment an event may be reported for coverage ending at `coverage_end`
+    pub fn closes_at(&self, coverage_end: u64) -> u64 {
+        coverage_end
+            .saturating_add(self.grace_period)
+            .saturating_add(self.reporting_deadline)
+    }
+
+    ///#Description
+    ///
+    /// may an event be reported for a policy
+    ///
+    /// #Parameters
+    ///
+    /// *`coverage` the start and end of the policy's coverage
+    /// *`event_period` the start and end of the event
+    /// *`date` when the event triggered the policy
+    /// *`now` the time of the report
+    pub fn check(
+        &self,
+        coverage: [u64; 2],
+        event_period: [u64; 2],
+        date: u64,
+        now: u64,
+    ) -> Result<(), HurricaneOracleError> {
+        let [coverage_start, coverage_end] = coverage;
+        let [event_start, event_end] = event_period;
+        if event_start < coverage_start {
+            return Err(HurricaneOracleError::EventBeforeCoverage);
+        }
+        if event_start > coverage_end {
+            return Err(HurricaneOracleError::EventAfterCoverage);
+        }
+        let grace_end = coverage_end.saturating_add(self.grace_period);
+        if date < coverage_start || date > grace_end {
+            return Err(HurricaneOracleError::ObservationOutsideCoverage);
+        }
+        if now > event_end.min(grace_end).saturating_add(self.reporting_deadline) {
+            return Err(HurricaneOracleError::SubmissionTooLate);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COVERAGE: [u64; 2] = [1_000 * HOUR, 2_000 * HOUR];
+
+    #[test]
+    fn events_must_begin_during_coverage() {
+        let rules = SubmissionRules::default();
+        let now = 2_000 * HOUR;
+        assert_eq!(
+            rules.check(COVERAGE, [999 * HOUR, 1_100 * HOUR], 1_050 * HOUR, now),
+            Err(HurricaneOracleError::EventBeforeCoverage)
+        );
+        assert_eq!(
+            rules.check(COVERAGE, [2_001 * HOUR, 2_010 * HOUR], 2_005 * HOUR, now),
+            Err(HurricaneOracleError::EventAfterCoverage)
+        );
+        assert_eq!(
+            rules.check(COVERAGE, [1_500 * HOUR, 1_510 * HOUR], 1_505 * HOUR, now),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn grace_window() {
+        let rules = SubmissionRules::default();
+        let event = [1_990 * HOUR, 2_100 * HOUR];
+        let now = 2_100 * HOUR;
+        assert_eq!(rules.check(COVERAGE, event, 2_072 * HOUR, now), Ok(()));
+        assert_eq!(
+            rules.check(COVERAGE, event, 2_073 * HOUR, now),
+            Err(HurricaneOracleError::ObservationOutsideCoverage)
+        );
+    }
+
+    #[test]
+    fn reporting_deadline() {
+        let rules = SubmissionRules::default();
+        let event = [1_500 * HOUR, 1_510 * HOUR];
+        let deadline = 1_510 * HOUR + 30 * 24 * HOUR;
+        assert_eq!(rules.check(COVERAGE, event, 1_505 * HOUR, deadline), Ok(()));
+        assert_eq!(
+            rules.check(COVERAGE, event, 1_505 * HOUR, deadline + 1),
+            Err(HurricaneOracleError::SubmissionTooLate)
+        );
+        // an event that outlasts the grace window is due from when the window closes
+        let long_event = [1_990 * HOUR, 3_000 * HOUR];
+        let grace_deadline = rules.closes_at(COVERAGE[1]);
+        assert_eq!(
+            rules.check(COVERAGE, long_event, 2_000 * HOUR, grace_deadline + 1),
+            Err(HurricaneOracleError::SubmissionTooLate)
+        );
+    }
+}
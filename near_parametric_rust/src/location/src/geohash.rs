@@ -0,0 +1,154 @@
+use crate::{BoundingBox, Direction, Location, LocationError};
+
+///the longest geohash, about 3.7cm × 1.9cm
+pub const MAX_PRECISION: usize = 12;
+
+const ALPHABET: &[u8; 32] = b"0123456789bcdefghjkmnpqrstuvwxyz";
+
+///# description
+/// the geohash of the cell containing `location`
+///
+///# parameters
+/// * `precision` the number of characters, 1 to 12. every character divides the cell by 32
+pub fn encode(location: &Location, precision: usize) -> Result<String, LocationError> {
+	if precision == 0 || precision > MAX_PRECISION {
+		return Err(LocationError::InvalidPrecision);
+	}
+	let location = location.normalized()?;
+	let (mut south, mut north) = (-90.0, 90.0);
+	let (mut west, mut east) = (-180.0, 180.0);
+	let mut geohash = String::with_capacity(precision);
+	let mut is_longitude = true;
+	for _ in 0..precision {
+		let mut index = 0;
+		for _ in 0..5 {
+			index <<= 1;
+			if is_longitude {
+				let middle = (west + east) / 2.0;
+				if location.longitude() >= middle {
+					index |= 1;
+					west = middle;
+				} else {
+					east = middle;
+				}
+			} else {
+				let middle = (south + north) / 2.0;
+				if location.latitude() >= middle {
+					index |= 1;
+					south = middle;
+				} else {
+					north = middle;
+				}
+			}
+			is_longitude = !is_longitude;
+		}
+		geohash.push(ALPHABET[index] as char);
+	}
+	Ok(geohash)
+}
+
+///# description
+/// the cell of a geohash
+pub fn decode(geohash: &str) -> Result<BoundingBox, LocationError> {
+	if geohash.is_empty() || geohash.len() > MAX_PRECISION {
+		return Err(LocationError::InvalidPrecision);
+	}
+	let mut cell = BoundingBox {
+		south: -90.0,
+		west: -180.0,
+		north: 90.0,
+		east: 180.0,
+	};
+	let mut is_longitude = true;
+	for character in geohash.bytes() {
+		let index = ALPHABET
+			.iter()
+			.position(|symbol| *symbol == character.to_ascii_lowercase())
+			.ok_or(LocationError::InvalidGeohash)?;
+		for bit in (0..5).rev() {
+			let is_set = (index >> bit) & 1 == 1;
+			if is_longitude {
+				let middle = (cell.west + cell.east) / 2.0;
+				if is_set {
+					cell.west = middle;
+				} else {
+					cell.east = middle;
+				}
+			} else {
+				let middle = (cell.south + cell.north) / 2.0;
+				if is_set {
+					cell.south = middle;
+				} else {
+					cell.north = middle;
+				}
+			}
+			is_longitude = !is_longitude;
+		}
+	}
+	Ok(cell)
+}
+
+///# description
+/// the adjacent cell of the same precision. longitudes wrap around the antimeridian
+///
+///# returns
+/// `None` beyond a pole
+pub fn neighbor(geohash: &str, direction: Direction) -> Result<Option<String>, LocationError> {
+	let cell = decode(geohash)?;
+	let center = cell.center();
+	let (north_steps, east_steps) = direction.offsets();
+	let latitude = center.latitude() + north_steps as f64 * (cell.north - cell.south);
+	if latitude.abs() > 90.0 {
+		return Ok(None);
+	}
+	let mut longitude = center.longitude() + east_steps as f64 * (cell.east - cell.west);
+	if longitude >= 180.0 {
+		longitude -= 360.0;
+	} else if longitude < -180.0 {
+		longitude += 360.0;
+	}
+	let neighbor_center = Location {
+		latitude,
+		longitude,
+	};
+	encode(&neighbor_center, geohash.len()).map(Some)
+}
+
+///# description
+/// the up to eight cells around a geohash, clockwise from north
+pub fn neighbors(geohash: &str) -> Result<Vec<String>, LocationError> {
+	let mut cells: Vec<String> = Vec::with_capacity(8);
+	for direction in Direction::ALL.iter() {
+		if let Some(cell) = neighbor(geohash, *direction)? {
+			if !cells.contains(&cell) {
+				cells.push(cell);
+			}
+		}
+	}
+	Ok(cells)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn encode_and_decode() {
+		let location = Location::new(57.64911, 10.40744).unwrap();
+		assert_eq!(encode(&location, 11).unwrap(), "u4pruydqqvj".to_string());
+		let cell = decode("u4pruydqqvj").unwrap();
+		assert!(cell.contains(&location));
+		assert_eq!(encode(&location, 0), Err(LocationError::InvalidPrecision));
+		assert_eq!(decode("u4pa"), Err(LocationError::InvalidGeohash));
+	}
+
+	#[test]
+	fn neighbor_cells() {
+		assert_eq!(neighbor("ezs42", Direction::North).unwrap(), Some("ezs48".to_string()));
+		assert_eq!(neighbor("ezs42", Direction::East).unwrap(), Some("ezs43".to_string()));
+		assert_eq!(neighbors("ezs42").unwrap().len(), 8);
+		// across the antimeridian and beyond the pole
+		assert_eq!(neighbor("8", Direction::West).unwrap(), Some("x".to_string()));
+		assert_eq!(neighbor("b", Direction::North).unwrap(), None);
+	}
+}
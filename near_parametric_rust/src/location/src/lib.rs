@@ -0,0 +1,201 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::serde::{Deserialize, Serialize};
+use std::fmt;
+
+pub mod geohash;
+pub mod morton;
+
+///the geohash precision used to key locations, about 4.8m × 4.8m
+pub const DEFAULT_GEOHASH_PRECISION: usize = 9;
+
+///locations are snapped to this many steps per degree so equal assets compare equal
+const MICRODEGREES: f64 = 1_000_000.0;
+
+///# description
+///
+/// the location of an insured asset, shared by the `QuoteManager` and `PolicyManager`.
+///
+///# fields
+/// * `latitude` degrees north, -90 to 90
+/// * `longitude` degrees east, -180 (inclusive) to 180 (exclusive)
+///
+///# notes
+/// contracts call `normalized` on every location they receive, so that the same asset is stored
+/// with the same coordinates whichever contract it came through
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Location {
+	latitude: f64,
+	longitude: f64,
+}
+
+///# description
+///
+/// the reasons a location or cell code can be refused
+#[derive(Debug, PartialEq, Eq)]
+pub enum LocationError {
+	InvalidLatitude,
+	InvalidLongitude,
+	InvalidPrecision,
+	InvalidGeohash,
+}
+
+impl fmt::Display for LocationError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match *self {
+			LocationError::InvalidLatitude => write!(f, "INVALID_LATITUDE"),
+			LocationError::InvalidLongitude => write!(f, "INVALID_LONGITUDE"),
+			LocationError::InvalidPrecision => write!(f, "INVALID_PRECISION"),
+			LocationError::InvalidGeohash => write!(f, "INVALID_GEOHASH"),
+		}
+	}
+}
+
+impl Location {
+	/// a normalized location
+	pub fn new(latitude: f64, longitude: f64) -> Result<Self, LocationError> {
+		Self { latitude, longitude }.normalized()
+	}
+
+	pub fn latitude(&self) -> f64 {
+		self.latitude
+	}
+
+	pub fn longitude(&self) -> f64 {
+		self.longitude
+	}
+
+	///# description
+	/// validate the bounds and snap to the microdegree grid. a longitude of 180 wraps to -180
+	pub fn normalized(&self) -> Result<Self, LocationError> {
+		if !self.latitude.is_finite() || self.latitude.abs() > 90.0 {
+			return Err(LocationError::InvalidLatitude);
+		}
+		if !self.longitude.is_finite() || self.longitude.abs() > 180.0 {
+			return Err(LocationError::InvalidLongitude);
+		}
+		let longitude = if self.longitude == 180.0 { -180.0 } else { self.longitude };
+		Ok(Self {
+			latitude: snap(self.latitude),
+			longitude: snap(longitude),
+		})
+	}
+
+	/// the location in integer microdegrees, (latitude, longitude)
+	pub fn microdegrees(&self) -> (i32, i32) {
+		(
+			(self.latitude * MICRODEGREES).round() as i32,
+			(self.longitude * MICRODEGREES).round() as i32,
+		)
+	}
+
+	/// the geohash of the cell containing the location
+	pub fn geohash(&self, precision: usize) -> Result<String, LocationError> {
+		geohash::encode(self, precision)
+	}
+
+	/// the Morton (Z-order) code of the cell containing the location, `level` bits per axis
+	pub fn morton(&self, level: u8) -> Result<u64, LocationError> {
+		morton::encode(self, level)
+	}
+}
+
+fn snap(degrees: f64) -> f64 {
+	(degrees * MICRODEGREES).round() / MICRODEGREES
+}
+
+///# description
+///
+/// the cell of a geohash or Morton code, in degrees
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct BoundingBox {
+	pub south: f64,
+	pub west: f64,
+	pub north: f64,
+	pub east: f64,
+}
+
+impl BoundingBox {
+	pub fn center(&self) -> Location {
+		Location {
+			latitude: (self.south + self.north) / 2.0,
+			longitude: (self.west + self.east) / 2.0,
+		}
+	}
+
+	pub fn contains(&self, location: &Location) -> bool {
+		self.south <= location.latitude
+			&& location.latitude <= self.north
+			&& self.west <= location.longitude
+			&& location.longitude <= self.east
+	}
+}
+
+///# description
+///
+/// the eight cells around a cell, for searches that must not miss assets near a cell edge
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Direction {
+	North,
+	NorthEast,
+	East,
+	SouthEast,
+	South,
+	SouthWest,
+	West,
+	NorthWest,
+}
+
+impl Direction {
+	pub const ALL: [Direction; 8] = [
+		Direction::North,
+		Direction::NorthEast,
+		Direction::East,
+		Direction::SouthEast,
+		Direction::South,
+		Direction::SouthWest,
+		Direction::West,
+		Direction::NorthWest,
+	];
+
+	/// the (north, east) cell steps of the direction
+	fn offsets(self) -> (i64, i64) {
+		match self {
+			Direction::North => (1, 0),
+			Direction::NorthEast => (1, 1),
+			Direction::East => (0, 1),
+			Direction::SouthEast => (-1, 1),
+			Direction::South => (-1, 0),
+			Direction::SouthWest => (-1, -1),
+			Direction::West => (0, -1),
+			Direction::NorthWest => (1, -1),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn normalization() {
+		let location = Location::new(25.76168049, -80.19179).unwrap();
+		assert_eq!(location.latitude(), 25.76168);
+		assert_eq!(location.microdegrees(), (25_761_680, -80_191_790));
+		assert_eq!(Location::new(0.0, 180.0).unwrap().longitude(), -180.0);
+		assert_eq!(Location::new(90.5, 0.0), Err(LocationError::InvalidLatitude));
+		assert_eq!(Location::new(0.0, f64::NAN), Err(LocationError::InvalidLongitude));
+	}
+
+	#[test]
+	fn same_asset_same_cell() {
+		let quoted = Location::new(25.7616801, -80.1917902).unwrap();
+		let activated = Location::new(25.76168, -80.19179).unwrap();
+		assert_eq!(quoted, activated);
+		assert_eq!(
+			quoted.geohash(DEFAULT_GEOHASH_PRECISION),
+			activated.geohash(DEFAULT_GEOHASH_PRECISION)
+		);
+	}
+}
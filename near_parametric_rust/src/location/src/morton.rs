@@ -0,0 +1,155 @@
+use crate::{BoundingBox, Direction, Location, LocationError};
+
+///the most bits per axis a Morton code can hold
+pub const MAX_LEVEL: u8 = 32;
+
+///# description
+/// the Morton (Z-order) code of the cell containing `location`. longitude bits take the even
+/// positions and latitude bits the odd ones, so nearby cells share a prefix
+///
+///# parameters
+/// * `level` bits per axis, 1 to 32. every level divides the cell by 4
+pub fn encode(location: &Location, level: u8) -> Result<u64, LocationError> {
+	validate_level(level)?;
+	let location = location.normalized()?;
+	let x = quantize(location.longitude() + 180.0, 360.0, level);
+	let y = quantize(location.latitude() + 90.0, 180.0, level);
+	Ok(interleave(x, y))
+}
+
+///# description
+/// the cell of a Morton code
+pub fn decode(code: u64, level: u8) -> Result<BoundingBox, LocationError> {
+	validate_level(level)?;
+	let (x, y) = deinterleave(code);
+	let cells = (1u64 << level) as f64;
+	let width = 360.0 / cells;
+	let height = 180.0 / cells;
+	Ok(BoundingBox {
+		south: -90.0 + y as f64 * height,
+		west: -180.0 + x as f64 * width,
+		north: -90.0 + (y + 1) as f64 * height,
+		east: -180.0 + (x + 1) as f64 * width,
+	})
+}
+
+///# description
+/// reduce a code to a coarser level, i.e. its enclosing cell
+pub fn parent(code: u64, level: u8, parent_level: u8) -> Result<u64, LocationError> {
+	validate_level(level)?;
+	if parent_level == 0 || parent_level > level {
+		return Err(LocationError::InvalidPrecision);
+	}
+	Ok(code >> (2 * (level - parent_level) as u32))
+}
+
+///# description
+/// the adjacent cell of the same level. longitudes wrap around the antimeridian
+///
+///# returns
+/// `None` beyond a pole
+pub fn neighbor(code: u64, level: u8, direction: Direction) -> Result<Option<u64>, LocationError> {
+	validate_level(level)?;
+	let (x, y) = deinterleave(code);
+	let cells = 1i64 << level;
+	let (north_steps, east_steps) = direction.offsets();
+	let y = y as i64 + north_steps;
+	if y < 0 || y >= cells {
+		return Ok(None);
+	}
+	let x = (x as i64 + east_steps).rem_euclid(cells);
+	Ok(Some(interleave(x as u32, y as u32)))
+}
+
+///# description
+/// the up to eight cells around a code, clockwise from north
+pub fn neighbors(code: u64, level: u8) -> Result<Vec<u64>, LocationError> {
+	let mut cells: Vec<u64> = Vec::with_capacity(8);
+	for direction in Direction::ALL.iter() {
+		if let Some(cell) = neighbor(code, level, *direction)? {
+			if !cells.contains(&cell) {
+				cells.push(cell);
+			}
+		}
+	}
+	Ok(cells)
+}
+
+fn validate_level(level: u8) -> Result<(), LocationError> {
+	if level == 0 || level > MAX_LEVEL {
+		return Err(LocationError::InvalidPrecision);
+	}
+	Ok(())
+}
+
+fn quantize(offset: f64, range: f64, level: u8) -> u32 {
+	let cells = (1u64 << level) as f64;
+	let cell = (offset / range * cells).floor() as u64;
+	cell.min((1u64 << level) - 1) as u32
+}
+
+fn interleave(x: u32, y: u32) -> u64 {
+	spread(x) | (spread(y) << 1)
+}
+
+fn deinterleave(code: u64) -> (u32, u32) {
+	(compact(code), compact(code >> 1))
+}
+
+/// put the bits of `value` on the even positions
+fn spread(value: u32) -> u64 {
+	let mut bits = value as u64;
+	bits = (bits | (bits << 16)) & 0x0000_ffff_0000_ffff;
+	bits = (bits | (bits << 8)) & 0x00ff_00ff_00ff_00ff;
+	bits = (bits | (bits << 4)) & 0x0f0f_0f0f_0f0f_0f0f;
+	bits = (bits | (bits << 2)) & 0x3333_3333_3333_3333;
+	(bits | (bits << 1)) & 0x5555_5555_5555_5555
+}
+
+/// gather the even positioned bits of `code`
+fn compact(code: u64) -> u32 {
+	let mut bits = code & 0x5555_5555_5555_5555;
+	bits = (bits | (bits >> 1)) & 0x3333_3333_3333_3333;
+	bits = (bits | (bits >> 2)) & 0x0f0f_0f0f_0f0f_0f0f;
+	bits = (bits | (bits >> 4)) & 0x00ff_00ff_00ff_00ff;
+	bits = (bits | (bits >> 8)) & 0x0000_ffff_0000_ffff;
+	((bits | (bits >> 16)) & 0x0000_0000_ffff_ffff) as u32
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn encode_and_decode() {
+		let location = Location::new(25.76168, -80.19179).unwrap();
+		for level in [1, 8, 16, 32].iter() {
+			let code = encode(&location, *level).unwrap();
+			assert!(decode(code, *level).unwrap().contains(&location));
+		}
+		// the south west quadrant is 0, the north east 3
+		assert_eq!(encode(&Location::new(-45.0, -90.0).unwrap(), 1), Ok(0));
+		assert_eq!(encode(&Location::new(45.0, 90.0).unwrap(), 1), Ok(3));
+		assert_eq!(encode(&location, 33), Err(LocationError::InvalidPrecision));
+	}
+
+	#[test]
+	fn parents_share_prefixes() {
+		let location = Location::new(25.76168, -80.19179).unwrap();
+		let fine = encode(&location, 20).unwrap();
+		assert_eq!(parent(fine, 20, 10), encode(&location, 10));
+	}
+
+	#[test]
+	fn neighbor_cells() {
+		let code = encode(&Location::new(0.1, 0.1).unwrap(), 4).unwrap();
+		let east = neighbor(code, 4, Direction::East).unwrap().unwrap();
+		assert!(decode(east, 4).unwrap().contains(&Location::new(0.1, 22.6).unwrap()));
+		assert_eq!(neighbors(code, 4).unwrap().len(), 8);
+		let west_edge = encode(&Location::new(0.1, -179.9).unwrap(), 4).unwrap();
+		let wrapped = neighbor(west_edge, 4, Direction::West).unwrap().unwrap();
+		assert!(decode(wrapped, 4).unwrap().contains(&Location::new(0.1, 179.9).unwrap()));
+		let pole = encode(&Location::new(89.9, 0.0).unwrap(), 4).unwrap();
+		assert_eq!(neighbor(pole, 4, Direction::North), Ok(None));
+	}
+}
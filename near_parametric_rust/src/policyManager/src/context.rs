@@ -0,0 +1,316 @@
+use crate::{Amount, AmountError, ComputedLoss, CoverageTerms, LossBreakdown, Obligation, Payment};
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::AccountId;
+
+///# description
+///
+/// everything a `Policy` holds for one `Event`. payouts do not stack within an event: `percent_due`
+/// only ratchets upward and a new `ComputedLoss` is owed the increase over it alone.
+/// example: a category 3 storm triggers a 30 percent payout. once the same storm is a category 4
+/// the payout is 50 percent, so the new loss is 20 percent of `balance_snapshot` and the event pays
+/// 50 percent in total rather than 80. the `CoverageTerms` are applied to the event's total, so its
+/// deductible is only taken once
+///
+///# fields
+/// * `date` {`u64`} when the event first triggered the policy. selects the policy year of its aggregate limit
+/// * `percent_due` {`u8`} the highest payout percent computed for the event, whatever the client decided
+/// * `balance_snapshot` {`Amount`} the `Policy` balance when the event was first reported. every
+///   payout percent of the event is a percent of it
+/// * `claims_manager` {`AccountId`} the `ClaimsManager` that computes the event's losses
+/// * `computed_losses` {`Vec<ComputedLoss>`} losses waiting for the client to accept or reject them
+/// * `rejected_losses` {`Vec<ComputedLoss>`} losses the client rejected
+/// * `obligations` {`Vec<Obligation>`} accepted losses the issuer has yet to pay
+/// * `payments` {`Vec<Payment>`} obligations the issuer has paid
+/// * `breakdowns` {`Vec<LossBreakdown>`} how each raised payout percent was reduced to the amount owed
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Debug, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct EventContext {
+	date: u64,
+	percent_due: u8,
+	balance_snapshot: Amount,
+	claims_manager: AccountId,
+	computed_losses: Vec<ComputedLoss>,
+	rejected_losses: Vec<ComputedLoss>,
+	obligations: Vec<Obligation>,
+	payments: Vec<Payment>,
+	breakdowns: Vec<LossBreakdown>,
+}
+
+impl EventContext {
+	pub fn new(date: u64, balance_snapshot: Amount, claims_manager: AccountId) -> Self {
+		Self {
+			date,
+			percent_due: 0,
+			balance_snapshot,
+			claims_manager,
+			computed_losses: Vec::new(),
+			rejected_losses: Vec::new(),
+			obligations: Vec::new(),
+			payments: Vec::new(),
+			breakdowns: Vec::new(),
+		}
+	}
+
+	pub fn date(&self) -> u64 {
+		self.date
+	}
+
+	pub fn percent_due(&self) -> u8 {
+		self.percent_due
+	}
+
+	pub fn balance_snapshot(&self) -> &Amount {
+		&self.balance_snapshot
+	}
+
+	pub fn breakdowns(&self) -> &Vec<LossBreakdown> {
+		&self.breakdowns
+	}
+
+	/// every loss of the event in any state
+	fn losses(&self) -> impl Iterator<Item = &ComputedLoss> {
+		self.computed_losses
+				.iter()
+				.chain(self.rejected_losses.iter())
+				.chain(self.obligations.iter().map(|obligation| &obligation.computed_loss))
+				.chain(self.payments.iter().map(|payment| &payment.obligation.computed_loss))
+	}
+
+	pub fn has_loss(&self, loss_id: &String) -> bool {
+		self.losses().any(|computed_loss| computed_loss.identity.id == *loss_id)
+	}
+
+	/// the minor units owed for the event, paid or not. rejected losses are not owed
+	pub fn owed(&self) -> u128 {
+		self.computed_losses
+				.iter()
+				.chain(self.obligations.iter().map(|obligation| &obligation.computed_loss))
+				.chain(self.payments.iter().map(|payment| &payment.obligation.computed_loss))
+				.map(|computed_loss| computed_loss.calculations.amount_due.minor_units())
+				.sum()
+	}
+
+	/// the minor units of the losses waiting for a client decision or for payment
+	pub fn outstanding(&self) -> u128 {
+		self.computed_losses
+				.iter()
+				.chain(self.obligations.iter().map(|obligation| &obligation.computed_loss))
+				.map(|computed_loss| computed_loss.calculations.amount_due.minor_units())
+				.sum()
+	}
+
+	/// losses waiting for a client decision or for payment
+	pub fn has_outstanding_losses(&self) -> bool {
+		!self.computed_losses.is_empty() || !self.obligations.is_empty()
+	}
+
+	pub fn computed_loss(&self, loss_id: &String) -> Option<&ComputedLoss> {
+		self.computed_losses
+				.iter()
+				.find(|computed_loss| computed_loss.identity.id == *loss_id)
+	}
+
+	pub fn obligation(&self, loss_id: &String) -> Option<&Obligation> {
+		self.obligations
+				.iter()
+				.find(|obligation| obligation.computed_loss.identity.id == *loss_id)
+	}
+
+	/// record `computed_loss` if it raises `percent_due`. the event's loss at the new percent is reduced
+	/// by `terms` and by what the event already owed at `percent_due`, then capped by `aggregate_remaining`
+	/// and `pending_balance`. a `LossBreakdown` of the reduction is kept either way
+	///
+	///# returns
+	/// the recorded loss with its `amount_due` set to the amount owed, or `None` if the event already
+	/// pays at least as much or nothing is owed
+	pub fn record(
+		&mut self,
+		mut computed_loss: ComputedLoss,
+		terms: &CoverageTerms,
+		aggregate_remaining: Option<u128>,
+		pending_balance: &Amount,
+	) -> Result<Option<ComputedLoss>, AmountError> {
+		let payout_percent = computed_loss.calculations.payout_percent;
+		if payout_percent <= self.percent_due {
+			return Ok(None);
+		}
+		let gross_loss = self.balance_snapshot.checked_percent(payout_percent)?.minor_units();
+		let previous_gross_loss = self.balance_snapshot.checked_percent(self.percent_due)?.minor_units();
+		let deductible = terms.deductible_on(gross_loss);
+		let event_limit_excess = terms.event_limit_excess(gross_loss);
+		let previous_net_loss = terms.net_loss(previous_gross_loss);
+		let net_increase = terms.net_loss(gross_loss).saturating_sub(previous_net_loss);
+		let within_aggregate = aggregate_remaining.map_or(net_increase, |remaining| net_increase.min(remaining));
+		let amount_owed = within_aggregate.min(pending_balance.minor_units());
+		let currency = self.balance_snapshot.currency().clone();
+		let amount = |minor_units: u128| Amount::new(minor_units, currency.clone());
+		self.breakdowns.push(LossBreakdown {
+			loss_id: computed_loss.identity.id.clone(),
+			previous_percent: self.percent_due,
+			payout_percent,
+			computed_amount: computed_loss.calculations.amount_due.clone(),
+			gross_loss: amount(gross_loss),
+			deductible: amount(deductible),
+			event_limit_excess: amount(event_limit_excess),
+			previous_net_loss: amount(previous_net_loss),
+			aggregate_excess: amount(net_increase - within_aggregate),
+			balance_excess: amount(within_aggregate - amount_owed),
+			amount_owed: amount(amount_owed),
+		});
+		self.percent_due = payout_percent;
+		if amount_owed == 0 {
+			return Ok(None);
+		}
+		computed_loss.calculations.amount_due = amount(amount_owed);
+		self.computed_losses.push(computed_loss.clone());
+		Ok(Some(computed_loss))
+	}
+
+	/// move a computed loss to `obligations`
+	pub fn accept(&mut self, loss_id: &String, now: u64) -> Option<Obligation> {
+		let index = self
+				.computed_losses
+				.iter()
+				.position(|computed_loss| computed_loss.identity.id == *loss_id)?;
+		let obligation = Obligation {
+			computed_loss: self.computed_losses.remove(index),
+			contract_update_time: now,
+		};
+		self.obligations.push(obligation.clone());
+		Some(obligation)
+	}
+
+	/// move a computed loss to `rejected_losses`. `percent_due` is kept
+	pub fn reject(&mut self, loss_id: &String) -> Option<ComputedLoss> {
+		let index = self
+				.computed_losses
+				.iter()
+				.position(|computed_loss| computed_loss.identity.id == *loss_id)?;
+		let computed_loss = self.computed_losses.remove(index);
+		self.rejected_losses.push(computed_loss.clone());
+		Some(computed_loss)
+	}
+
+	/// move an obligation to `payments`
+	pub fn pay(&mut self, loss_id: &String, payment_proof: String, now: u64) -> Option<Payment> {
+		let index = self
+				.obligations
+				.iter()
+				.position(|obligation| obligation.computed_loss.identity.id == *loss_id)?;
+		let payment = Payment {
+			contract_update_time: now,
+			payment_proof,
+			obligation: self.obligations.remove(index),
+		};
+		self.payments.push(payment.clone());
+		Some(payment)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{LossCalculation, LossIdentity, OracleMetadata};
+	use std::collections::HashMap;
+
+	fn computed_loss(payout_percent: u8, amount_due: u128) -> ComputedLoss {
+		ComputedLoss {
+			identity: LossIdentity {
+				id: format!("loss-{}", payout_percent),
+				event_id: "al092022".to_string(),
+				policy_id: "policy".to_string(),
+				client_id: "client".to_string(),
+				issuer_id: "issuer".to_string(),
+			},
+			oracle_data: OracleMetadata {
+				triggering_values: HashMap::new(),
+				claims_manager: "claims.near".parse().unwrap(),
+				oracle: "oracle.near".parse().unwrap(),
+			},
+			calculations: LossCalculation {
+				payout_percent,
+				amount_due: Amount::new(amount_due, "USD".to_string()),
+			},
+		}
+	}
+
+	fn usd(minor_units: u128) -> Amount {
+		Amount::new(minor_units, "USD".to_string())
+	}
+
+	fn context() -> EventContext {
+		EventContext::new(0, usd(100_000), "claims.near".parse().unwrap())
+	}
+
+	fn record(context: &mut EventContext, computed_loss: ComputedLoss) -> Option<ComputedLoss> {
+		context
+				.record(computed_loss, &CoverageTerms::default(), None, &usd(100_000))
+				.unwrap()
+	}
+
+	#[test]
+	fn escalation_only_owes_the_increase() {
+		let mut context = context();
+		let first = record(&mut context, computed_loss(30, 30_000)).unwrap();
+		assert_eq!(first.calculations.amount_due.minor_units(), 30_000);
+		// computed against a stale percent of 0, so the claims manager asked for the full 50 percent
+		let second = record(&mut context, computed_loss(50, 50_000)).unwrap();
+		assert_eq!(second.calculations.amount_due.minor_units(), 20_000);
+		assert_eq!(context.percent_due(), 50);
+		assert!(record(&mut context, computed_loss(40, 10_000)).is_none());
+		assert!(record(&mut context, computed_loss(50, 20_000)).is_none());
+		assert_eq!(context.owed(), 50_000);
+	}
+
+	#[test]
+	fn losses_move_through_their_states() {
+		let mut context = context();
+		record(&mut context, computed_loss(30, 30_000));
+		record(&mut context, computed_loss(50, 20_000));
+		let loss_30 = "loss-30".to_string();
+		let loss_50 = "loss-50".to_string();
+		assert!(context.accept(&loss_30, 1).is_some());
+		assert!(context.reject(&loss_50).is_some());
+		assert!(context.reject(&loss_50).is_none());
+		assert!(context.has_outstanding_losses());
+		assert_eq!(context.pay(&loss_30, "proof".to_string(), 2).unwrap().payment_proof, "proof");
+		assert!(!context.has_outstanding_losses());
+		assert!(context.has_loss(&loss_30) && context.has_loss(&loss_50));
+		// a rejected loss still counts towards the ratchet but is not owed
+		assert_eq!(context.percent_due(), 50);
+		assert_eq!(context.owed(), 30_000);
+	}
+
+	#[test]
+	fn terms_reduce_the_amount_owed() {
+		let mut context = context();
+		let terms = CoverageTerms {
+			deductible: Some(usd(10_000)),
+			event_limit: Some(usd(50_000)),
+			..Default::default()
+		};
+		// 10 percent does not exceed the deductible
+		let below_deductible = context.record(computed_loss(10, 10_000), &terms, None, &usd(100_000));
+		assert!(below_deductible.unwrap().is_none());
+		let loss = context
+				.record(computed_loss(50, 40_000), &terms, Some(25_000), &usd(100_000))
+				.unwrap()
+				.unwrap();
+		assert_eq!(loss.calculations.amount_due.minor_units(), 25_000);
+		let loss = context
+				.record(computed_loss(100, 50_000), &terms, None, &usd(5_000))
+				.unwrap()
+				.unwrap();
+		assert_eq!(loss.calculations.amount_due.minor_units(), 5_000);
+		let breakdown = context.breakdowns().last().unwrap();
+		assert_eq!(breakdown.gross_loss.minor_units(), 100_000);
+		assert_eq!(breakdown.deductible.minor_units(), 10_000);
+		assert_eq!(breakdown.event_limit_excess.minor_units(), 40_000);
+		assert_eq!(breakdown.previous_net_loss.minor_units(), 40_000);
+		assert_eq!(breakdown.aggregate_excess.minor_units(), 0);
+		assert_eq!(breakdown.balance_excess.minor_units(), 5_000);
+		assert_eq!(context.breakdowns().len(), 3);
+		assert_eq!(context.breakdowns()[1].aggregate_excess.minor_units(), 15_000);
+	}
+}
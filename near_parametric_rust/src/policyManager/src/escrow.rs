@@ -0,0 +1,393 @@
+use crate::{Amount, AmountError, LossIdentity};
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::json_types::U128;
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::AccountId;
+
+/// a `capital_requirement` of `10_000` basis points locks capital for the full outstanding limits
+pub const FULL_CAPITAL_REQUIREMENT: u16 = 10_000;
+
+///# description
+///
+/// how an issuer pays the obligations of its policies
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+#[serde(rename_all = "snake_case")]
+pub enum SettlementMode {
+	///the issuer pays off-chain and a policy manager posts the proof with `post_payment_made`
+	OffChain,
+	///`PolicyManager` pays from the issuer's escrow once the client accepts a loss
+	OnChain,
+}
+
+///# description
+///
+/// what an issuer's capital is held in. mirrors `PremiumAsset` in `QuoteManager`
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+#[serde(rename_all = "snake_case")]
+pub enum CapitalAsset {
+	///yoctoNEAR attached to `deposit_capital`
+	Near,
+	///a NEP-141 token sent with `ft_transfer_call`
+	FungibleToken(AccountId),
+}
+
+///# description
+///
+/// the asset that backs and pays obligations in a currency
+///
+///# fields
+/// * `asset` {`CapitalAsset`} NEAR or the token contract
+/// * `units_per_minor_unit` {`U128`} asset units per minor unit of the currency. e.g. `10000` for a
+///   stablecoin with 6 decimals paying `USD` amounts kept in cents
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct SettlementToken {
+	pub asset: CapitalAsset,
+	pub units_per_minor_unit: U128,
+}
+
+impl SettlementToken {
+	pub fn to_token_units(&self, amount: &Amount) -> Result<u128, AmountError> {
+		amount
+				.minor_units()
+				.checked_mul(self.units_per_minor_unit.0)
+				.ok_or(AmountError::Overflow)
+	}
+}
+
+///# description
+///
+/// the capital a policy holds in its issuer's escrow. it only ever shrinks: with the balance while the
+/// policy is live or can still be reported a late event, then to what its open losses still owe once
+/// the reporting window closes, or it is cancelled or exhausted
+///
+///# fields
+/// * `account_id` {`AccountId`} the issuer account whose escrow holds the capital
+/// * `asset` {`CapitalAsset`} the `SettlementToken` asset of the policy's currency at activation
+/// * `units_per_minor_unit` {`U128`} the rate of `asset` at activation
+/// * `requirement` {`u16`} the `capital_requirement` at activation, in basis points
+/// * `exposure` {`U128`} the policy's outstanding limit in units of `asset`
+/// * `locked` {`U128`} `requirement` of `exposure`, rounded up
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct CapitalLock {
+	pub account_id: AccountId,
+	pub asset: CapitalAsset,
+	pub units_per_minor_unit: U128,
+	pub requirement: u16,
+	pub exposure: U128,
+	pub locked: U128,
+}
+
+impl CapitalLock {
+	pub fn new(
+		account_id: AccountId,
+		settlement_token: &SettlementToken,
+		requirement: u16,
+		outstanding: &Amount,
+	) -> Result<Self, AmountError> {
+		let exposure = settlement_token.to_token_units(outstanding)?;
+		Ok(Self {
+			account_id,
+			asset: settlement_token.asset.clone(),
+			units_per_minor_unit: settlement_token.units_per_minor_unit,
+			requirement,
+			exposure: U128(exposure),
+			locked: U128(required_capital(exposure, requirement)?),
+		})
+	}
+
+	/// the lock of a policy whose outstanding limit is now `outstanding` minor units. never larger than `self`
+	pub fn resized(&self, outstanding: u128) -> Result<Self, AmountError> {
+		let exposure = outstanding
+				.checked_mul(self.units_per_minor_unit.0)
+				.ok_or(AmountError::Overflow)?
+				.min(self.exposure.0);
+		Ok(Self {
+			exposure: U128(exposure),
+			locked: U128(required_capital(exposure, self.requirement)?.min(self.locked.0)),
+			..self.clone()
+		})
+	}
+}
+
+/// `requirement` basis points of `exposure`, rounded up
+fn required_capital(exposure: u128, requirement: u16) -> Result<u128, AmountError> {
+	let scaled = exposure
+			.checked_mul(requirement as u128)
+			.ok_or(AmountError::Overflow)?;
+	let divisor = FULL_CAPITAL_REQUIREMENT as u128;
+	Ok(scaled / divisor + if scaled % divisor == 0 { 0 } else { 1 })
+}
+
+///# description
+///
+/// an issuer's capital in one asset
+///
+///# fields
+/// * `asset` {`CapitalAsset`} what the capital is held in
+/// * `balance` {`U128`} asset units held
+/// * `exposure` {`U128`} the outstanding limits of the issuer's policies backed by the asset
+/// * `locked` {`U128`} the part of `balance` the policies hold. it can only pay their obligations
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Capital {
+	pub asset: CapitalAsset,
+	pub balance: U128,
+	pub exposure: U128,
+	pub locked: U128,
+}
+
+impl Capital {
+	/// the part of `balance` that can back new policies or be withdrawn
+	pub fn free(&self) -> u128 {
+		self.balance.0.saturating_sub(self.locked.0)
+	}
+
+	/// `balance` over `exposure` in basis points. `None` without exposure
+	pub fn solvency_ratio(&self) -> Option<u32> {
+		if self.exposure.0 == 0 {
+			return None;
+		}
+		let ratio = self.balance.0.saturating_mul(FULL_CAPITAL_REQUIREMENT as u128) / self.exposure.0;
+		Some(ratio.min(u32::MAX as u128) as u32)
+	}
+}
+
+///# description
+///
+/// how well an issuer's capital in one asset covers the limits of its policies
+///
+///# fields
+/// * `capital` {`Capital`} the balance, exposure and locked capital
+/// * `free` {`U128`} what can back new policies or be withdrawn
+/// * `solvency_ratio` {`Option<u32>`} `balance` over `exposure` in basis points. `10000` covers every
+///   outstanding limit. `None` without exposure
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Solvency {
+	pub capital: Capital,
+	pub free: U128,
+	pub solvency_ratio: Option<u32>,
+}
+
+///# description
+///
+/// the capital an issuer deposited to back its policies and pay their obligations
+///
+///# fields
+/// * `settlement_mode` {`SettlementMode`} how the issuer's obligations are paid
+/// * `capital` {`Vec<Capital>`} the capital held per asset
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct IssuerEscrow {
+	pub settlement_mode: SettlementMode,
+	capital: Vec<Capital>,
+}
+
+impl Default for IssuerEscrow {
+	fn default() -> Self {
+		Self {
+			settlement_mode: SettlementMode::OffChain,
+			capital: Vec::new(),
+		}
+	}
+}
+
+impl IssuerEscrow {
+	fn capital(&self, asset: &CapitalAsset) -> Option<&Capital> {
+		self.capital.iter().find(|capital| capital.asset == *asset)
+	}
+
+	fn capital_mut(&mut self, asset: &CapitalAsset) -> &mut Capital {
+		let index = match self.capital.iter().position(|capital| capital.asset == *asset) {
+			Some(index) => index,
+			None => {
+				self.capital.push(Capital {
+					asset: asset.clone(),
+					balance: U128(0),
+					exposure: U128(0),
+					locked: U128(0),
+				});
+				self.capital.len() - 1
+			}
+		};
+		&mut self.capital[index]
+	}
+
+	pub fn balance(&self, asset: &CapitalAsset) -> u128 {
+		self.capital(asset).map_or(0, |capital| capital.balance.0)
+	}
+
+	pub fn free(&self, asset: &CapitalAsset) -> u128 {
+		self.capital(asset).map_or(0, Capital::free)
+	}
+
+	pub fn solvency(&self) -> Vec<Solvency> {
+		self.capital
+				.iter()
+				.map(|capital| Solvency {
+					capital: capital.clone(),
+					free: U128(capital.free()),
+					solvency_ratio: capital.solvency_ratio(),
+				})
+				.collect()
+	}
+
+	pub fn deposit(&mut self, asset: &CapitalAsset, amount: u128) -> Result<(), AmountError> {
+		let capital = self.capital_mut(asset);
+		capital.balance = U128(capital.balance.0.checked_add(amount).ok_or(AmountError::Overflow)?);
+		Ok(())
+	}
+
+	/// take `amount` out of the free capital. locked capital never leaves this way
+	pub fn withdraw(&mut self, asset: &CapitalAsset, amount: u128) -> Result<(), AmountError> {
+		if amount > self.free(asset) {
+			return Err(AmountError::NegativeBalance);
+		}
+		let capital = self.capital_mut(asset);
+		capital.balance = U128(capital.balance.0 - amount);
+		Ok(())
+	}
+
+	/// take `amount` out to pay an obligation. the capital locked by the policies exists to pay their
+	/// obligations, so all of `balance` can be used. the locks shrink once the payment is recorded
+	pub fn pay(&mut self, asset: &CapitalAsset, amount: u128) -> Result<(), AmountError> {
+		let capital = self.capital_mut(asset);
+		capital.balance = U128(capital.balance.0.checked_sub(amount).ok_or(AmountError::NegativeBalance)?);
+		Ok(())
+	}
+
+	/// lock capital for a new policy. refused if the free capital does not cover it
+	pub fn lock(&mut self, capital_lock: &CapitalLock) -> Result<(), AmountError> {
+		if capital_lock.locked.0 > self.free(&capital_lock.asset) {
+			return Err(AmountError::NegativeBalance);
+		}
+		let capital = self.capital_mut(&capital_lock.asset);
+		capital.exposure = U128(
+			capital
+					.exposure
+					.0
+					.checked_add(capital_lock.exposure.0)
+					.ok_or(AmountError::Overflow)?,
+		);
+		capital.locked = U128(capital.locked.0 + capital_lock.locked.0);
+		Ok(())
+	}
+
+	/// release what a policy's lock no longer holds after it shrank from `previous` to `current`
+	pub fn release(&mut self, previous: &CapitalLock, current: &CapitalLock) {
+		let capital = self.capital_mut(&previous.asset);
+		let exposure = previous.exposure.0.saturating_sub(current.exposure.0);
+		let locked = previous.locked.0.saturating_sub(current.locked.0);
+		capital.exposure = U128(capital.exposure.0.saturating_sub(exposure));
+		capital.locked = U128(capital.locked.0.saturating_sub(locked));
+	}
+}
+
+///# description
+///
+/// a transfer paying an obligation that has yet to resolve. its amount has left the escrow
+///
+///# fields
+/// * `identity` {`LossIdentity`} the loss of the obligation
+/// * `escrow` {`AccountId`} the issuer account whose escrow pays
+/// * `asset` {`CapitalAsset`} what is sent
+/// * `amount` {`U128`} the asset units sent
+/// * `receiver` {`AccountId`} the client's payout account
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Debug, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Settlement {
+	pub identity: LossIdentity,
+	pub escrow: AccountId,
+	pub asset: CapitalAsset,
+	pub amount: U128,
+	pub receiver: AccountId,
+}
+
+impl Settlement {
+	/// the `payment_proof` of the `Payment` the settlement creates
+	pub fn payment_proof(&self) -> String {
+		match &self.asset {
+			CapitalAsset::Near => format!("transfer:{}:{}", self.receiver, self.amount.0),
+			CapitalAsset::FungibleToken(token) => {
+				format!("ft_transfer:{}:{}:{}", token, self.receiver, self.amount.0)
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn usdc() -> SettlementToken {
+		SettlementToken {
+			asset: CapitalAsset::FungibleToken("usdc.near".parse().unwrap()),
+			units_per_minor_unit: U128(10_000),
+		}
+	}
+
+	fn usd(minor_units: u128) -> Amount {
+		Amount::new(minor_units, "USD".to_string())
+	}
+
+	#[test]
+	fn token_units() {
+		assert_eq!(usdc().to_token_units(&usd(12_345)), Ok(123_450_000));
+		assert_eq!(usdc().to_token_units(&usd(u128::MAX)), Err(AmountError::Overflow));
+	}
+
+	#[test]
+	fn escrow_never_goes_negative() {
+		let asset = usdc().asset;
+		let mut escrow = IssuerEscrow::default();
+		assert_eq!(escrow.withdraw(&asset, 1), Err(AmountError::NegativeBalance));
+		escrow.deposit(&asset, 500).unwrap();
+		escrow.withdraw(&asset, 200).unwrap();
+		assert_eq!(escrow.balance(&asset), 300);
+		assert_eq!(escrow.withdraw(&asset, 301), Err(AmountError::NegativeBalance));
+		assert_eq!(escrow.pay(&asset, 301), Err(AmountError::NegativeBalance));
+		assert_eq!(escrow.balance(&asset), 300);
+	}
+
+	#[test]
+	fn locked_capital_never_leaves() {
+		let issuer: AccountId = "issuer.near".parse().unwrap();
+		let asset = usdc().asset;
+		let mut escrow = IssuerEscrow::default();
+		escrow.deposit(&asset, 1_000_000).unwrap();
+		// half of a 1000.00 USD limit
+		let capital_lock = CapitalLock::new(issuer.clone(), &usdc(), 5_000, &usd(100_000)).unwrap();
+		assert_eq!(capital_lock.locked, U128(500_000_000));
+		assert_eq!(escrow.lock(&capital_lock), Err(AmountError::NegativeBalance));
+		escrow.deposit(&asset, 499_000_000).unwrap();
+		escrow.lock(&capital_lock).unwrap();
+		assert_eq!(escrow.free(&asset), 0);
+		assert_eq!(escrow.withdraw(&asset, 1), Err(AmountError::NegativeBalance));
+		assert_eq!(escrow.solvency()[0].solvency_ratio, Some(5_000));
+		// obligations can still be paid from locked capital
+		escrow.pay(&asset, 100_000_000).unwrap();
+		let paid = capital_lock.resized(80_000).unwrap();
+		escrow.release(&capital_lock, &paid);
+		assert_eq!(escrow.free(&asset), 0);
+		let expired = paid.resized(0).unwrap();
+		escrow.release(&paid, &expired);
+		assert_eq!(escrow.free(&asset), 400_000_000);
+		assert_eq!(escrow.solvency()[0].solvency_ratio, None);
+	}
+
+	#[test]
+	fn locks_round_up_and_never_grow() {
+		let cents = SettlementToken {
+			units_per_minor_unit: U128(1),
+			..usdc()
+		};
+		let capital_lock = CapitalLock::new("issuer.near".parse().unwrap(), &cents, 3_333, &usd(3)).unwrap();
+		assert_eq!(capital_lock.locked, U128(1));
+		assert_eq!(capital_lock.resized(5).unwrap(), capital_lock);
+		assert_eq!(capital_lock.resized(0).unwrap().locked, U128(0));
+	}
+}
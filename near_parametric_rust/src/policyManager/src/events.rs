@@ -0,0 +1,188 @@
+use near_sdk::serde::Serialize;
+use near_sdk::json_types::U128;
+use near_sdk::{env, AccountId};
+
+use crate::{
+	Amount, CapitalAsset, CapitalLock, LossIdentity, PolicyStatus, Settlement, SettlementMode, SettlementToken,
+	SubmissionRules,
+};
+
+/// the NEP-297 `standard` of every event emitted by `PolicyManager`
+pub const EVENT_STANDARD: &str = "parametric_policy_manager";
+/// bumped whenever the shape of an existing event's `data` changes
+pub const EVENT_STANDARD_VERSION: &str = "1.0.0";
+
+///# description
+///
+/// a state change in `PolicyManager` that is logged for indexers as `EVENT_JSON:{...}`
+/// following NEP-297. `event` is the snake case name of the variant and `data` a one element array
+#[derive(Serialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+#[serde(tag = "event", content = "data", rename_all = "snake_case")]
+pub enum PolicyManagerEvent {
+	PolicyActivated([PolicyActivatedData; 1]),
+	PolicyStatusChanged([PolicyStatusChangedData; 1]),
+	LossComputed([LossData; 1]),
+	LossAccepted([LossData; 1]),
+	LossRejected([LossData; 1]),
+	ObligationCreated([LossData; 1]),
+	PaymentPosted([PaymentPostedData; 1]),
+	OracleAdded([AccountData; 1]),
+	OracleRemoved([AccountData; 1]),
+	PolicyActivatorAdded([AccountData; 1]),
+	PolicyActivatorRemoved([AccountData; 1]),
+	AdminChangeStarted([AdminChangeData; 1]),
+	AdminChangeCancelled([AdminChangeData; 1]),
+	AdminChanged([AdminChangeData; 1]),
+	SubmissionRulesChanged([SubmissionRulesData; 1]),
+	EscrowDeposited([EscrowData; 1]),
+	EscrowWithdrawn([EscrowData; 1]),
+	SettlementModeChanged([SettlementModeData; 1]),
+	SettlementTokenSet([SettlementTokenData; 1]),
+	PayoutAccountRegistered([PayoutAccountData; 1]),
+	ObligationSettlementStarted([Settlement; 1]),
+	ObligationSettlementFailed([Settlement; 1]),
+	CapitalLocked([CapitalLockData; 1]),
+	CapitalReleased([CapitalLockData; 1]),
+	CapitalRequirementChanged([CapitalRequirementData; 1]),
+}
+
+#[derive(Serialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct PolicyActivatedData {
+	pub policy_id: String,
+	pub client_id: String,
+	pub issuer_id: String,
+	pub max_payout: Amount,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct PolicyStatusChangedData {
+	pub policy_id: String,
+	pub from: PolicyStatus,
+	pub to: PolicyStatus,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct LossData {
+	pub identity: LossIdentity,
+	pub payout_percent: u8,
+	pub amount_due: Amount,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct PaymentPostedData {
+	pub identity: LossIdentity,
+	pub amount_paid: Amount,
+	pub payment_proof: String,
+	pub balance: Amount,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct AccountData {
+	pub account_id: AccountId,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct AdminChangeData {
+	pub master_admin: AccountId,
+	pub new_master_admin: Option<AccountId>,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct SubmissionRulesData {
+	pub rules: SubmissionRules,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct EscrowData {
+	pub account_id: AccountId,
+	pub asset: CapitalAsset,
+	pub amount: U128,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct SettlementModeData {
+	pub account_id: AccountId,
+	pub settlement_mode: SettlementMode,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct SettlementTokenData {
+	pub currency: String,
+	pub settlement_token: SettlementToken,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct PayoutAccountData {
+	pub client_id: String,
+	pub account_id: AccountId,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct CapitalLockData {
+	pub policy_id: String,
+	pub capital_lock: CapitalLock,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct CapitalRequirementData {
+	pub requirement: u16,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+struct EventLog<'a> {
+	standard: &'static str,
+	version: &'static str,
+	#[serde(flatten)]
+	event: &'a PolicyManagerEvent,
+}
+
+impl PolicyManagerEvent {
+	/// log the event as `EVENT_JSON:{...}`
+	pub fn emit(&self) {
+		env::log(self.to_log_string().as_bytes());
+	}
+
+	fn to_log_string(&self) -> String {
+		let log = EventLog {
+			standard: EVENT_STANDARD,
+			version: EVENT_STANDARD_VERSION,
+			event: self,
+		};
+		format!(
+			"EVENT_JSON:{}",
+			near_sdk::serde_json::to_string(&log).expect("ERR_EVENT_SERIALIZATION")
+		)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn log_follows_nep297() {
+		let event = PolicyManagerEvent::PolicyActivatorAdded([AccountData {
+			account_id: "quotes.near".parse().unwrap(),
+		}]);
+		assert_eq!(
+			event.to_log_string(),
+			"EVENT_JSON:{\"standard\":\"parametric_policy_manager\",\"version\":\"1.0.0\",\
+			\"event\":\"policy_activator_added\",\"data\":[{\"account_id\":\"quotes.near\"}]}"
+		);
+	}
+}
@@ -1,10 +1,48 @@
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
 use near_sdk::collections::UnorderedMap;
+use near_sdk::json_types::U128;
 use near_sdk::serde::{Deserialize, Serialize};
-use near_sdk::{env, ext_contract, near_bindgen, AccountId, BorshStorageKey, PanicOnDefault, Promise, PromiseResult};
+use near_sdk::{
+	env, ext_contract, near_bindgen, AccountId, BorshStorageKey, PanicOnDefault, Promise, PromiseOrValue, PromiseResult,
+};
 use std::collections::HashMap;
 //use rust_elgamal::CipherText;
 
+mod context;
+mod escrow;
+mod events;
+mod status;
+mod submission;
+mod terms;
+
+pub use amount::{Amount, AmountError};
+pub use coverage::CoverageTerms;
+pub use crate::context::EventContext;
+pub use crate::escrow::{
+	Capital, CapitalAsset, CapitalLock, IssuerEscrow, Settlement, SettlementMode, SettlementToken, Solvency,
+	FULL_CAPITAL_REQUIREMENT,
+};
+use crate::events::{
+	AccountData, AdminChangeData, CapitalLockData, CapitalRequirementData, EscrowData, LossData, PaymentPostedData,
+	PayoutAccountData, PolicyManagerEvent, PolicyActivatedData, PolicyStatusChangedData, SettlementModeData,
+	SettlementTokenData, SubmissionRulesData,
+};
+pub use crate::status::{PolicyStatus, StatusTransition};
+pub use crate::submission::{SubmissionError, SubmissionRules};
+pub use crate::terms::{LossBreakdown, POLICY_YEAR};
+pub use location::Location;
+
+/// gas attached to the `ClaimsManager`'s `compute_loss`
+const COMPUTE_LOSS_GAS: u64 = 20_000_000_000_000;
+/// gas attached to the `compute_loss_callback` callback
+const COMPUTE_LOSS_CALLBACK_GAS: u64 = 30_000_000_000_000;
+/// gas attached to `ft_transfer` when an obligation is settled or escrow withdrawn in a token
+const FT_TRANSFER_GAS: u64 = 10_000_000_000_000;
+/// gas attached to the `on_obligation_settled` callback
+const ON_OBLIGATION_SETTLED_GAS: u64 = 20_000_000_000_000;
+/// gas attached to the `on_escrow_withdrawn` callback
+const ON_ESCROW_WITHDRAWN_GAS: u64 = 10_000_000_000_000;
+
 #[derive(BorshStorageKey, BorshSerialize)]
 pub enum StorageKeys {
 	Policies,
@@ -14,6 +52,10 @@ pub enum StorageKeys {
 	LossConfirmationRequests,
 	ObligationsAwaitingPayment,
 	Clients,
+	Escrows,
+	SettlementTokens,
+	PayoutAccounts,
+	Settlements,
 }
 
 
@@ -35,8 +77,14 @@ pub struct Quote {
 	claims_manager: AccountId,
 	///the protection option that this quote represents
 	policy_type: u8,
+	///the version of the `policy_type` payout table this quote was sold under
+	product_version: u32,
 	///maximum value total value of the policy
-	max_payout: f64,
+	max_payout: Amount,
+	///the price of the policy, paid to the `QuoteManager` before activation
+	premium: Premium,
+	///deductible, limits and reinstatements applied to every loss
+	terms: CoverageTerms,
 	///the period that a policy will be valid.
 	coverage_period: [u64; 2],
 	/// the policy manager that activated this quote
@@ -45,6 +93,30 @@ pub struct Quote {
 	location: Location,
 }
 
+/// what a `Premium` is paid in. mirrors `PremiumAsset` in `QuoteManager`
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+#[serde(rename_all = "snake_case")]
+pub enum PremiumAsset {
+	Near,
+	FungibleToken(AccountId),
+}
+
+///# description
+///
+/// the price of a `Policy`. mirrors `Premium` in `QuoteManager`, which collects it and holds it for the
+/// issuer
+///
+///# fields
+/// * `asset` {`PremiumAsset`} what the premium is paid in
+/// * `amount` {`U128`} the premium in the smallest unit of `asset`
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Premium {
+	asset: PremiumAsset,
+	amount: U128,
+}
+
 /// a location being protected under a **Policy**
 ///
 /// # Fields
@@ -87,82 +159,6 @@ pub enum UserType {
 }
 
 
-///#Description
-///
-/// a confirmation object that gives the caller relevant information about the
-/// the transaction
-///
-/// #Fields
-/// `client_id` the unique client id
-#[derive(BorshDeserialize, BorshSerialize, Serialize,Deserialize, Debug, Clone)]
-#[serde(crate = "near_sdk::serde")]
-pub struct EventContext {
-	percent_due: u8,
-	balance_snapshot: f64,
-	payments: Vec<Payment>,
-	obligations: Vec<Obligation>,
-	rejected_losses: Vec<ComputedLoss>,
-	computed_losses: Vec<ComputedLoss>,
-	claims_manager: AccountId,
-}
-
-//TODO payments decrease balance, obligations decrease pending balance and computed_loss as well
-/// a construct that is sent to a `PolicyManager` so it can update its internal state relative to a particular `Event`
-/// in the context of a particular `Policy`
-///
-/// # fields
-/// * `policy_manager` which `PolicyManager` retains the `Policy` data
-/// * `policy_id` policy id
-/// * `event_id` event id
-/// * `max_payout_percent` the current percent payout for an `Event`
-/// * `max_possible_amount` the real value amount that is to be paid to the `Client` and is to be deducted from `Policy`
-/// balance. Is calculated from `max_payout_percent`
-/// * `payout_obligation` a `PayoutObligation` that is calculated by the `ClaimsManager` and is subsequently processed
-/// by the `PolicyManager`
-/// # notes
-/// - payout amounts do not stack. (not accumulative). if the `max_payout_percent` was 30 and circumstance for an
-/// event dictate that the event payout is to be 50 percent. then the total payout against a policy balance would be
-/// 50% and not 80% cumulatively.
-/// example: category 3 hits at distance x and the payout percent is 30. the same storm has grown to a category 4
-/// and the policy is to payout out at 50 percent. the previous `max_payout_percent` was 30 and has become 50.
-///
-#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Debug, Clone)]
-#[serde(crate = "near_sdk::serde")]
-pub struct EventContextUpdate {
-	policy_manager: AccountId,
-	policy_id: String,
-	event_id: String,
-	max_payout_percent: u8,
-	max_possible_payout: f64,
-	computed_loss: ComputedLoss,
-}
-
-///a quote is issued to a client. Quotes come after estimates. Estimates are not represented in this contract
-///
-/// # Fields
-///
-/// * `id` unique identifier
-/// * `issuer` an issuer is the party that is permitted to issue `Quotes` to `Clients`
-/// * `client` represents the potential purchaser of insurance
-/// * `claims_contract` The contract that maintains the logic that determines if a `PayoutObligation` is made
-///by an Oracle account
-/// * `policy_type` one of `N` different choices for `Policies`
-///* `max_payout` the maximum payout value of the [`Policy`]
-///* `coverage_period` the period of time that a [`Policy`] would be active and valid
-///* `policy_manager` Which policy manager will be responsible for maintaining an issued [`Policy`]
-///*  `payment_processor` is a party responsible for receiving `Client` payment off chain and subsequently
-/// binding a [`Policy`] by calling `issue_policy` on a `QuoteManager` contract
-/// * `location` the location of the asset that is being insured.
-///* `payout_authority` the party that is responsible for fulfilling `PayoutObligations`
-///* `premium` the amount that the `Client` must pay to bind a [`Policy`]
-#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Debug, Clone)]
-#[serde(crate = "near_sdk::serde")]
-pub struct Location {
-	latitude: f64,
-	longitude: f64,
-}
-
-
 /// # parameters
 /// * accept {`bool`} client's decision related to the loss
 /// * loss_ident {`LossIdentity`} relevant data  to query Loss
@@ -180,10 +176,10 @@ pub struct LossDecision {
 ///
 /// * `payout_obligation` the original `PayoutObligation`
 /// * `time_of_contract_notification` this represents the blocktime when the `payout_authority` notified
-///the contract of a successful payment (and provided a payment proof) to the `Client`
-///This does not represent the time the the `payout_authority` issued the payment to the `Client`
+///   the contract of a successful payment (and provided a payment proof) to the `Client`
+///   This does not represent the time the the `payout_authority` issued the payment to the `Client`
 /// * `payment_proof` a string that represents an on-chain record of some reference to an off-chain payment to a `Client`
-///for auditing purposes
+///   for auditing purposes
 #[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Debug, Clone)]
 #[serde(crate = "near_sdk::serde")]
 pub struct LossIdentity {
@@ -194,6 +190,46 @@ pub struct LossIdentity {
 	issuer_id: String,
 }
 
+///# description
+///
+/// an `Event` as reported by an oracle contract
+///
+///# fields
+/// * `id` the event's unique id
+/// * `peril` the kind of catastrophe
+/// * `oracle` the oracle contract that reported the event
+/// * `date` the date the event triggered the policy in nanoseconds
+/// * `start` when the event began in nanoseconds
+/// * `end` when the event ended, or was last observed, in nanoseconds
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Debug, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Event {
+	id: String,
+	peril: Peril,
+	oracle: AccountId,
+	date: u64,
+	start: u64,
+	end: u64,
+}
+
+/// the kind of natural catastrophe behind an `Event`. mirrors `Peril` in the oracle
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub enum Peril {
+	Hurricane,
+	Earthquake,
+	Rainfall,
+	Drought,
+}
+
+///# description
+///
+/// the oracle data behind a loss
+///
+///# fields
+/// * `triggering_values` the aggregated readings of the oracle nodes keyed by trigger name (e.g. `category`)
+/// * `claims_manager` the `ClaimsManager` that computes the loss
+/// * `oracle` the oracle contract that reported the values
 #[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Debug, Clone)]
 #[serde(crate = "near_sdk::serde")]
 pub struct OracleMetadata {
@@ -208,15 +244,16 @@ pub struct LossContext {
 	identity:LossIdentity,
 	oracle_data:OracleMetadata,
 	policy_type:u8,
-	balance_snapshot:f64,
+	product_version:u32,
+	balance_snapshot:Amount,
 	current_percent:u8,
 }
 
 #[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Debug, Clone)]
 #[serde(crate = "near_sdk::serde")]
 pub struct LossCalculation {
-	payout_percent: f64,
-	amount_due: f64,
+	payout_percent: u8,
+	amount_due: Amount,
 }
 
 #[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Debug, Clone)]
@@ -227,6 +264,16 @@ pub struct ComputedLoss {
 	calculations: LossCalculation,
 }
 
+impl ComputedLoss {
+	fn event_data(&self) -> [LossData; 1] {
+		[LossData {
+			identity: self.identity.clone(),
+			payout_percent: self.calculations.payout_percent,
+			amount_due: self.calculations.amount_due.clone(),
+		}]
+	}
+}
+
 #[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Debug, Clone)]
 #[serde(crate = "near_sdk::serde")]
 pub struct Obligation {
@@ -243,17 +290,16 @@ pub struct Payment {
 }
 
 
-///# description
-/// an object with relevant confirmation data meant to a caller outside of the chain.
-///
-///# fields
-/// * `client_id` {`String`}
-/// * `data_id` {`String`} the id of the relevant data type that has been changed
-/// * `issuer_id` {`String`} the issuer of the `Policy`
-/// * `from` {`String`} `near_sdk::env::predecessor_account_id()`
-/// * `confirmation_type` {`ConfirmationType`} enum for what data change is being confirmed
-/// * `success` {`bool`} was the action successful
-
+//# description
+// an object with relevant confirmation data meant to a caller outside of the chain.
+//
+//# fields
+// * `client_id` {`String`}
+// * `data_id` {`String`} the id of the relevant data type that has been changed
+// * `issuer_id` {`String`} the issuer of the `Policy`
+// * `from` {`String`} `near_sdk::env::predecessor_account_id()`
+// * `confirmation_type` {`ConfirmationType`} enum for what data change is being confirmed
+// * `success` {`bool`} was the action successful
 
 /// the `PayoutContext` construct retains all necessary information to create `PayoutObligations` and update a
 /// `Policy` `EventContext` in conjunction with `OracleData`. it is one part of a two part calculation
@@ -263,7 +309,7 @@ pub struct Payment {
 /// * `policy_manager` the source of the requested `PayoutContext`
 /// * `event_id` `PayoutObligations` are issued on a per `Event` basis
 /// * `pending_balance` the current balance of the `Policy` under the consideration of all current `PayoutObligations`
-/// that have been confirmed by a `Client` and those that and pending loss confirmation from a `Client`
+///   that have been confirmed by a `Client` and those that and pending loss confirmation from a `Client`
 /// * `event_context` construct that has all relevant information for a particular `Event`
 /// * `policy_type`  the policy option that the client chose. this is used to calculate `PayoutObligation`
 #[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Debug, Clone)]
@@ -272,17 +318,19 @@ pub struct Policy {
 	///unique identifier can be deterministic.
 	policy_id: String,
 	///the total balance of the account. can be at most equal to the `max_payout` in the `Policy`'s `Quote`
-	balance: f64,
+	balance: Amount,
 	///an updated value for the balance
-	pending_balance: f64,
+	pending_balance: Amount,
 	///the `Quote`, originally issued from the QuoteManager. the `Quote` defines then `Policy`
 	quote: Quote,
 	///`Policy` start date
 	start_date: u64,
 	///`Policy` end date
 	end_date: u64,
-	///is `Policy` active
-	active: bool,
+	///where the `Policy` is in its lifecycle
+	status: PolicyStatus,
+	///every `status` change, oldest first
+	status_history: Vec<StatusTransition>,
 	///the party issuing the `Quote`
 	issuer: User,
 	///an arbitrary unique identifier
@@ -291,41 +339,119 @@ pub struct Policy {
 	claims_manager: AccountId,
 	///the protection option that this quote represents
 	policy_type: u8,
+	///the payout table version of `policy_type` this policy is pinned to
+	product_version: u32,
 	///maximum value total value of the policy
-	max_payout: f64,
+	max_payout: Amount,
+	///the premium paid for the policy
+	premium: Premium,
+	///deductible, limits and reinstatements applied to every loss
+	terms: CoverageTerms,
 	///location under policy protection
 	location: Location,
-	///payments that have been made to the client of the `Policy`
-	payments: Vec<Payment>,
-	///insurer payment obligations to client
-	obligations: Vec<Obligation>,
-	///calculated losses that the client has rejected
-	rejected_losses: Vec<ComputedLoss>,
-	///computed losses that have yet to receive a decision from client
-	computed_losses: Vec<ComputedLoss>,
+	///the losses, obligations and payments of every reported `Event`, keyed by event id
+	events: HashMap<String, EventContext>,
+	///the capital held in the issuer's escrow for the policy. `None` if none was required at activation
+	capital_lock: Option<CapitalLock>,
 }
 
+impl Policy {
+	/// move the `Policy` to `status`, recording the block timestamp and the caller
+	fn transition(&mut self, status: PolicyStatus) {
+		assert!(self.status.can_transition_to(&status), "INVALID_STATUS_TRANSITION");
+		self.status_history.push(StatusTransition {
+			from: self.status.clone(),
+			to: status.clone(),
+			timestamp: env::block_timestamp(),
+			caller: env::predecessor_account_id(),
+		});
+		PolicyManagerEvent::PolicyStatusChanged([PolicyStatusChangedData {
+			policy_id: self.policy_id.clone(),
+			from: self.status.clone(),
+			to: status.clone(),
+		}])
+		.emit();
+		self.status = status;
+	}
+
+	/// apply the transitions that follow from the coverage period alone
+	fn refresh_status(&mut self) {
+		let now = env::block_timestamp();
+		if self.status == PolicyStatus::Pending && now >= self.start_date && now <= self.end_date {
+			self.transition(PolicyStatus::Active);
+		}
+		if now > self.end_date && self.status.can_transition_to(&PolicyStatus::Expired) {
+			self.transition(PolicyStatus::Expired);
+		}
+	}
+
+	/// what the policy can still pay: its balance while it is live or an expired policy can still be
+	/// reported a late event (until `closes_at`), then only what its open losses owe
+	fn outstanding_limit(&self, closes_at: u64) -> u128 {
+		match self.status {
+			PolicyStatus::Pending | PolicyStatus::Active | PolicyStatus::Suspended => self.balance.minor_units(),
+			PolicyStatus::Expired if env::block_timestamp() <= closes_at => self.balance.minor_units(),
+			_ => self.events.values().map(EventContext::outstanding).sum(),
+		}
+	}
+
+	fn assert_status(&self, allowed: &[PolicyStatus], message: &str) {
+		assert!(allowed.contains(&self.status), "{}", message);
+	}
+
+	/// the highest payout percent already computed for an `Event`, whatever the client decided
+	fn event_payout_percent(&self, event_id: &String) -> u8 {
+		self.events.get(event_id).map(EventContext::percent_due).unwrap_or(0)
+	}
+
+	fn has_loss(&self, loss_id: &String) -> bool {
+		self.events.values().any(|event_context| event_context.has_loss(loss_id))
+	}
+
+	fn event_context_mut(&mut self, event_id: &String) -> &mut EventContext {
+		self.events
+				.get_mut(event_id)
+				.unwrap_or_else(|| env::panic(b"EVENT_NOT_FOUND"))
+	}
+
+	/// the policy year, counted from `start_date`, that an `Event` triggering at `date` belongs to
+	fn policy_year(&self, date: u64) -> u64 {
+		date.saturating_sub(self.start_date) / POLICY_YEAR
+	}
+
+	/// how much more the events of a policy year can pay under the aggregate limit
+	fn aggregate_remaining(&self, year: u64) -> Option<u128> {
+		let mut owed_per_year: HashMap<u64, u128> = HashMap::new();
+		for event_context in self.events.values() {
+			*owed_per_year.entry(self.policy_year(event_context.date())).or_default() += event_context.owed();
+		}
+		let reinstatements_elsewhere: u32 = owed_per_year
+				.iter()
+				.filter(|(other_year, _)| **other_year != year)
+				.map(|(_, owed)| self.terms.reinstatements_used(*owed))
+				.sum();
+		self.terms
+				.aggregate_remaining(owed_per_year.get(&year).copied().unwrap_or(0), reinstatements_elsewhere)
+	}
+
+	/// record a `ComputedLoss` in its `EventContext` under the `terms` and take the amount owed from
+	/// `pending_balance`
+	fn record_loss(&mut self, computed_loss: ComputedLoss) -> Result<Option<ComputedLoss>, AmountError> {
+		let event_id = computed_loss.identity.event_id.clone();
+		let date = self.event_context_mut(&event_id).date();
+		let aggregate_remaining = self.aggregate_remaining(self.policy_year(date));
+		let terms = self.terms.clone();
+		let pending_balance = self.pending_balance.clone();
+		let recorded_loss = self
+				.event_context_mut(&event_id)
+				.record(computed_loss, &terms, aggregate_remaining, &pending_balance)?;
+		if let Some(computed_loss) = &recorded_loss {
+			self.pending_balance = self.pending_balance.checked_sub(&computed_loss.calculations.amount_due)?;
+		}
+		Ok(recorded_loss)
+	}
+}
 
-/// a construct that defines all the relevant data related to an `Event`
-///
-/// # fields
-/// * `max_payout_percent` the current percentage of pending_balance being paid due to an event
-/// * `max_possible_payout` the maximum amount that this `Event` can payout from the `Policy` balance
-/// * `paid_obligations` a collection of obligations that have already been issued and paid
-/// * `confirmed_obligations` obligations that have been 'loss confirmed' by a `Client` through a `PolicyManager`
-/// * `rejected_obligations` obligations that have been rejected by a `Client` through a `PolicyManager`
-/// * `pending_confirmation_obligations` obligations that are pending a `Client` decision (confirm loss or reject)
-/// * `claims_manager` which `ClaimsManager` retains the information to calculate a `PayoutObligation`
-///
-/// # notes
-/// `EventContext` retains PayoutObligations in their four possible states:
-/// - confirmed and paid
-/// - confirmed and unpaid
-/// - rejected
-/// - pending client decision (confirmation or rejection)
-/// this context is needed to calculate any subsequent `PayoutObligation` because neither the `ClaimManager` nor
-/// the `Oracle` have a view on the state of any `PayoutObligations` or `CompletedPayouts`. That is the purview of
-/// the `PolicyManager`
 #[near_bindgen]
 #[derive(BorshDeserialize, BorshSerialize, PanicOnDefault)]
 pub struct PolicyManager {
@@ -346,9 +472,22 @@ pub struct PolicyManager {
 	///client_id is the key in `(key, value)` to make it easier for `Client`
 	/// apps to receive this  data.
 	loss_identities: UnorderedMap<String, Vec<LossIdentity>>,
+	///oracle contracts permitted to report `Event`s through `event_callback`
+	oracles: Vec<AccountId>,
+	///how late an `Event` may be reported relative to a `Policy`'s coverage period
+	submission_rules: SubmissionRules,
+	///issuer accounts as key. the tokens each issuer keeps to pay its obligations on-chain
+	escrows: UnorderedMap<AccountId, IssuerEscrow>,
+	///currency as key. the token obligations in that currency are paid with on-chain
+	settlement_tokens: UnorderedMap<String, SettlementToken>,
+	///client_id as key. where a client is paid on-chain
+	payout_accounts: UnorderedMap<String, AccountId>,
+	///loss id as key. the `ft_transfer`s paying obligations that have yet to resolve
+	settlements: UnorderedMap<String, Settlement>,
+	///the share of a new policy's `max_payout` its issuer must lock in escrow, in basis points
+	capital_requirement: u16,
 }
 
-//TODO accept events that are older than 72 hours
 ///# description
 /// `PolicyManager` is the main contract. `QuoteManager` and `ClaimsManager` work as support.
 /// all parties are to interact directly with the `PolicyManager` for getting data. it is the canonical source.
@@ -377,28 +516,84 @@ impl PolicyManager {
 			),
 			clients: UnorderedMap::new(StorageKeys::Clients),
 			loss_identities: UnorderedMap::new(StorageKeys::LossConfirmationRequests),
+			oracles: Vec::new(),
+			submission_rules: SubmissionRules::default(),
+			escrows: UnorderedMap::new(StorageKeys::Escrows),
+			settlement_tokens: UnorderedMap::new(StorageKeys::SettlementTokens),
+			payout_accounts: UnorderedMap::new(StorageKeys::PayoutAccounts),
+			settlements: UnorderedMap::new(StorageKeys::Settlements),
+			capital_requirement: FULL_CAPITAL_REQUIREMENT,
 		}
 	}
 
 	///#Description
 	///
-	/// activate a policy through this method
+	/// activate a policy through this method. only whitelisted `QuoteManager` accounts (`policy_managers`)
+	/// may call it. the `Policy` is built from the `Quote` alone: its balance starts at `max_payout`
+	/// and its dates come from `coverage_period`
 	///
 	/// #Parameters
 	///
-	///`Quote`
+	///`Quote` the accepted quote. its `id` becomes the policy id
 	///
 	/// #Returns
 	///
-	/// `Confirmation` function returns the new policy id
-	pub fn save_policy(&mut self, policy: Policy) -> Policy {
-		// assert!(self
-		// 		.policy_managers
-		// 		.to_vec()
-		// 		.contains(&env::predecessor_account_id()));
-		let response = policy.clone();
-		self.policies.insert(&policy.policy_id, &policy);
-		response
+	/// `Policy` the new policy
+	pub fn activate_policy(&mut self, quote: Quote) -> Policy {
+		assert!(
+			self.policy_managers.contains(&env::predecessor_account_id()),
+			"POLICY_MANAGER_RESTRICTED"
+		);
+		assert_eq!(quote.policy_manager, env::current_account_id(), "WRONG_POLICY_MANAGER");
+		assert!(self.policies.get(&quote.id).is_none(), "POLICY_ALREADY_EXISTS");
+		let [start_date, end_date] = quote.coverage_period;
+		assert!(start_date < end_date, "INVALID_COVERAGE_PERIOD");
+		assert!(env::block_timestamp() <= end_date, "COVERAGE_PERIOD_ENDED");
+		assert!(!quote.max_payout.is_zero(), "INVALID_MAX_PAYOUT");
+		if let Some(error) = quote.terms.validation_error(&quote.max_payout) {
+			env::panic(error.as_bytes());
+		}
+		let location: Location = quote
+				.location
+				.normalized()
+				.unwrap_or_else(|error| env::panic(error.to_string().as_bytes()));
+		let mut policy = Policy {
+			policy_id: quote.id.clone(),
+			balance: quote.max_payout.clone(),
+			pending_balance: quote.max_payout.clone(),
+			start_date,
+			end_date,
+			status: PolicyStatus::Pending,
+			status_history: Vec::new(),
+			issuer: quote.issuer.clone(),
+			client: quote.client.clone(),
+			claims_manager: quote.claims_manager.clone(),
+			policy_type: quote.policy_type,
+			product_version: quote.product_version,
+			max_payout: quote.max_payout.clone(),
+			premium: quote.premium.clone(),
+			terms: quote.terms.clone(),
+			location: location.clone(),
+			quote: Quote { location, ..quote },
+			events: HashMap::new(),
+			capital_lock: None,
+		};
+		if self.capital_requirement > 0 {
+			policy.capital_lock = Some(self.lock_capital(&policy));
+		}
+		policy.refresh_status();
+		self.save_policy(&mut policy);
+		let mut client_policies: Vec<String> = self.clients.get(&policy.client.id).unwrap_or_default();
+		client_policies.push(policy.policy_id.clone());
+		self.clients.insert(&policy.client.id, &client_policies);
+		PolicyManagerEvent::PolicyActivated([PolicyActivatedData {
+			policy_id: policy.policy_id.clone(),
+			client_id: policy.client.id.clone(),
+			issuer_id: policy.issuer.id.clone(),
+			max_payout: policy.max_payout.clone(),
+		}])
+		.emit();
+		policy
 	}
 
 	/// get a `Policy`'s information
@@ -414,7 +609,71 @@ impl PolicyManager {
 		self.policies.get(&policy_id)
 	}
 
+	/// get the losses, obligations and payments of one `Event` of a `Policy`
+	///
+	/// # parameters
+	///
+	/// * `policy_id` the unique identifier for a policy
+	/// * `event_id` the unique identifier of the event
+	pub fn get_event_context(&self, policy_id: String, event_id: String) -> Option<EventContext> {
+		self.policies
+				.get(&policy_id)
+				.and_then(|policy| policy.events.get(&event_id).cloned())
+	}
+
+	/// get how every loss of an `Event` of a `Policy` was reduced from the amount the `ClaimsManager`
+	/// computed to the amount owed, oldest first
+	///
+	/// # parameters
+	///
+	/// * `policy_id` the unique identifier for a policy
+	/// * `event_id` the unique identifier of the event
+	pub fn get_loss_breakdowns(&self, policy_id: String, event_id: String) -> Vec<LossBreakdown> {
+		self.get_event_context(policy_id, event_id)
+				.map(|event_context| event_context.breakdowns().clone())
+				.unwrap_or_default()
+	}
+
+
+
+	/// get the `Policy`s of a client, in the order they were saved
+	///
+	/// # parameters
+	///
+	/// * `client_id` the unique identifier of the client
+	/// * `from` index of the first `Policy` to return
+	/// * `limit` maximum number of `Policy`s to return
+	pub fn get_policies_for_client(&self, client_id: String, from: u64, limit: u64) -> Vec<Policy> {
+		self.clients
+				.get(&client_id)
+				.unwrap_or_default()
+				.iter()
+				.skip(from as usize)
+				.take(limit as usize)
+				.filter_map(|policy_id| self.policies.get(policy_id))
+				.collect()
+	}
 
+	/// get the `ComputedLoss`es that are waiting for a client to accept or reject them
+	///
+	/// # parameters
+	///
+	/// * `client_id` the unique identifier of the client
+	pub fn get_pending_loss_decisions(&self, client_id: String) -> Vec<ComputedLoss> {
+		self.loss_identities
+				.get(&client_id)
+				.unwrap_or_default()
+				.iter()
+				.filter_map(|loss_identity| {
+					self.policies.get(&loss_identity.policy_id).and_then(|policy| {
+						policy
+								.events
+								.get(&loss_identity.event_id)
+								.and_then(|event_context| event_context.computed_loss(&loss_identity.id).cloned())
+					})
+				})
+				.collect()
+	}
 
 	/// update contract when an off-chain payment is made to a client
 	/// # parameters
@@ -426,50 +685,71 @@ impl PolicyManager {
 			self.policy_managers.to_vec().contains(&env::predecessor_account_id()),
 			"POLICY_MANAGER_RESTRICTED"
 		);
-		let mut payment_response:Option<Payment> = None;
-		if let Some(mut policy) = self.policies.get(&resolve_obligation.identity.policy_id) {
-				let obligation_vec_index_option = policy
-						.obligations
-						.iter()
-						.position(|obligation| *obligation.computed_loss.identity.id == resolve_obligation.identity.id);
-				assert!(obligation_vec_index_option.is_some(),"OBLIGATION_NOT_FOUND");
-				let  obligation: Obligation = policy.obligations.get(obligation_vec_index_option.unwrap()).unwrap().clone();
-				let completed_payment:Payment = Payment {
-					contract_update_time:env::block_timestamp(),
-					obligation:obligation.clone(),
-					payment_proof:resolve_obligation.payment_proof.clone()
-				};
-				payment_response = Option::from(completed_payment.clone());
-				policy.payments.push(completed_payment.clone());
-				policy
-						.obligations
-						.remove(obligation_vec_index_option.unwrap());
-				policy.balance = policy.balance - obligation.computed_loss.calculations.amount_due;
-				self.policies.insert(&resolve_obligation.identity.policy_id, &policy);
-				let obligation_vec_option: Option<Vec<Obligation>> = self.obligations.get(&resolve_obligation.identity.issuer_id);
-				assert!(obligation_vec_option.is_some(),"OBLIGATION_NOT_FOUND_IN_MANAGER");
-				let mut obligation_vec = obligation_vec_option.unwrap();
-				let obligation_index_option = obligation_vec
-						.iter()
-						.position(|obligation| *obligation.computed_loss.identity.id == resolve_obligation.identity.id);
-				assert!(obligation_index_option.is_some(),"OBLIGATION_NOT_FOUND_IN_VEC");
-				obligation_vec.remove(obligation_index_option.unwrap());
-				self.obligations.insert(&resolve_obligation.identity.issuer_id, &obligation_vec);
-				assert!(payment_response.is_some(),"ERROR_RESOLVING_PAYMENT_DATA");
-
-		};
-		payment_response.unwrap()
+		assert!(
+			self.settlements.get(&resolve_obligation.identity.id).is_none(),
+			"OBLIGATION_SETTLING"
+		);
+		let policy_option: Option<Policy> = self.policies.get(&resolve_obligation.identity.policy_id);
+		assert!(policy_option.is_some(), "POLICY_NOT_FOUND");
+		let mut policy: Policy = policy_option.unwrap();
+		policy.refresh_status();
+		policy.assert_status(
+			&[PolicyStatus::Active, PolicyStatus::Suspended, PolicyStatus::Expired, PolicyStatus::Cancelled],
+			"POLICY_STATUS_FORBIDS_PAYMENT",
+		);
+		self.record_payment(policy, &resolve_obligation.identity, resolve_obligation.payment_proof)
 	}
 
-	pub fn compute_loss(&self, loss_contexts:Vec<LossContext>)->Promise{
-		let loss_context:LossContext = loss_contexts.last().unwrap().clone();
-		let claims_manager = loss_context.oracle_data.claims_manager.clone();
-		claims_contract::ext(claims_manager)
-				.compute_loss(loss_contexts)
-				.then(
-					Self::ext(env::current_account_id())
-							.compute_loss_callback()
+	/// # definition
+	/// called by a whitelisted oracle contract once its nodes agree on an `Event` that may trigger a
+	/// `Policy`. the aggregated `OracleMetadata` is sent to the `Policy`'s `ClaimsManager` to compute the loss.
+	/// the event must satisfy the `SubmissionRules`, so an expired `Policy` still accepts an event that
+	/// began during its coverage as long as it is reported in time
+	///
+	/// # parameters
+	/// * event {`Event`} the reported event
+	/// * policy_id {`String`} the policy the oracle request was made for
+	/// * oracle_data {`OracleMetadata`} the aggregated triggering values
+	pub fn event_callback(&mut self, event: Event, policy_id: String, oracle_data: OracleMetadata) -> Promise {
+		let oracle = env::predecessor_account_id();
+		assert!(self.oracles.contains(&oracle), "ORACLE_RESTRICTED");
+		assert_eq!(oracle_data.oracle, oracle, "ORACLE_MISMATCH");
+		assert_eq!(event.oracle, oracle, "ORACLE_MISMATCH");
+		let policy_option = self.policies.get(&policy_id);
+		assert!(policy_option.is_some(), "POLICY_NOT_FOUND");
+		let mut policy = policy_option.unwrap();
+		policy.refresh_status();
+		self.save_policy(&mut policy);
+		policy.assert_status(&[PolicyStatus::Active, PolicyStatus::Expired], "POLICY_NOT_ACTIVE");
+		self.submission_rules
+				.check(
+					[policy.start_date, policy.end_date],
+					[event.start, event.end],
+					event.date,
+					env::block_timestamp(),
 				)
+				.unwrap_or_else(|error| env::panic(error.to_string().as_bytes()));
+		assert_eq!(oracle_data.claims_manager, policy.claims_manager, "WRONG_CLAIMS_MANAGER");
+		if !policy.events.contains_key(&event.id) {
+			let event_context = EventContext::new(event.date, policy.balance.clone(), policy.claims_manager.clone());
+			policy.events.insert(event.id.clone(), event_context);
+			self.save_policy(&mut policy);
+		}
+		let loss_context = LossContext {
+			identity: LossIdentity {
+				id: String::new(),
+				event_id: event.id.clone(),
+				policy_id: policy.policy_id.clone(),
+				client_id: policy.client.id.clone(),
+				issuer_id: policy.issuer.id.clone(),
+			},
+			oracle_data,
+			policy_type: policy.policy_type,
+			product_version: policy.product_version,
+			balance_snapshot: policy.events[&event.id].balance_snapshot().clone(),
+			current_percent: policy.event_payout_percent(&event.id),
+		};
+		self.compute_loss(vec![loss_context])
 	}
 
 	#[private]
@@ -479,19 +759,45 @@ impl PolicyManager {
 			PromiseResult::NotReady => unreachable!(),
 			PromiseResult::Successful(returned_value) => {
 				if let Ok(computed_losses) = near_sdk::serde_json::from_slice::<Vec<ComputedLoss>>(&returned_value) {
-					for computed_loss in computed_losses.clone().into_iter() {
+					let mut recorded_losses: Vec<ComputedLoss> = Vec::new();
+					for computed_loss in computed_losses.into_iter() {
 						if let Some(mut policy) = self.policies.get(&computed_loss.identity.policy_id){
-							policy.computed_losses.push(computed_loss.clone());
-							policy.pending_balance = policy.pending_balance - computed_loss.calculations.amount_due.clone();
-							self.policies.insert(&policy.policy_id,&policy.clone());
+							policy.refresh_status();
+							// an expired policy can still owe for an event `event_callback` accepted late
+							if !matches!(policy.status, PolicyStatus::Active | PolicyStatus::Expired)
+									|| policy.has_loss(&computed_loss.identity.id)
+							{
+								self.save_policy(&mut policy);
+								continue;
+							}
+							// only the increase over the event's payout percent so far is owed, after the terms
+							let recorded_loss = policy
+									.record_loss(computed_loss)
+									.unwrap_or_else(|error| env::panic(error.to_string().as_bytes()));
+							let computed_loss = match recorded_loss {
+								Some(computed_loss) => computed_loss,
+								None => {
+									self.save_policy(&mut policy);
+									continue;
+								}
+							};
+							self.save_policy(&mut policy);
+							let mut loss_identities: Vec<LossIdentity> = self
+									.loss_identities
+									.get(&computed_loss.identity.client_id)
+									.unwrap_or_default();
+							loss_identities.push(computed_loss.identity.clone());
+							self.loss_identities.insert(&computed_loss.identity.client_id, &loss_identities);
+							PolicyManagerEvent::LossComputed(computed_loss.event_data()).emit();
+							recorded_losses.push(computed_loss);
 						}
 					}
-					computed_losses
+					recorded_losses
 				} else {
-					env::panic_str("ERR_WRONG_VAL_RECEIVED")
+					env::panic(b"ERR_WRONG_VAL_RECEIVED")
 				}
 			},
-			PromiseResult::Failed => env::panic_str("ERR_CALL_FAILED")
+			PromiseResult::Failed => env::panic(b"ERR_CALL_FAILED")
 		}
 	}
 	/// retrieve computed_loss data
@@ -503,60 +809,16 @@ impl PolicyManager {
 		let policy_option:Option<Policy> = self.policies.get(&loss_identity.policy_id);
 		assert!(policy_option.is_some(),"POLICY_NOT_FOUND");
 		let policy:Policy = policy_option.unwrap();
-		let computed_loss_vec_index_option:Option<usize> = policy.computed_losses.iter().position
-		(|computed_loss:&ComputedLoss|*computed_loss.identity.id	== loss_identity.id);
-		assert!(computed_loss_vec_index_option.is_some(),"COMPUTED_LOSS_NOT_FOUND");
-		policy.computed_losses.get(computed_loss_vec_index_option.unwrap()).unwrap().clone()
+		let computed_loss_option: Option<&ComputedLoss> = policy
+				.events
+				.get(&loss_identity.event_id)
+				.and_then(|event_context| event_context.computed_loss(&loss_identity.id));
+		assert!(computed_loss_option.is_some(),"COMPUTED_LOSS_NOT_FOUND");
+		computed_loss_option.unwrap().clone()
 	}
-	/// # definition
-	/// can only be called by the respective `ClaimsManager` contract of a `Policy` this function
-	/// updates the `EventContext` of a particular `Event` with respect to a `Policy`
-	/// it adds a `PayoutObligation`, creates a `ConfirmLossRequest` and updates the `Policy` balance
-	///
-	/// # parameters
-	/// * event_context_update {EventContextUpdate} the object that defines the changes
-	// pub fn post_event_update(&mut self, event_context_update: EventContextUpdate) {
-	// 	if let Some(mut policy) = self.policies.get(&event_context_update.policy_id) {
-	// 		assert_eq!(policy.claims_manager, env::predecessor_account_id(), "NOT_AUTHORIZED_TO_UPDATE_EVENT");
-	// 		assert!(env::block_timestamp() <= policy.end_date,"POLICY_EXPIRED");
-	// 		assert_eq!(policy.active, true, "POLICY_INACTIVE");
-	// 		if let Some(event_context) = policy.events.get(&event_context_update.event_id) {
-	// 			let mut computed_losses: Vec<ComputedLoss> = event_context.computed_losses.clone();
-	// 			computed_losses.push(event_context_update.computed_loss.clone());
-	// 			let new_event_context = EventContext{
-	// 				percent_due: event_context_update.max_payout_percent,
-	// 				balance_snapshot: event_context_update.max_possible_payout,
-	// 				payments: event_context.payments.clone(),
-	// 				obligations: event_context.obligations.clone(),
-	// 				rejected_losses: event_context.rejected_losses.clone(),
-	// 				computed_losses,
-	// 				claims_manager: event_context.claims_manager.clone()
-	// 			};
-	// 			let new_balance = policy.pending_balance - event_context_update.computed_loss.calculations.amount_due.clone();
-	// 			if new_balance < 0.0 {
-	// 				policy.pending_balance = 0.0;
-	// 			} else {
-	// 				policy.pending_balance = new_balance;
-	// 			}
-	// 			let mut loss_identities_vec: Vec<LossIdentity> = self.loss_identities.get(&policy.quote.client.id).unwrap();
-	// 			loss_identities_vec.push(event_context_update.computed_loss.identity);
-	// 			self.loss_identities.insert(&policy.quote.client.id, &loss_identities_vec);
-	// 			policy
-	// 					.events
-	// 					.insert(event_context_update.event_id, new_event_context);
-	// 			self.policies.insert(&policy.policy_id, &policy);
-	// 		} else {
-	// 			panic!("Event not found in Policy data.");
-	// 		}
-	// 	} else {
-	// 		panic!("No Policy found in PolicyManager");
-	// 	}
-	// }
-
 	///# definition
 	/// method called by `Client` to update to confirm/reject loss based on `LossConfirmationRequest`. will subsequently
-	/// move a `PayoutObligation` from `pending_confirmation_obligations` to `loss_confirmed_obligations` in the `EventContext` of a
-	/// particular event within a `Policy`
+	/// move the `ComputedLoss` to the `obligations` or `rejected_losses` of the `EventContext` of its `Event`
 	///
 	/// # parameters
 	/// * `client_id` {`String`} unique identifier of client to find their loss_confirmation_requests
@@ -564,69 +826,368 @@ impl PolicyManager {
 	/// * `LossConfirmationRequest` therefore a payout_id is a suitable unique identifier.
 	/// * `accept` {`bool`} the `Client` decision to accept/reject the `PayoutObligation`
 	///
+	/// the `event_id`, `client_id` and `issuer_id` of the decision are taken from the recorded loss, not
+	/// from the caller
+	///
 	/// # returns
 	/// ContractResponse -tentative- liable to change.
-
 	pub fn post_loss_decision(&mut self, loss_decision: LossDecision) -> LossDecision {
-		if let Some(mut policy) = self.policies.get(&loss_decision.identity.policy_id) {
-			assert_eq!(
-				policy.quote.client.authorized_administrator,
-				env::predecessor_account_id(),
-				"Not Authorized to Confirm loss for this client."
-			);
-				if loss_decision.accept {
-					let computed_loss_vec_index_option:Option<usize> = policy.computed_losses
-							.iter()
-							.position(|computed_loss| *computed_loss.identity.id == *loss_decision.identity.id);
-					assert!(computed_loss_vec_index_option.is_some(), "COMPUTED_LOSS_NOT_FOUND_IN_POLICY");
-					let computed_loss_vec_index: usize = computed_loss_vec_index_option.unwrap();
-					let computed_loss: ComputedLoss = policy.computed_losses.get(computed_loss_vec_index).unwrap().clone();
-					policy.computed_losses.remove(computed_loss_vec_index);
-					let new_obligation: Obligation = Obligation {
-						computed_loss,
-						contract_update_time: env::block_timestamp()
-					};
-					policy.obligations.push(new_obligation.clone());
-					let issuer_obligations_option: Option<Vec<Obligation>> = self.obligations
-					                                                             .get(&loss_decision.identity.issuer_id);
-					assert!(issuer_obligations_option.is_some(), "ISSUER_OBLIGATIONS_NOT_FOUND");
-					let mut issuer_obligations = issuer_obligations_option.unwrap();
-					issuer_obligations.push(new_obligation);
-					self.obligations.insert(
-						&loss_decision.identity.issuer_id,
-						&issuer_obligations,
-					); } else {
-					let computed_loss_vec_index_option = policy
-							.computed_losses
-							.iter()
-							.position(|computed_loss| *computed_loss.identity.id == *loss_decision.identity.id);
-					assert!(computed_loss_vec_index_option.is_some(), "COMPUTED_LOSS_NOT_FOUND_IN_POLICY");
-					let computed_loss_vec_index: usize = computed_loss_vec_index_option.unwrap();
-					let computed_loss: ComputedLoss = policy.computed_losses.get(computed_loss_vec_index).unwrap().clone();
-					policy.computed_losses.remove(computed_loss_vec_index);
-					policy.rejected_losses.push(computed_loss);
-					let new_pending_balance = policy.pending_balance
-							+ policy.rejected_losses.last().unwrap().calculations.amount_due;
-					policy.pending_balance = new_pending_balance;
-				};
-				self.policies.insert(&policy.policy_id, &policy);
+		let policy_option: Option<Policy> = self.policies.get(&loss_decision.identity.policy_id);
+		assert!(policy_option.is_some(), "POLICY_NOT_FOUND");
+		let mut policy: Policy = policy_option.unwrap();
+		assert_eq!(
+			policy.quote.client.authorized_administrator,
+			env::predecessor_account_id(),
+			"Not Authorized to Confirm loss for this client."
+		);
+		policy.refresh_status();
+		policy.assert_status(
+			&[PolicyStatus::Active, PolicyStatus::Expired, PolicyStatus::Cancelled],
+			"POLICY_STATUS_FORBIDS_LOSS_DECISION",
+		);
+		// the identity recorded with the loss. only the policy and loss ids are taken from the caller
+		let identity_option: Option<LossIdentity> = policy
+				.events
+				.values()
+				.find_map(|event_context| event_context.computed_loss(&loss_decision.identity.id))
+				.map(|computed_loss| computed_loss.identity.clone());
+		assert!(identity_option.is_some(), "COMPUTED_LOSS_NOT_FOUND_IN_POLICY");
+		let identity: LossIdentity = identity_option.unwrap();
+		if loss_decision.accept {
+			let new_obligation_option: Option<Obligation> = policy
+					.event_context_mut(&identity.event_id)
+					.accept(&identity.id, env::block_timestamp());
+			assert!(new_obligation_option.is_some(), "COMPUTED_LOSS_NOT_FOUND_IN_POLICY");
+			let new_obligation: Obligation = new_obligation_option.unwrap();
+			PolicyManagerEvent::LossAccepted(new_obligation.computed_loss.event_data()).emit();
+			PolicyManagerEvent::ObligationCreated(new_obligation.computed_loss.event_data()).emit();
+			let mut issuer_obligations: Vec<Obligation> = self
+					.obligations
+					.get(&identity.issuer_id)
+					.unwrap_or_default();
+			issuer_obligations.push(new_obligation);
+			self.obligations.insert(&identity.issuer_id, &issuer_obligations);
+		} else {
+			let computed_loss_option: Option<ComputedLoss> = policy
+					.event_context_mut(&identity.event_id)
+					.reject(&identity.id);
+			assert!(computed_loss_option.is_some(), "COMPUTED_LOSS_NOT_FOUND_IN_POLICY");
+			let computed_loss: ComputedLoss = computed_loss_option.unwrap();
+			PolicyManagerEvent::LossRejected(computed_loss.event_data()).emit();
+			let new_pending_balance = policy
+					.pending_balance
+					.checked_add(&computed_loss.calculations.amount_due)
+					.unwrap_or_else(|error| env::panic(error.to_string().as_bytes()));
+			policy.pending_balance = new_pending_balance;
 		};
-		let mut loss_identities: Vec<LossIdentity> =
-				self.loss_identities.get(&loss_decision.identity.client_id).unwrap();
+		self.save_policy(&mut policy);
+		let loss_identities_option: Option<Vec<LossIdentity>> = self.loss_identities.get(&identity.client_id);
+		assert!(loss_identities_option.is_some(), "LOSS_IDENTITY_NOT_FOUND");
+		let mut loss_identities: Vec<LossIdentity> = loss_identities_option.unwrap();
 		let loss_identity_vec_option = loss_identities
 				.iter()
-				.position(|vec_loss_identity| *vec_loss_identity.id == loss_decision.identity.id);
-		if loss_identity_vec_option.is_some() {
-			loss_identities.remove(loss_identity_vec_option.unwrap());
-			self.loss_identities
-			    .insert(&loss_decision.identity.client_id, &loss_identities);
+				.position(|vec_loss_identity| *vec_loss_identity.id == identity.id);
+		if let Some(index) = loss_identity_vec_option {
+			loss_identities.remove(index);
+			self.loss_identities.insert(&identity.client_id, &loss_identities);
 		} else {
 			panic!("LOSS_IDENTITY_NOT_FOUND");
 		}
-		loss_decision
+		if loss_decision.accept {
+			// an issuer settling on-chain pays right away. otherwise the obligation waits for
+			// `post_payment_made` or a retried `settle_obligation`
+			let _ = self.try_settle(&identity);
+		}
+		LossDecision {
+			accept: loss_decision.accept,
+			identity,
+		}
+	}
+
+	///#Description
+	///
+	/// pay an obligation from the escrow of its issuer. anyone can call it, e.g. once the issuer topped up
+	/// its escrow or the client registered a payout account after accepting the loss
+	///
+	/// #Parameters
+	///
+	/// *`loss_identity` the accepted loss
+	pub fn settle_obligation(&mut self, loss_identity: LossIdentity) -> Promise {
+		self.try_settle(&loss_identity).unwrap_or_else(|error| env::panic(error.as_bytes()))
+	}
+
+	///callback of the transfer paying an obligation. records the `Payment` if the transfer went
+	/// through, otherwise returns the amount to the issuer's escrow so the obligation can be retried
+	#[private]
+	pub fn on_obligation_settled(&mut self, loss_identity: LossIdentity) -> bool {
+		assert_eq!(env::promise_results_count(), 1, "ERR_TOO_MANY_RESULTS");
+		let settlement_option: Option<Settlement> = self.settlements.remove(&loss_identity.id);
+		assert!(settlement_option.is_some(), "SETTLEMENT_NOT_FOUND");
+		let settlement: Settlement = settlement_option.unwrap();
+		match env::promise_result(0) {
+			PromiseResult::NotReady => unreachable!(),
+			PromiseResult::Successful(_) => {
+				let policy_option: Option<Policy> = self.policies.get(&loss_identity.policy_id);
+				assert!(policy_option.is_some(), "POLICY_NOT_FOUND");
+				let mut policy: Policy = policy_option.unwrap();
+				policy.refresh_status();
+				self.record_payment(policy, &loss_identity, settlement.payment_proof());
+				true
+			},
+			PromiseResult::Failed => {
+				let mut escrow: IssuerEscrow = self.escrows.get(&settlement.escrow).unwrap_or_default();
+				escrow
+						.deposit(&settlement.asset, settlement.amount.0)
+						.unwrap_or_else(|error| env::panic(error.to_string().as_bytes()));
+				self.escrows.insert(&settlement.escrow, &escrow);
+				PolicyManagerEvent::ObligationSettlementFailed([settlement]).emit();
+				false
+			}
+		}
+	}
+
+	///#Description
+	///
+	/// NEP-141 receiver. an issuer funds its escrow with `ft_transfer_call` in a token that is the
+	/// `SettlementToken` of a currency. `msg` is ignored
+	///
+	/// #Returns
+	///
+	/// the unused amount, always `0`
+	pub fn ft_on_transfer(&mut self, sender_id: AccountId, amount: U128, msg: String) -> PromiseOrValue<U128> {
+		let _ = msg;
+		let asset = CapitalAsset::FungibleToken(env::predecessor_account_id());
+		assert!(
+			self.settlement_tokens.values().any(|settlement_token| settlement_token.asset == asset),
+			"TOKEN_NOT_ACCEPTED"
+		);
+		self.deposit(sender_id, asset, amount);
+		PromiseOrValue::Value(U128(0))
+	}
+
+	///#Description
+	///
+	/// an issuer funds its escrow with the attached NEAR
+	#[payable]
+	pub fn deposit_capital(&mut self) -> U128 {
+		let amount = U128(env::attached_deposit());
+		assert!(amount.0 > 0, "INVALID_AMOUNT");
+		self.deposit(env::predecessor_account_id(), CapitalAsset::Near, amount)
+	}
+
+	///#Description
+	///
+	/// an issuer withdraws free capital from its escrow. capital locked by its policies never leaves this
+	/// way, and amounts of settlements in flight have already left it
+	///
+	/// #Parameters
+	///
+	/// *`asset` NEAR or the token contract
+	/// *`amount` how many units to withdraw
+	pub fn withdraw_escrow(&mut self, asset: CapitalAsset, amount: U128) -> Promise {
+		let issuer = env::predecessor_account_id();
+		assert!(amount.0 > 0, "INVALID_AMOUNT");
+		let mut escrow: IssuerEscrow = self.escrows.get(&issuer).unwrap_or_default();
+		escrow
+				.withdraw(&asset, amount.0)
+				.unwrap_or_else(|_| env::panic(b"INSUFFICIENT_FREE_CAPITAL"));
+		self.escrows.insert(&issuer, &escrow);
+		PolicyManagerEvent::EscrowWithdrawn([EscrowData {
+			account_id: issuer.clone(),
+			asset: asset.clone(),
+			amount,
+		}])
+		.emit();
+		Self::transfer_asset(&asset, issuer.clone(), amount.0, None).then(ext_self::on_escrow_withdrawn(
+			issuer,
+			asset,
+			amount,
+			&env::current_account_id(),
+			0,
+			ON_ESCROW_WITHDRAWN_GAS,
+		))
 	}
 
+	///callback of `withdraw_escrow`. returns the capital to the escrow if the transfer failed
+	#[private]
+	pub fn on_escrow_withdrawn(&mut self, issuer: AccountId, asset: CapitalAsset, amount: U128) -> bool {
+		assert_eq!(env::promise_results_count(), 1, "ERR_TOO_MANY_RESULTS");
+		match env::promise_result(0) {
+			PromiseResult::NotReady => unreachable!(),
+			PromiseResult::Successful(_) => true,
+			PromiseResult::Failed => {
+				let mut escrow: IssuerEscrow = self.escrows.get(&issuer).unwrap_or_default();
+				escrow
+						.deposit(&asset, amount.0)
+						.unwrap_or_else(|error| env::panic(error.to_string().as_bytes()));
+				self.escrows.insert(&issuer, &escrow);
+				false
+			}
+		}
+	}
 
+	///#Description
+	///
+	/// choose how the caller pays the obligations of the policies it issued
+	pub fn set_settlement_mode(&mut self, settlement_mode: SettlementMode) -> SettlementMode {
+		let issuer = env::predecessor_account_id();
+		let mut escrow: IssuerEscrow = self.escrows.get(&issuer).unwrap_or_default();
+		escrow.settlement_mode = settlement_mode;
+		self.escrows.insert(&issuer, &escrow);
+		PolicyManagerEvent::SettlementModeChanged([SettlementModeData {
+			account_id: issuer,
+			settlement_mode,
+		}])
+		.emit();
+		settlement_mode
+	}
+
+	///#Description
+	///
+	/// set the NEP-141 token obligations in `currency` are paid with on-chain
+	pub fn set_settlement_token(&mut self, currency: String, settlement_token: SettlementToken) -> SettlementToken {
+		assert_eq!(env::predecessor_account_id(), self.master_admin);
+		assert!(settlement_token.units_per_minor_unit.0 > 0, "INVALID_SETTLEMENT_TOKEN");
+		self.settlement_tokens.insert(&currency, &settlement_token);
+		PolicyManagerEvent::SettlementTokenSet([SettlementTokenData {
+			currency,
+			settlement_token: settlement_token.clone(),
+		}])
+		.emit();
+		settlement_token
+	}
+
+	///#Description
+	///
+	/// set where a client is paid on-chain. only the `authorized_administrator` of the client can call it
+	///
+	/// #Parameters
+	///
+	/// *`client_id` the client's id
+	/// *`account_id` the account receiving the client's payouts
+	pub fn register_payout_account(&mut self, client_id: String, account_id: AccountId) -> AccountId {
+		let caller = env::predecessor_account_id();
+		let is_client_administrator = self
+				.clients
+				.get(&client_id)
+				.unwrap_or_default()
+				.iter()
+				.filter_map(|policy_id| self.policies.get(policy_id))
+				.any(|policy| policy.client.authorized_administrator == caller);
+		assert!(is_client_administrator, "NOT_CLIENT_ADMINISTRATOR");
+		self.payout_accounts.insert(&client_id, &account_id);
+		PolicyManagerEvent::PayoutAccountRegistered([PayoutAccountData {
+			client_id,
+			account_id: account_id.clone(),
+		}])
+		.emit();
+		account_id
+	}
+
+	pub fn get_escrow(&self, account_id: AccountId) -> IssuerEscrow {
+		self.escrows.get(&account_id).unwrap_or_default()
+	}
+
+	pub fn get_settlement_token(&self, currency: String) -> Option<SettlementToken> {
+		self.settlement_tokens.get(&currency)
+	}
+
+	pub fn get_payout_account(&self, client_id: String) -> Option<AccountId> {
+		self.payout_accounts.get(&client_id)
+	}
+
+	pub fn get_settlement(&self, loss_id: String) -> Option<Settlement> {
+		self.settlements.get(&loss_id)
+	}
+
+	///#Description
+	///
+	/// set the share of a new policy's `max_payout` its issuer must lock in escrow, in basis points.
+	/// `0` activates policies without capital. policies keep the requirement they were activated with
+	pub fn set_capital_requirement(&mut self, requirement: u16) -> u16 {
+		assert_eq!(env::predecessor_account_id(), self.master_admin);
+		self.capital_requirement = requirement;
+		PolicyManagerEvent::CapitalRequirementChanged([CapitalRequirementData {
+			requirement,
+		}])
+		.emit();
+		requirement
+	}
+
+	pub fn get_capital_requirement(&self) -> u16 {
+		self.capital_requirement
+	}
+
+	///#Description
+	///
+	/// how well an issuer's capital covers the outstanding limits of its policies, per asset. the capital
+	/// of a policy that expired is only released once its status is refreshed, e.g. with `refresh_policy_status`
+	///
+	/// #Parameters
+	///
+	/// *`account_id` the issuer account
+	pub fn get_solvency(&self, account_id: AccountId) -> Vec<Solvency> {
+		self.escrows.get(&account_id).unwrap_or_default().solvency()
+	}
+
+
+
+	///#Description
+	///
+	/// manually move a `Policy` through its lifecycle. `Exhausted` and `Expired` are only ever
+	/// reached automatically. a `Cancelled` policy still settles the losses computed before it was
+	/// cancelled, and is only `Closed` once none is outstanding
+	///
+	/// #Parameters
+	///
+	/// *`policy_id` unique id of policy
+	/// *`status` the requested `PolicyStatus`
+	///
+	/// #Returns
+	///
+	/// the new `PolicyStatus`
+	pub fn update_policy_status(&mut self, policy_id: String, status: PolicyStatus) -> PolicyStatus {
+		let policy_option = self.policies.get(&policy_id);
+		assert!(policy_option.is_some(), "NO_POLICY_FOUND");
+		let mut policy = policy_option.unwrap();
+		let caller = env::predecessor_account_id();
+		assert!(
+			caller == self.master_admin || caller == policy.issuer.authorized_administrator,
+			"NOT_AUTHORIZED_TO_UPDATE_STATUS"
+		);
+		assert!(
+			status != PolicyStatus::Exhausted && status != PolicyStatus::Expired,
+			"STATUS_IS_AUTOMATIC"
+		);
+		policy.refresh_status();
+		if status == PolicyStatus::Closed {
+			assert!(
+				!policy.events.values().any(EventContext::has_outstanding_losses),
+				"POLICY_HAS_OUTSTANDING_LOSSES"
+			);
+		}
+		policy.transition(status);
+		self.save_policy(&mut policy);
+		policy.status
+	}
+
+	///#Description
+	///
+	/// apply any transition that is due because of the coverage period (e.g. `Active` -> `Expired`), and
+	/// release the issuer capital locked for the policy once late events can no longer be reported.
+	/// can be called by anyone
+	///
+	/// #Parameters
+	///
+	/// *`policy_id` unique id of policy
+	pub fn refresh_policy_status(&mut self, policy_id: String) -> PolicyStatus {
+		let policy_option = self.policies.get(&policy_id);
+		assert!(policy_option.is_some(), "NO_POLICY_FOUND");
+		let mut policy = policy_option.unwrap();
+		policy.refresh_status();
+		self.save_policy(&mut policy);
+		policy.status
+	}
 
 	///#Description
 	///
@@ -635,16 +1196,61 @@ impl PolicyManager {
 	/// #Parameters
 	///
 	/// *`policy_id` unique id of policy
-	pub fn get_policy_balance(&self, policy_id: &String) -> f64 {
+	pub fn get_policy_balance(&self, policy_id: &String) -> Amount {
 		let policy_option = self.policies.get(policy_id);
 		assert!(policy_option.is_some(),"NO_POLICY_FOUND");
 		let policy = policy_option.unwrap();
 		policy.balance
 	}
 
+	///#Description
+	///
+	/// set how late an `Event` may be reported relative to a `Policy`'s coverage period
+	pub fn set_submission_rules(&mut self, rules: SubmissionRules) -> SubmissionRules {
+		assert_eq!(env::predecessor_account_id(), self.master_admin);
+		self.submission_rules = rules.clone();
+		PolicyManagerEvent::SubmissionRulesChanged([SubmissionRulesData {
+			rules: rules.clone(),
+		}])
+		.emit();
+		rules
+	}
+
+	pub fn get_submission_rules(&self) -> SubmissionRules {
+		self.submission_rules.clone()
+	}
+
+	pub fn add_oracle(&mut self, oracle: AccountId) -> AccountId {
+		assert_eq!(env::predecessor_account_id(), self.master_admin);
+		if !self.oracles.contains(&oracle) {
+			self.oracles.push(oracle.clone());
+		}
+		PolicyManagerEvent::OracleAdded([AccountData {
+			account_id: oracle.clone(),
+		}])
+		.emit();
+		oracle
+	}
+
+	pub fn remove_oracle(&mut self, oracle: AccountId) -> AccountId {
+		assert_eq!(env::predecessor_account_id(), self.master_admin);
+		let index = self.oracles.iter().position(|account| *account == oracle);
+		assert!(index.is_some(), "ORACLE_NOT_FOUND");
+		let removed_oracle: AccountId = self.oracles.swap_remove(index.unwrap());
+		PolicyManagerEvent::OracleRemoved([AccountData {
+			account_id: removed_oracle.clone(),
+		}])
+		.emit();
+		removed_oracle
+	}
+
 	pub fn add_policy_activator(&mut self, policy_activator: &AccountId) -> AccountId {
 		assert_eq!(env::predecessor_account_id(), self.master_admin);
 		self.policy_managers.push(policy_activator.clone());
+		PolicyManagerEvent::PolicyActivatorAdded([AccountData {
+			account_id: policy_activator.clone(),
+		}])
+		.emit();
 		policy_activator.clone()
 	}
 
@@ -656,6 +1262,10 @@ impl PolicyManager {
 				.position(|manager| *manager == *policy_activator);
 		assert!(index.is_some(), "That Policy Activator was not found");
 		let removed_activator:AccountId = self.policy_managers.swap_remove(index.unwrap());
+		PolicyManagerEvent::PolicyActivatorRemoved([AccountData {
+			account_id: removed_activator.clone(),
+		}])
+		.emit();
 		removed_activator
 	}
 
@@ -671,6 +1281,11 @@ impl PolicyManager {
 			"only master admin can start abdication process."
 		);
 		self.new_master_admin = Some(new_master_admin.clone());
+		PolicyManagerEvent::AdminChangeStarted([AdminChangeData {
+			master_admin: self.master_admin.clone(),
+			new_master_admin: self.new_master_admin.clone(),
+		}])
+		.emit();
 		new_master_admin
 	}
 
@@ -682,6 +1297,11 @@ impl PolicyManager {
 			"only current master admin can do that"
 		);
 		self.new_master_admin = None;
+		PolicyManagerEvent::AdminChangeCancelled([AdminChangeData {
+			master_admin: self.master_admin.clone(),
+			new_master_admin: None,
+		}])
+		.emit();
 		true
 	}
 
@@ -697,18 +1317,698 @@ impl PolicyManager {
 			new_master_admin,
 			"you are not the new master admin"
 		);
+		let previous_master_admin = self.master_admin.clone();
 		self.master_admin = new_master_admin.clone();
 		self.new_master_admin = None;
+		PolicyManagerEvent::AdminChanged([AdminChangeData {
+			master_admin: previous_master_admin,
+			new_master_admin: Some(new_master_admin.clone()),
+		}])
+		.emit();
 		new_master_admin.clone()
 		}
 }
 
+impl PolicyManager {
+	/// move an obligation of `policy` to its payments and take the amount paid from the balance. the
+	/// payment is filed under the identity recorded with the obligation
+	fn record_payment(&mut self, mut policy: Policy, loss_identity: &LossIdentity, payment_proof: String) -> Payment {
+		let completed_payment_option: Option<Payment> = policy
+				.event_context_mut(&loss_identity.event_id)
+				.pay(&loss_identity.id, payment_proof.clone(), env::block_timestamp());
+		assert!(completed_payment_option.is_some(),"OBLIGATION_NOT_FOUND");
+		let completed_payment:Payment = completed_payment_option.unwrap();
+		let obligation: Obligation = completed_payment.obligation.clone();
+		let identity: &LossIdentity = &obligation.computed_loss.identity;
+		policy.balance = policy
+				.balance
+				.checked_sub(&obligation.computed_loss.calculations.amount_due)
+				.unwrap_or_else(|error| env::panic(error.to_string().as_bytes()));
+		PolicyManagerEvent::PaymentPosted([PaymentPostedData {
+			identity: identity.clone(),
+			amount_paid: obligation.computed_loss.calculations.amount_due.clone(),
+			payment_proof,
+			balance: policy.balance.clone(),
+		}])
+		.emit();
+		if policy.balance.is_zero() && policy.status.can_transition_to(&PolicyStatus::Exhausted) {
+			policy.transition(PolicyStatus::Exhausted);
+		}
+		self.save_policy(&mut policy);
+		let obligation_vec_option: Option<Vec<Obligation>> = self.obligations.get(&identity.issuer_id);
+		assert!(obligation_vec_option.is_some(),"OBLIGATION_NOT_FOUND_IN_MANAGER");
+		let mut obligation_vec = obligation_vec_option.unwrap();
+		let obligation_index_option = obligation_vec
+				.iter()
+				.position(|obligation| *obligation.computed_loss.identity.id == identity.id);
+		assert!(obligation_index_option.is_some(),"OBLIGATION_NOT_FOUND_IN_VEC");
+		obligation_vec.remove(obligation_index_option.unwrap());
+		self.obligations.insert(&identity.issuer_id, &obligation_vec);
+		completed_payment
+	}
+
+	/// start paying an accepted loss from the escrow of the policy's issuer. the amount leaves the escrow
+	/// now and is returned to it by `on_obligation_settled` if the transfer fails. only the policy and loss
+	/// ids of `loss_identity` are used, the client paid is the one recorded with the obligation
+	///
+	///# returns
+	/// the transfer and its callback, or the reason the obligation cannot be paid on-chain
+	fn try_settle(&mut self, loss_identity: &LossIdentity) -> Result<Promise, &'static str> {
+		if self.settlements.get(&loss_identity.id).is_some() {
+			return Err("OBLIGATION_SETTLING");
+		}
+		let mut policy: Policy = self.policies.get(&loss_identity.policy_id).ok_or("POLICY_NOT_FOUND")?;
+		policy.refresh_status();
+		if !matches!(
+			policy.status,
+			PolicyStatus::Active | PolicyStatus::Suspended | PolicyStatus::Expired | PolicyStatus::Cancelled
+		) {
+			return Err("POLICY_STATUS_FORBIDS_PAYMENT");
+		}
+		let obligation: &Obligation = policy
+				.events
+				.values()
+				.find_map(|event_context| event_context.obligation(&loss_identity.id))
+				.ok_or("OBLIGATION_NOT_FOUND")?;
+		let identity: &LossIdentity = &obligation.computed_loss.identity;
+		let amount_due: &Amount = &obligation.computed_loss.calculations.amount_due;
+		let settlement_token: SettlementToken = self
+				.settlement_tokens
+				.get(amount_due.currency())
+				.ok_or("NO_SETTLEMENT_TOKEN")?;
+		let receiver: AccountId = self.payout_accounts.get(&identity.client_id).ok_or("NO_PAYOUT_ACCOUNT")?;
+		let issuer: AccountId = policy.issuer.authorized_administrator.clone();
+		let mut escrow: IssuerEscrow = self.escrows.get(&issuer).unwrap_or_default();
+		if escrow.settlement_mode != SettlementMode::OnChain {
+			return Err("OFF_CHAIN_SETTLEMENT");
+		}
+		let units: u128 = settlement_token
+				.to_token_units(amount_due)
+				.map_err(|_| "SETTLEMENT_AMOUNT_OVERFLOW")?;
+		escrow
+				.pay(&settlement_token.asset, units)
+				.map_err(|_| "INSUFFICIENT_ESCROW")?;
+		self.escrows.insert(&issuer, &escrow);
+		let settlement = Settlement {
+			identity: identity.clone(),
+			escrow: issuer,
+			asset: settlement_token.asset.clone(),
+			amount: U128(units),
+			receiver: receiver.clone(),
+		};
+		self.settlements.insert(&identity.id, &settlement);
+		PolicyManagerEvent::ObligationSettlementStarted([settlement.clone()]).emit();
+		Ok(
+			Self::transfer_asset(&settlement_token.asset, receiver, units, Some(identity.id.clone())).then(
+				ext_self::on_obligation_settled(
+					identity.clone(),
+					&env::current_account_id(),
+					0,
+					ON_OBLIGATION_SETTLED_GAS,
+				)
+			)
+		)
+	}
+
+	/// send `amount` units of `asset` out of the contract
+	fn transfer_asset(asset: &CapitalAsset, receiver: AccountId, amount: u128, memo: Option<String>) -> Promise {
+		match asset {
+			CapitalAsset::Near => Promise::new(receiver).transfer(amount),
+			CapitalAsset::FungibleToken(token) => {
+				fungible_token::ft_transfer(receiver, U128(amount), memo, token, 1, FT_TRANSFER_GAS)
+			}
+		}
+	}
+
+	/// credit `amount` units of `asset` to the escrow of `account_id`
+	fn deposit(&mut self, account_id: AccountId, asset: CapitalAsset, amount: U128) -> U128 {
+		let mut escrow: IssuerEscrow = self.escrows.get(&account_id).unwrap_or_default();
+		escrow
+				.deposit(&asset, amount.0)
+				.unwrap_or_else(|error| env::panic(error.to_string().as_bytes()));
+		self.escrows.insert(&account_id, &escrow);
+		PolicyManagerEvent::EscrowDeposited([EscrowData {
+			account_id,
+			asset: asset.clone(),
+			amount,
+		}])
+		.emit();
+		U128(escrow.balance(&asset))
+	}
+
+	/// lock the `capital_requirement` of a new policy's `max_payout` in its issuer's escrow. panics if the
+	/// issuer's free capital does not cover it
+	fn lock_capital(&mut self, policy: &Policy) -> CapitalLock {
+		let settlement_token_option: Option<SettlementToken> = self.settlement_tokens.get(policy.max_payout.currency());
+		assert!(settlement_token_option.is_some(), "NO_SETTLEMENT_TOKEN");
+		let issuer: AccountId = policy.issuer.authorized_administrator.clone();
+		let capital_lock = CapitalLock::new(
+			issuer.clone(),
+			&settlement_token_option.unwrap(),
+			self.capital_requirement,
+			&policy.max_payout,
+		)
+		.unwrap_or_else(|error| env::panic(error.to_string().as_bytes()));
+		let mut escrow: IssuerEscrow = self.escrows.get(&issuer).unwrap_or_default();
+		escrow
+				.lock(&capital_lock)
+				.unwrap_or_else(|_| env::panic(b"INSUFFICIENT_FREE_CAPITAL"));
+		self.escrows.insert(&issuer, &escrow);
+		PolicyManagerEvent::CapitalLocked([CapitalLockData {
+			policy_id: policy.policy_id.clone(),
+			capital_lock: capital_lock.clone(),
+		}])
+		.emit();
+		capital_lock
+	}
+
+	/// store `policy`, first releasing the capital its issuer no longer has to lock for it. the lock
+	/// follows the balance while the policy is live and until the `SubmissionRules` stop accepting late
+	/// events for it, then only what its open losses owe
+	fn save_policy(&mut self, policy: &mut Policy) {
+		if let Some(capital_lock) = policy.capital_lock.clone() {
+			let closes_at: u64 = self.submission_rules.closes_at(policy.end_date);
+			let resized: CapitalLock = capital_lock
+					.resized(policy.outstanding_limit(closes_at))
+					.unwrap_or_else(|error| env::panic(error.to_string().as_bytes()));
+			if resized != capital_lock {
+				let mut escrow: IssuerEscrow = self.escrows.get(&capital_lock.account_id).unwrap_or_default();
+				escrow.release(&capital_lock, &resized);
+				self.escrows.insert(&capital_lock.account_id, &escrow);
+				PolicyManagerEvent::CapitalReleased([CapitalLockData {
+					policy_id: policy.policy_id.clone(),
+					capital_lock: resized.clone(),
+				}])
+				.emit();
+				policy.capital_lock = Some(resized);
+			}
+		}
+		self.policies.insert(&policy.policy_id, policy);
+	}
+
+	/// ask the `ClaimsManager` of the `LossContext`s to compute their losses
+	fn compute_loss(&self, loss_contexts: Vec<LossContext>) -> Promise {
+		let loss_context: LossContext = loss_contexts.last().unwrap().clone();
+		let claims_manager = loss_context.oracle_data.claims_manager.clone();
+		claims_contract::compute_loss(loss_contexts, &claims_manager, 0, COMPUTE_LOSS_GAS).then(
+			ext_self::compute_loss_callback(&env::current_account_id(), 0, COMPUTE_LOSS_CALLBACK_GAS)
+		)
+	}
+}
+
 #[ext_contract(claims_contract)]
 trait ClaimsContract {
 	fn compute_loss(loss_contexts:Vec<LossContext>)->Vec<ComputedLoss>;
 }
 
+#[ext_contract(ext_self)]
+trait PolicyManagerCallbacks {
+	fn compute_loss_callback() -> Vec<ComputedLoss>;
+	fn on_obligation_settled(loss_identity: LossIdentity) -> bool;
+	fn on_escrow_withdrawn(issuer: AccountId, asset: CapitalAsset, amount: U128) -> bool;
+}
+
+#[ext_contract(fungible_token)]
+trait FungibleToken {
+	fn ft_transfer(receiver_id: AccountId, amount: U128, memo: Option<String>);
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use near_sdk::MockedBlockchain;
+	use near_sdk::{testing_env, VMContext};
+
+	const ADMIN: &str = "admin.near";
+	const QUOTE_MANAGER: &str = "quote_manager.near";
+	const ORACLE: &str = "oracle.near";
+	const CLAIMS_MANAGER: &str = "claims_manager.near";
+	const ISSUER: &str = "issuer.near";
+	const DAY: u64 = 86_400_000_000_000;
+
+	fn usd(minor_units: u128) -> Amount {
+		Amount::new(minor_units, "USD".to_string())
+	}
+
+	fn policy_manager() -> PolicyManager {
+		testing_env!(get_context(ADMIN, 0));
+		let mut policy_manager = PolicyManager::new();
+		policy_manager.add_policy_activator(&QUOTE_MANAGER.to_string());
+		policy_manager.add_oracle(ORACLE.to_string());
+		policy_manager.set_capital_requirement(0);
+		policy_manager
+	}
+
+	fn user(user_type: UserType, id: &str, authorized_administrator: &str) -> User {
+		User {
+			user_type,
+			id: id.to_string(),
+			authorized_administrator: authorized_administrator.to_string(),
+		}
+	}
+
+	/// a quote covering days 1 to 100 for the client `client_id`, administered by `client_id.near`
+	fn quote(policy_id: &str, client_id: &str) -> Quote {
+		Quote {
+			id: policy_id.to_string(),
+			issuer: user(UserType::Issuer, "issuer", ISSUER),
+			client: user(UserType::Client, client_id, &format!("{}.near", client_id)),
+			claims_manager: CLAIMS_MANAGER.to_string(),
+			policy_type: 1,
+			product_version: 1,
+			max_payout: usd(100_000),
+			premium: Premium {
+				asset: PremiumAsset::Near,
+				amount: U128(0),
+			},
+			terms: CoverageTerms::default(),
+			coverage_period: [DAY, 100 * DAY],
+			policy_manager: "policy_manager.near".to_string(),
+			location: Location::new(25.7617, -80.1918).unwrap(),
+		}
+	}
+
+	fn activate(policy_manager: &mut PolicyManager, policy_id: &str, client_id: &str) -> Policy {
+		testing_env!(get_context(QUOTE_MANAGER, 0));
+		policy_manager.activate_policy(quote(policy_id, client_id))
+	}
+
+	fn identity(policy: &Policy, loss_id: &str) -> LossIdentity {
+		LossIdentity {
+			id: loss_id.to_string(),
+			event_id: "storm".to_string(),
+			policy_id: policy.policy_id.clone(),
+			client_id: policy.client.id.clone(),
+			issuer_id: policy.issuer.id.clone(),
+		}
+	}
+
+	fn oracle_data() -> OracleMetadata {
+		OracleMetadata {
+			triggering_values: HashMap::new(),
+			claims_manager: CLAIMS_MANAGER.to_string(),
+			oracle: ORACLE.to_string(),
+		}
+	}
+
+	/// report the event `storm` on day 2 and return what the `ClaimsManager` computed, a loss of
+	/// `payout_percent` of the policy
+	fn report_loss(
+		policy_manager: &mut PolicyManager,
+		policy: &Policy,
+		loss_id: &str,
+		payout_percent: u8,
+	) -> Vec<ComputedLoss> {
+		testing_env!(get_context(ORACLE, 2 * DAY));
+		let event = Event {
+			id: "storm".to_string(),
+			peril: Peril::Hurricane,
+			oracle: ORACLE.to_string(),
+			date: 2 * DAY,
+			start: 2 * DAY,
+			end: 2 * DAY,
+		};
+		policy_manager.event_callback(event, policy.policy_id.clone(), oracle_data());
+		let computed_losses = vec![ComputedLoss {
+			identity: identity(policy, loss_id),
+			oracle_data: oracle_data(),
+			calculations: LossCalculation {
+				payout_percent,
+				amount_due: usd(0),
+			},
+		}];
+		let returned_value = near_sdk::serde_json::to_vec(&computed_losses).unwrap();
+		callback_context(2 * DAY, PromiseResult::Successful(returned_value));
+		policy_manager.compute_loss_callback()
+	}
+
+	/// the contract calling itself back with the result of its promise
+	fn callback_context(block_timestamp: u64, promise_result: PromiseResult) {
+		testing_env!(
+			get_context("policy_manager.near", block_timestamp),
+			Default::default(),
+			Default::default(),
+			Default::default(),
+			vec![promise_result]
+		);
+	}
+
+	fn decision(accept: bool, identity: LossIdentity) -> LossDecision {
+		LossDecision {
+			accept,
+			identity,
+		}
+	}
+
+	#[test]
+	#[should_panic(expected = "POLICY_NOT_FOUND")]
+	fn a_loss_decision_needs_an_existing_policy() {
+		let mut policy_manager = policy_manager();
+		let policy = activate(&mut policy_manager, "policy", "client");
+		report_loss(&mut policy_manager, &policy, "loss", 10);
+		let mut forged = identity(&policy, "loss");
+		forged.policy_id = "missing".to_string();
+		testing_env!(get_context("intruder.near", 3 * DAY));
+		policy_manager.post_loss_decision(decision(false, forged));
+	}
+
+	#[test]
+	#[should_panic(expected = "Not Authorized to Confirm loss for this client.")]
+	fn only_the_client_decides_a_loss() {
+		let mut policy_manager = policy_manager();
+		let policy = activate(&mut policy_manager, "policy", "client");
+		report_loss(&mut policy_manager, &policy, "loss", 10);
+		testing_env!(get_context("intruder.near", 3 * DAY));
+		policy_manager.post_loss_decision(decision(true, identity(&policy, "loss")));
+	}
 
+	#[test]
+	#[should_panic(expected = "COMPUTED_LOSS_NOT_FOUND_IN_POLICY")]
+	fn a_client_cannot_decide_the_loss_of_another_policy() {
+		let mut policy_manager = policy_manager();
+		let own_policy = activate(&mut policy_manager, "own", "client");
+		let other_policy = activate(&mut policy_manager, "other", "victim");
+		report_loss(&mut policy_manager, &other_policy, "victim-loss", 10);
+		let mut forged = identity(&other_policy, "victim-loss");
+		forged.policy_id = own_policy.policy_id;
+		testing_env!(get_context("client.near", 3 * DAY));
+		policy_manager.post_loss_decision(decision(false, forged));
+	}
 
+	#[test]
+	fn a_loss_decision_uses_the_recorded_identity() {
+		let mut policy_manager = policy_manager();
+		let policy = activate(&mut policy_manager, "own", "client");
+		let other_policy = activate(&mut policy_manager, "other", "victim");
+		report_loss(&mut policy_manager, &policy, "loss", 10);
+		report_loss(&mut policy_manager, &other_policy, "victim-loss", 10);
+		let mut forged = identity(&policy, "loss");
+		forged.client_id = "victim".to_string();
+		forged.issuer_id = "intruder".to_string();
+		forged.event_id = "another-storm".to_string();
+		testing_env!(get_context("client.near", 3 * DAY));
+		let posted = policy_manager.post_loss_decision(decision(true, forged));
+		assert_eq!(posted.identity.client_id, "client".to_string());
+		assert!(policy_manager.get_pending_loss_decisions("client".to_string()).is_empty());
+		assert_eq!(policy_manager.get_pending_loss_decisions("victim".to_string()).len(), 1);
+		assert_eq!(policy_manager.obligations.get(&"issuer".to_string()).unwrap().len(), 1);
+		assert!(policy_manager.obligations.get(&"intruder".to_string()).is_none());
+	}
 
+	#[test]
+	fn a_cancelled_policy_pays_its_obligations_before_closing() {
+		let mut policy_manager = policy_manager();
+		let policy = activate(&mut policy_manager, "policy", "client");
+		report_loss(&mut policy_manager, &policy, "loss", 10);
+		testing_env!(get_context("client.near", 3 * DAY));
+		policy_manager.post_loss_decision(decision(true, identity(&policy, "loss")));
+		testing_env!(get_context(ISSUER, 4 * DAY));
+		policy_manager.update_policy_status("policy".to_string(), PolicyStatus::Cancelled);
+		testing_env!(get_context(QUOTE_MANAGER, 5 * DAY));
+		policy_manager.post_payment_made(ResolveObligation {
+			identity: identity(&policy, "loss"),
+			payment_proof: "wire-1".to_string(),
+		});
+		assert_eq!(policy_manager.get_policy_balance(&"policy".to_string()), usd(90_000));
+		testing_env!(get_context(ISSUER, 6 * DAY));
+		let status = policy_manager.update_policy_status("policy".to_string(), PolicyStatus::Closed);
+		assert_eq!(status, PolicyStatus::Closed);
+	}
 
+	#[test]
+	#[should_panic(expected = "POLICY_HAS_OUTSTANDING_LOSSES")]
+	fn a_cancelled_policy_does_not_close_over_its_obligations() {
+		let mut policy_manager = policy_manager();
+		let policy = activate(&mut policy_manager, "policy", "client");
+		report_loss(&mut policy_manager, &policy, "loss", 10);
+		testing_env!(get_context("client.near", 3 * DAY));
+		policy_manager.post_loss_decision(decision(true, identity(&policy, "loss")));
+		testing_env!(get_context(ISSUER, 4 * DAY));
+		policy_manager.update_policy_status("policy".to_string(), PolicyStatus::Cancelled);
+		policy_manager.update_policy_status("policy".to_string(), PolicyStatus::Closed);
+	}
+
+	/// the issuer pays on-chain in `usd.near`, one token unit per cent, and holds `balance` of it
+	fn settle_on_chain(policy_manager: &mut PolicyManager, balance: u128) {
+		testing_env!(get_context(ADMIN, 0));
+		policy_manager.set_settlement_token(
+			"USD".to_string(),
+			SettlementToken {
+				asset: CapitalAsset::FungibleToken("usd.near".to_string()),
+				units_per_minor_unit: U128(1),
+			},
+		);
+		testing_env!(get_context("usd.near", 0));
+		policy_manager.ft_on_transfer(ISSUER.to_string(), U128(balance), String::new());
+		testing_env!(get_context(ISSUER, 0));
+		policy_manager.set_settlement_mode(SettlementMode::OnChain);
+	}
+
+	#[test]
+	fn an_obligation_is_settled_to_the_recorded_client() {
+		let mut policy_manager = policy_manager();
+		settle_on_chain(&mut policy_manager, 1_000_000);
+		let policy = activate(&mut policy_manager, "policy", "client");
+		activate(&mut policy_manager, "other", "intruder");
+		report_loss(&mut policy_manager, &policy, "loss", 10);
+		// without a payout account the accepted loss waits for `settle_obligation`
+		testing_env!(get_context("client.near", 3 * DAY));
+		policy_manager.post_loss_decision(decision(true, identity(&policy, "loss")));
+		assert!(policy_manager.get_settlement("loss".to_string()).is_none());
+		policy_manager.register_payout_account("client".to_string(), "client_wallet.near".to_string());
+		testing_env!(get_context("intruder.near", 3 * DAY));
+		policy_manager.register_payout_account("intruder".to_string(), "intruder_wallet.near".to_string());
+		let mut forged = identity(&policy, "loss");
+		forged.client_id = "intruder".to_string();
+		forged.issuer_id = "intruder".to_string();
+		policy_manager.settle_obligation(forged);
+		let settlement = policy_manager.get_settlement("loss".to_string()).unwrap();
+		assert_eq!(settlement.receiver, "client_wallet.near".to_string());
+		assert_eq!(settlement.identity.client_id, "client".to_string());
+		assert_eq!(settlement.identity.issuer_id, "issuer".to_string());
+	}
+
+	fn usd_token() -> CapitalAsset {
+		CapitalAsset::FungibleToken("usd.near".to_string())
+	}
+
+	#[test]
+	#[should_panic(expected = "POLICY_MANAGER_RESTRICTED")]
+	fn activate_policy_is_restricted_to_policy_managers() {
+		let mut policy_manager = policy_manager();
+		testing_env!(get_context("intruder.near", 0));
+		policy_manager.activate_policy(quote("policy", "client"));
+	}
+
+	#[test]
+	#[should_panic(expected = "POLICY_ALREADY_EXISTS")]
+	fn a_policy_is_activated_once() {
+		let mut policy_manager = policy_manager();
+		activate(&mut policy_manager, "policy", "client");
+		activate(&mut policy_manager, "policy", "client");
+	}
+
+	#[test]
+	#[should_panic(expected = "INSUFFICIENT_FREE_CAPITAL")]
+	fn activation_locks_the_issuer_capital() {
+		let mut policy_manager = policy_manager();
+		settle_on_chain(&mut policy_manager, 100_000);
+		testing_env!(get_context(ADMIN, 0));
+		policy_manager.set_capital_requirement(FULL_CAPITAL_REQUIREMENT);
+		let policy = activate(&mut policy_manager, "policy", "client");
+		assert_eq!(policy.capital_lock.unwrap().locked, U128(100_000));
+		assert_eq!(policy_manager.get_escrow(ISSUER.to_string()).free(&usd_token()), 0);
+		activate(&mut policy_manager, "another", "client");
+	}
+
+	#[test]
+	fn policies_are_listed_per_client() {
+		let mut policy_manager = policy_manager();
+		for policy_id in ["a", "b", "c"] {
+			activate(&mut policy_manager, policy_id, "client");
+		}
+		activate(&mut policy_manager, "d", "other");
+		let policies = policy_manager.get_policies_for_client("client".to_string(), 1, 1);
+		assert_eq!(policies.len(), 1);
+		assert_eq!(policies[0].policy_id, "b".to_string());
+		assert_eq!(policy_manager.get_policies_for_client("client".to_string(), 0, 10).len(), 3);
+		assert!(policy_manager.get_policies_for_client("nobody".to_string(), 0, 10).is_empty());
+	}
+
+	#[test]
+	#[should_panic(expected = "ORACLE_RESTRICTED")]
+	fn event_callback_is_restricted_to_oracles() {
+		let mut policy_manager = policy_manager();
+		let policy = activate(&mut policy_manager, "policy", "client");
+		testing_env!(get_context("intruder.near", 2 * DAY));
+		let event = Event {
+			id: "storm".to_string(),
+			peril: Peril::Hurricane,
+			oracle: "intruder.near".to_string(),
+			date: 2 * DAY,
+			start: 2 * DAY,
+			end: 2 * DAY,
+		};
+		policy_manager.event_callback(event, policy.policy_id, oracle_data());
+	}
+
+	#[test]
+	#[should_panic(expected = "WRONG_CLAIMS_MANAGER")]
+	fn event_callback_uses_the_claims_manager_of_the_policy() {
+		let mut policy_manager = policy_manager();
+		let policy = activate(&mut policy_manager, "policy", "client");
+		testing_env!(get_context(ORACLE, 2 * DAY));
+		let event = Event {
+			id: "storm".to_string(),
+			peril: Peril::Hurricane,
+			oracle: ORACLE.to_string(),
+			date: 2 * DAY,
+			start: 2 * DAY,
+			end: 2 * DAY,
+		};
+		let mut oracle_data = oracle_data();
+		oracle_data.claims_manager = "generous_claims_manager.near".to_string();
+		policy_manager.event_callback(event, policy.policy_id, oracle_data);
+	}
+
+	#[test]
+	fn a_raised_loss_owes_only_the_increase() {
+		let mut policy_manager = policy_manager();
+		let policy = activate(&mut policy_manager, "policy", "client");
+		let recorded = report_loss(&mut policy_manager, &policy, "loss-10", 10);
+		assert_eq!(recorded[0].calculations.amount_due, usd(10_000));
+		// the same loss is only recorded once
+		assert!(report_loss(&mut policy_manager, &policy, "loss-10", 10).is_empty());
+		let recorded = report_loss(&mut policy_manager, &policy, "loss-30", 30);
+		assert_eq!(recorded[0].calculations.amount_due, usd(20_000));
+		let pending = policy_manager.get_pending_loss_decisions("client".to_string());
+		assert_eq!(pending.len(), 2);
+		assert_eq!(policy_manager.get_policy(policy.policy_id).unwrap().pending_balance, usd(70_000));
+	}
+
+	#[test]
+	fn a_rejected_loss_returns_to_the_pending_balance() {
+		let mut policy_manager = policy_manager();
+		let policy = activate(&mut policy_manager, "policy", "client");
+		report_loss(&mut policy_manager, &policy, "loss", 10);
+		testing_env!(get_context("client.near", 3 * DAY));
+		policy_manager.post_loss_decision(decision(false, identity(&policy, "loss")));
+		assert!(policy_manager.get_pending_loss_decisions("client".to_string()).is_empty());
+		let policy = policy_manager.get_policy(policy.policy_id).unwrap();
+		assert_eq!(policy.pending_balance, usd(100_000));
+		assert_eq!(policy.events["storm"].percent_due(), 10);
+	}
+
+	#[test]
+	#[should_panic(expected = "NOT_AUTHORIZED_TO_UPDATE_STATUS")]
+	fn only_the_issuer_or_the_admin_updates_the_status() {
+		let mut policy_manager = policy_manager();
+		activate(&mut policy_manager, "policy", "client");
+		testing_env!(get_context("client.near", 2 * DAY));
+		policy_manager.update_policy_status("policy".to_string(), PolicyStatus::Suspended);
+	}
+
+	#[test]
+	#[should_panic(expected = "STATUS_IS_AUTOMATIC")]
+	fn expiry_is_automatic() {
+		let mut policy_manager = policy_manager();
+		activate(&mut policy_manager, "policy", "client");
+		testing_env!(get_context(ADMIN, 2 * DAY));
+		policy_manager.update_policy_status("policy".to_string(), PolicyStatus::Expired);
+	}
+
+	#[test]
+	fn a_policy_expires_after_its_coverage_period() {
+		let mut policy_manager = policy_manager();
+		let policy = activate(&mut policy_manager, "policy", "client");
+		assert_eq!(policy.status, PolicyStatus::Pending);
+		testing_env!(get_context("anyone.near", 2 * DAY));
+		assert_eq!(policy_manager.refresh_policy_status("policy".to_string()), PolicyStatus::Active);
+		testing_env!(get_context("anyone.near", 101 * DAY));
+		assert_eq!(policy_manager.refresh_policy_status("policy".to_string()), PolicyStatus::Expired);
+	}
+
+	/// an accepted loss of 10 percent of the policy, settled on-chain to `client_wallet.near`
+	fn settling_policy_manager() -> PolicyManager {
+		let mut policy_manager = policy_manager();
+		settle_on_chain(&mut policy_manager, 1_000_000);
+		let policy = activate(&mut policy_manager, "policy", "client");
+		report_loss(&mut policy_manager, &policy, "loss", 10);
+		testing_env!(get_context("client.near", 3 * DAY));
+		policy_manager.register_payout_account("client".to_string(), "client_wallet.near".to_string());
+		policy_manager.post_loss_decision(decision(true, identity(&policy, "loss")));
+		assert!(policy_manager.get_settlement("loss".to_string()).is_some());
+		assert_eq!(policy_manager.get_escrow(ISSUER.to_string()).balance(&usd_token()), 990_000);
+		policy_manager
+	}
+
+	#[test]
+	fn a_settled_obligation_is_paid() {
+		let mut policy_manager = settling_policy_manager();
+		let policy = policy_manager.get_policy("policy".to_string()).unwrap();
+		callback_context(3 * DAY, PromiseResult::Successful(Vec::new()));
+		assert!(policy_manager.on_obligation_settled(identity(&policy, "loss")));
+		assert!(policy_manager.get_settlement("loss".to_string()).is_none());
+		assert_eq!(policy_manager.get_policy_balance(&"policy".to_string()), usd(90_000));
+		assert!(policy_manager.obligations.get(&"issuer".to_string()).unwrap().is_empty());
+	}
+
+	#[test]
+	fn a_failed_settlement_returns_to_the_escrow() {
+		let mut policy_manager = settling_policy_manager();
+		let policy = policy_manager.get_policy("policy".to_string()).unwrap();
+		callback_context(3 * DAY, PromiseResult::Failed);
+		assert!(!policy_manager.on_obligation_settled(identity(&policy, "loss")));
+		assert!(policy_manager.get_settlement("loss".to_string()).is_none());
+		assert_eq!(policy_manager.get_escrow(ISSUER.to_string()).balance(&usd_token()), 1_000_000);
+		assert_eq!(policy_manager.get_policy_balance(&"policy".to_string()), usd(100_000));
+		// the obligation can be settled again
+		assert_eq!(policy_manager.obligations.get(&"issuer".to_string()).unwrap().len(), 1);
+	}
+
+	#[test]
+	fn a_failed_withdrawal_returns_to_the_escrow() {
+		let mut policy_manager = policy_manager();
+		settle_on_chain(&mut policy_manager, 1_000);
+		testing_env!(get_context(ISSUER, 0));
+		policy_manager.withdraw_escrow(usd_token(), U128(400));
+		assert_eq!(policy_manager.get_escrow(ISSUER.to_string()).balance(&usd_token()), 600);
+		callback_context(0, PromiseResult::Failed);
+		assert!(!policy_manager.on_escrow_withdrawn(ISSUER.to_string(), usd_token(), U128(400)));
+		assert_eq!(policy_manager.get_escrow(ISSUER.to_string()).balance(&usd_token()), 1_000);
+	}
+
+	#[test]
+	#[should_panic(expected = "INSUFFICIENT_FREE_CAPITAL")]
+	fn locked_capital_is_not_withdrawn() {
+		let mut policy_manager = policy_manager();
+		settle_on_chain(&mut policy_manager, 100_000);
+		testing_env!(get_context(ADMIN, 0));
+		policy_manager.set_capital_requirement(FULL_CAPITAL_REQUIREMENT);
+		activate(&mut policy_manager, "policy", "client");
+		testing_env!(get_context(ISSUER, 0));
+		policy_manager.withdraw_escrow(usd_token(), U128(1));
+	}
+
+	fn get_context(predecessor_account_id: &str, block_timestamp: u64) -> VMContext {
+		VMContext {
+			current_account_id: "policy_manager.near".to_string(),
+			signer_account_id: predecessor_account_id.to_string(),
+			signer_account_pk: vec![0, 1, 2],
+			predecessor_account_id: predecessor_account_id.to_string(),
+			input: vec![],
+			block_index: 0,
+			block_timestamp,
+			account_balance: 10u128.pow(24),
+			account_locked_balance: 0,
+			// storage written under an earlier context is still there
+			storage_usage: 10u64.pow(6),
+			attached_deposit: 0,
+			prepaid_gas: 10u64.pow(18),
+			random_seed: vec![0, 1, 2],
+			is_view: false,
+			output_data_receivers: vec![],
+			epoch_height: 19,
+		}
+	}
+}
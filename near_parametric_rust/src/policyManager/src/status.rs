@@ -0,0 +1,95 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::AccountId;
+
+///# description
+///
+/// the lifecycle of a `Policy`
+///
+///# variants
+/// * `Pending` issued but the coverage period has not started
+/// * `Active` within the coverage period and able to accept losses
+/// * `Suspended` temporarily unable to accept new losses
+/// * `Exhausted` the `Policy` balance has been paid out in full
+/// * `Expired` the coverage period has ended. outstanding losses can still be settled
+/// * `Cancelled` terminated before the end of the coverage period. losses computed before it can still
+///   be decided and settled
+/// * `Closed` terminal state. nothing is outstanding
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(crate = "near_sdk::serde")]
+pub enum PolicyStatus {
+	Pending,
+	Active,
+	Suspended,
+	Exhausted,
+	Expired,
+	Cancelled,
+	Closed,
+}
+
+impl PolicyStatus {
+	/// is moving from `self` to `next` a valid lifecycle step
+	pub fn can_transition_to(&self, next: &PolicyStatus) -> bool {
+		use PolicyStatus::*;
+		matches!(
+			(self, next),
+			(Pending, Active)
+					| (Pending, Expired)
+					| (Pending, Cancelled)
+					| (Active, Suspended)
+					| (Active, Exhausted)
+					| (Active, Expired)
+					| (Active, Cancelled)
+					| (Suspended, Active)
+					| (Suspended, Exhausted)
+					| (Suspended, Expired)
+					| (Suspended, Cancelled)
+					| (Exhausted, Closed)
+					| (Expired, Closed)
+					| (Cancelled, Closed)
+		)
+	}
+}
+
+///# description
+///
+/// an audit record of a `PolicyStatus` change
+///
+///# fields
+/// * `from` the previous status
+/// * `to` the new status
+/// * `timestamp` block timestamp of the change
+/// * `caller` `near_sdk::env::predecessor_account_id()` of the call that caused the change
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Debug, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct StatusTransition {
+	pub from: PolicyStatus,
+	pub to: PolicyStatus,
+	pub timestamp: u64,
+	pub caller: AccountId,
+}
+
+#[cfg(test)]
+mod tests {
+	use super::PolicyStatus::*;
+
+	#[test]
+	fn terminal_states_do_not_reopen() {
+		assert!(!Closed.can_transition_to(&Active));
+		assert!(!Cancelled.can_transition_to(&Active));
+		assert!(!Exhausted.can_transition_to(&Active));
+		assert!(!Expired.can_transition_to(&Active));
+	}
+
+	#[test]
+	fn suspension_is_reversible() {
+		assert!(Active.can_transition_to(&Suspended));
+		assert!(Suspended.can_transition_to(&Active));
+	}
+
+	#[test]
+	fn pending_must_activate_before_exhausting() {
+		assert!(!Pending.can_transition_to(&Exhausted));
+		assert!(Pending.can_transition_to(&Active));
+	}
+}
@@ -0,0 +1,144 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::serde::{Deserialize, Serialize};
+use std::fmt;
+
+const HOUR: u64 = 3_600_000_000_000;
+
+///# description
+///
+/// how late an `Event` may be reported for a `Policy`. mirrors `SubmissionRules` in the oracle.
+/// an event must begin within the coverage period, and the date it triggered the policy must fall
+/// within the coverage period or the grace window after it
+///
+///# fields
+/// * `grace_period` {`u64`} how long after `end_date` an event that began before it may still
+///   trigger the policy, in nanoseconds
+/// * `reporting_deadline` {`u64`} how long after the event ends, or the grace window closes if
+///   that is earlier, the event may still be reported, in nanoseconds. it should be no shorter than
+///   the oracle's, plus its dispute window when the oracle runs in optimistic mode
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct SubmissionRules {
+	pub grace_period: u64,
+	pub reporting_deadline: u64,
+}
+
+///# description
+///
+/// why an `Event` was refused by the `SubmissionRules`
+#[derive(Debug, PartialEq, Eq)]
+pub enum SubmissionError {
+	EventBeforeCoverage,
+	EventAfterCoverage,
+	EventOutsideCoverage,
+	SubmissionTooLate,
+}
+
+impl fmt::Display for SubmissionError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match *self {
+			SubmissionError::EventBeforeCoverage => write!(f, "EVENT_BEFORE_COVERAGE"),
+			SubmissionError::EventAfterCoverage => write!(f, "EVENT_AFTER_COVERAGE"),
+			SubmissionError::EventOutsideCoverage => write!(f, "EVENT_OUTSIDE_COVERAGE"),
+			SubmissionError::SubmissionTooLate => write!(f, "EVENT_SUBMISSION_TOO_LATE"),
+		}
+	}
+}
+
+impl Default for SubmissionRules {
+	fn default() -> Self {
+		Self {
+			grace_period: 72 * HOUR,
+			reporting_deadline: 31 * 24 * HOUR,
+		}
+	}
+}
+
+impl SubmissionRules {
+	/// the last moment an event may be reported for a policy whose coverage ends at `end_date`
+	pub fn closes_at(&self, end_date: u64) -> u64 {
+		end_date.saturating_add(self.grace_period).saturating_add(self.reporting_deadline)
+	}
+
+	/// may an event that ran over `event_period` and triggered at `date` be reported at `now` for
+	/// a policy covering `coverage`
+	pub fn check(
+		&self,
+		coverage: [u64; 2],
+		event_period: [u64; 2],
+		date: u64,
+		now: u64,
+	) -> Result<(), SubmissionError> {
+		let [start_date, end_date] = coverage;
+		let [event_start, event_end] = event_period;
+		if event_start < start_date {
+			return Err(SubmissionError::EventBeforeCoverage);
+		}
+		if event_start > end_date {
+			return Err(SubmissionError::EventAfterCoverage);
+		}
+		let grace_end = end_date.saturating_add(self.grace_period);
+		if date < start_date || date > grace_end {
+			return Err(SubmissionError::EventOutsideCoverage);
+		}
+		if now > event_end.min(grace_end).saturating_add(self.reporting_deadline) {
+			return Err(SubmissionError::SubmissionTooLate);
+		}
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const COVERAGE: [u64; 2] = [1_000 * HOUR, 2_000 * HOUR];
+
+	#[test]
+	fn backdated_events_are_refused() {
+		let rules = SubmissionRules::default();
+		assert_eq!(
+			rules.check(COVERAGE, [990 * HOUR, 1_010 * HOUR], 1_005 * HOUR, 1_100 * HOUR),
+			Err(SubmissionError::EventBeforeCoverage)
+		);
+		assert_eq!(
+			rules.check(COVERAGE, [2_010 * HOUR, 2_020 * HOUR], 2_015 * HOUR, 2_100 * HOUR),
+			Err(SubmissionError::EventAfterCoverage)
+		);
+	}
+
+	#[test]
+	fn events_continuing_after_expiry_get_a_grace_window() {
+		let rules = SubmissionRules::default();
+		let event = [1_999 * HOUR, 2_200 * HOUR];
+		assert_eq!(rules.check(COVERAGE, event, 2_050 * HOUR, 2_100 * HOUR), Ok(()));
+		assert_eq!(
+			rules.check(COVERAGE, event, 2_080 * HOUR, 2_100 * HOUR),
+			Err(SubmissionError::EventOutsideCoverage)
+		);
+	}
+
+	#[test]
+	fn late_submissions_are_refused() {
+		let rules = SubmissionRules::default();
+		let event = [1_500 * HOUR, 1_520 * HOUR];
+		let deadline = 1_520 * HOUR + rules.reporting_deadline;
+		assert_eq!(rules.check(COVERAGE, event, 1_510 * HOUR, deadline), Ok(()));
+		assert_eq!(
+			rules.check(COVERAGE, event, 1_510 * HOUR, deadline + 1).map_err(|error| error.to_string()),
+			Err("EVENT_SUBMISSION_TOO_LATE".to_string())
+		);
+	}
+
+	#[test]
+	fn nothing_is_reported_after_closes_at() {
+		let rules = SubmissionRules::default();
+		let closes_at = rules.closes_at(COVERAGE[1]);
+		let event = [1_999 * HOUR, 2_500 * HOUR];
+		assert_eq!(rules.check(COVERAGE, event, 2_050 * HOUR, closes_at), Ok(()));
+		assert_eq!(
+			rules.check(COVERAGE, event, 2_050 * HOUR, closes_at + 1),
+			Err(SubmissionError::SubmissionTooLate)
+		);
+	}
+}
@@ -0,0 +1,43 @@
+use crate::Amount;
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::serde::{Deserialize, Serialize};
+
+/// the length of a policy year, counted from the `Policy` start date, in nanoseconds
+pub const POLICY_YEAR: u64 = 365 * 24 * 3_600_000_000_000;
+
+///# description
+///
+/// how the amount the `ClaimsManager` computed for a loss was reduced to the amount owed. every
+/// reduction is taken from `gross_loss` in the order of the fields, so
+/// `gross_loss - deductible - event_limit_excess - previous_net_loss - aggregate_excess - balance_excess`
+/// is `amount_owed`
+///
+///# fields
+/// * `loss_id` {`String`} the id of the `ComputedLoss`
+/// * `previous_percent` {`u8`} the event's payout percent before this loss
+/// * `payout_percent` {`u8`} the event's payout percent after this loss
+/// * `computed_amount` {`Amount`} the amount due returned by the `ClaimsManager`
+/// * `gross_loss` {`Amount`} `payout_percent` of the event's balance snapshot
+/// * `deductible` {`Amount`} retained by the deductible
+/// * `event_limit_excess` {`Amount`} above the event limit
+/// * `previous_net_loss` {`Amount`} the event's loss after the terms at `previous_percent`, settled by
+///   earlier losses whether they were owed, capped or rejected by the client
+/// * `aggregate_excess` {`Amount`} above what the aggregate limit of the policy year had left
+/// * `balance_excess` {`Amount`} above what the policy's pending balance had left
+/// * `amount_owed` {`Amount`} the amount due of the recorded `ComputedLoss`. nothing is recorded when
+///   it is zero
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct LossBreakdown {
+	pub loss_id: String,
+	pub previous_percent: u8,
+	pub payout_percent: u8,
+	pub computed_amount: Amount,
+	pub gross_loss: Amount,
+	pub deductible: Amount,
+	pub event_limit_excess: Amount,
+	pub previous_net_loss: Amount,
+	pub aggregate_excess: Amount,
+	pub balance_excess: Amount,
+	pub amount_owed: Amount,
+}
@@ -0,0 +1,112 @@
+use near_sdk::json_types::U128;
+use near_sdk::serde::Serialize;
+use near_sdk::{env, AccountId};
+
+use crate::PremiumAsset;
+
+/// the NEP-297 `standard` of every event emitted by `QuoteManager`
+pub const EVENT_STANDARD: &str = "parametric_quote_manager";
+/// bumped whenever the shape of an existing event's `data` changes
+pub const EVENT_STANDARD_VERSION: &str = "1.0.0";
+
+///#Description
+///
+/// a state change in `QuoteManager` that is logged for indexers as `EVENT_JSON:{...}`
+/// following NEP-297. `event` is the snake case name of the variant and `data` a one element array
+#[derive(Serialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+#[serde(tag = "event", content = "data", rename_all = "snake_case")]
+pub enum QuoteManagerEvent {
+    QuoteIssued([QuoteIssuedData; 1]),
+    QuoteRemoved([QuoteData; 1]),
+    PolicyIssued([QuoteData; 1]),
+    DaysValidChanged([DaysValidData; 1]),
+    IssuerAdded([DaysValidData; 1]),
+    IssuerRemoved([AccountData; 1]),
+    OwnerChanged([AccountData; 1]),
+    PremiumPaid([PremiumData; 1]),
+    PremiumRefunded([PremiumData; 1]),
+    RefundHeld([PremiumBalanceData; 1]),
+    RefundClaimed([PremiumBalanceData; 1]),
+    PremiumCollected([PremiumData; 1]),
+    PremiumWithdrawn([PremiumBalanceData; 1]),
+    PremiumTokenAdded([AccountData; 1]),
+    PremiumTokenRemoved([AccountData; 1]),
+}
+
+#[derive(Serialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct QuoteIssuedData {
+    pub quote_id: String,
+    pub client: String,
+    pub issuer: AccountId,
+    pub accept_deadline: u64,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct QuoteData {
+    pub quote_id: String,
+    pub client: String,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct DaysValidData {
+    pub issuer: AccountId,
+    pub days_valid: u64,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct AccountData {
+    pub account_id: AccountId,
+}
+
+///#Description
+///
+/// a premium moving for a quote. `account_id` is the payer, or the issuer it was collected for
+#[derive(Serialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct PremiumData {
+    pub quote_id: String,
+    pub account_id: AccountId,
+    pub asset: PremiumAsset,
+    pub amount: U128,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct PremiumBalanceData {
+    pub account_id: AccountId,
+    pub asset: PremiumAsset,
+    pub amount: U128,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+struct EventLog<'a> {
+    standard: &'static str,
+    version: &'static str,
+    #[serde(flatten)]
+    event: &'a QuoteManagerEvent,
+}
+
+impl QuoteManagerEvent {
+    /// log the event as `EVENT_JSON:{...}`
+    pub fn emit(&self) {
+        env::log(self.to_log_string().as_bytes());
+    }
+
+    fn to_log_string(&self) -> String {
+        let log = EventLog {
+            standard: EVENT_STANDARD,
+            version: EVENT_STANDARD_VERSION,
+            event: self,
+        };
+        format!(
+            "EVENT_JSON:{}",
+            near_sdk::serde_json::to_string(&log).expect("ERR_EVENT_SERIALIZATION")
+        )
+    }
+}
@@ -1,37 +1,95 @@
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
 use near_sdk::collections::UnorderedMap;
-use near_sdk::{ext_contract, init, near_bindgen, AccountId, BorshStorageKey, PanicOnDefault};
-use std::collections::hash_map::DefaultHasher;
-use std::collections::HashMap;
-use std::hash::{Hash, Hasher};
-use std::panic;
+use near_sdk::json_types::U128;
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::{
+    env, ext_contract, near_bindgen, AccountId, BorshStorageKey, PanicOnDefault, Promise,
+    PromiseOrValue, PromiseResult,
+};
+
+mod events;
+mod premium;
+
+use crate::events::{
+    AccountData, DaysValidData, PremiumBalanceData, PremiumData, QuoteData, QuoteIssuedData,
+    QuoteManagerEvent,
+};
+pub use crate::premium::{Premium, PremiumAsset, PremiumPayment};
+pub use amount::Amount;
+pub use coverage::CoverageTerms;
+pub use location::Location;
 
 //#Description Stroage key enum for NEAR Protocoll persistent storage
 #[derive(BorshStorageKey, BorshSerialize)]
 pub enum StorageKeys {
     UndecidedQuotes,
     DaysValid,
+    PremiumBalances,
+    Refunds,
 }
+
+/// gas attached to `PolicyManager::activate_policy`
+const ACTIVATE_POLICY_GAS: u64 = 20_000_000_000_000;
+/// gas attached to the `on_policy_activated` callback
+const ON_POLICY_ACTIVATED_GAS: u64 = 10_000_000_000_000;
+/// gas attached to `ft_transfer` when a token premium is refunded or withdrawn
+const FT_TRANSFER_GAS: u64 = 10_000_000_000_000;
+/// gas attached to the `on_premium_withdrawn` callback
+const ON_PREMIUM_WITHDRAWN_GAS: u64 = 10_000_000_000_000;
+/// gas attached to the `on_premium_refunded` callback
+const ON_PREMIUM_REFUNDED_GAS: u64 = 10_000_000_000_000;
+
 ///#Description
 ///
-///  `Quote` is an offer from an insurer that has been accepted by a client
-#[near_bindgen]
-#[derive(BorshDeserialize, BorshSerialize, Debug, PartialEq, Clone)]
+///  `Quote` is an offer from an insurer that has been accepted by a client.
+/// it has the same shape as the `Quote` that `PolicyManager::activate_policy` accepts
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(crate = "near_sdk::serde")]
 pub struct Quote {
-    ///unique to each client
-    client: String,
     ///unique to each quote
     id: String,
-    ///Trigger contracts define the logic and oracle data that validates policy triggers
-    triggers_contract: AccountId,
-    ///Triggers are the threshold value that executes payment for a poilcy
-    triggers: HashMap<String, i32>,
+    ///the party issuing the `Quote`
+    issuer: User,
+    ///the potential purchaser of the policy
+    client: User,
+    ///the contract that determines if a loss is due under this quote's triggers
+    claims_manager: AccountId,
+    ///the protection option that this quote represents
+    policy_type: u8,
+    ///the version of the `policy_type` payout table this quote is sold under
+    product_version: u32,
     ///maxmim value total value of the policy
-    max_payout: u32,
-    ///a representation of client location. Can be Z curve or geohash or something else
-    location: String,
+    max_payout: Amount,
+    ///the price of the policy, paid by the client to bind the quote
+    premium: Premium,
+    ///deductible, limits and reinstatements applied to every loss of the policy
+    terms: CoverageTerms,
     ///the period that a policy will be valid.
     coverage_period: [u64; 2],
+    ///the `PolicyManager` that will maintain the policy
+    policy_manager: AccountId,
+    ///location under policy protection
+    location: Location,
+}
+
+///#Description
+///
+/// a party to a `Quote`. mirrors `User` in `PolicyManager`
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct User {
+    user_type: UserType,
+    id: String,
+    authorized_administrator: AccountId,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub enum UserType {
+    PayoutAuthority,
+    PaymentProcessor,
+    Client,
+    Issuer,
 }
 
 //implement data valildation
@@ -45,6 +103,20 @@ pub struct UndecidedQuote {
     quote: Quote,
     ///the deadline in nanoseconds before the Quote becomes invalid
     accept_deadline: u64,
+    ///what the client has paid towards the premium
+    payment: Option<PremiumPayment>,
+    ///whether the policy is being activated. the quote can neither be paid nor removed meanwhile
+    activating: bool,
+}
+
+impl UndecidedQuote {
+    fn paid(&self) -> u128 {
+        self.payment.as_ref().map_or(0, |payment| payment.paid.0)
+    }
+
+    fn is_paid(&self) -> bool {
+        self.quote.premium.is_paid(self.paid())
+    }
 }
 
 ///#Description
@@ -62,6 +134,12 @@ pub struct QuoteManager {
     quote_issuers: Vec<AccountId>,
     ///what days have the quote issuers determined to be the period of time that a quote remains valid
     standard_days_valid: UnorderedMap<AccountId, u64>,
+    ///the NEP-141 tokens premiums can be paid in
+    premium_tokens: Vec<AccountId>,
+    ///premiums of activated policies held for their issuers, keyed by issuer and asset
+    premium_balances: UnorderedMap<(AccountId, PremiumAsset), U128>,
+    ///refunds whose transfer failed, held for their payers until claimed, keyed by payer and asset
+    refunds: UnorderedMap<(AccountId, PremiumAsset), U128>,
 }
 
 #[near_bindgen]
@@ -75,80 +153,391 @@ impl QuoteManager {
                 StorageKeys::UndecidedQuotes,
             ),
             quote_issuers: Vec::new(),
-            standard_days_valid: HashMap::new(Storage::Keys::DaysValid),
+            standard_days_valid: UnorderedMap::new(StorageKeys::DaysValid),
+            premium_tokens: Vec::new(),
+            premium_balances: UnorderedMap::new(StorageKeys::PremiumBalances),
+            refunds: UnorderedMap::new(StorageKeys::Refunds),
         }
     }
 
     ///a quote is issued with a set , predetermined, valid time period.
     /// a quote does not become a policy until accepted by client
+    #[allow(clippy::too_many_arguments)]
     pub fn issue_quote(
         &mut self,
-        client: String,
         id: String,
-        triggers_contract: AccountId,
-        triggers: HashMap<String, i32>,
-        max_payout: u32,
-        location: String,
+        client: User,
+        claims_manager: AccountId,
+        policy_type: u8,
+        product_version: u32,
+        max_payout: Amount,
+        premium: Premium,
+        terms: CoverageTerms,
+        location: Location,
         coverage_period: [u64; 2],
+        policy_manager: AccountId,
     ) {
         assert!(
             self.quote_issuers
                 .contains(&near_sdk::env::predecessor_account_id()),
             "Not permitted."
         );
+        assert!(
+            coverage_period[0] < coverage_period[1],
+            "invalid coverage period"
+        );
+        // the `PolicyManager` refuses to activate a policy whose terms do not fit its payout
+        if let Some(error) = terms.validation_error(&max_payout) {
+            near_sdk::env::panic(error.as_bytes());
+        }
+        if let PremiumAsset::FungibleToken(token) = &premium.asset {
+            assert!(
+                self.premium_tokens.contains(token),
+                "premium token not accepted"
+            );
+        }
+        // normalized so the policy for this asset is keyed the same way in every contract
+        let location = location
+            .normalized()
+            .unwrap_or_else(|error| near_sdk::env::panic(error.to_string().as_bytes()));
+        let issuer = User {
+            user_type: UserType::Issuer,
+            id: near_sdk::env::predecessor_account_id(),
+            authorized_administrator: near_sdk::env::predecessor_account_id(),
+        };
         let quote = Quote {
-            client,
             id,
-            triggers_contract,
-            triggers,
+            issuer,
+            client,
+            claims_manager,
+            policy_type,
+            product_version,
             max_payout,
-            location,
+            premium,
+            terms,
             coverage_period,
+            policy_manager,
+            location,
         };
         let undecided_quote = UndecidedQuote {
             accept_deadline: self.get_valid_period(near_sdk::env::predecessor_account_id()),
             quote,
+            payment: None,
+            activating: false,
         };
 
         self.undecided_quotes
             .insert(&undecided_quote.quote.id, &undecided_quote);
+        QuoteManagerEvent::QuoteIssued([QuoteIssuedData {
+            quote_id: undecided_quote.quote.id,
+            client: undecided_quote.quote.client.id,
+            issuer: near_sdk::env::predecessor_account_id(),
+            accept_deadline: undecided_quote.accept_deadline,
+        }])
+        .emit();
     }
 
-    ///remove single invalid quote
+    ///remove single invalid quote. any premium paid towards it is refunded
     pub fn remove_invalid_quote(&mut self, quote_id: &String) {
-        assert!(*&near_sdk::env::predecessor_account_id() == self.owner);
+        assert!(near_sdk::env::predecessor_account_id() == self.owner);
         if let Some(quote) = self.undecided_quotes.get(quote_id) {
-            assert!(self.is_valid_quote(&quote) != true, "quote is still valid");
-            self.undecided_quotes.remove(quote_id);
+            assert!(!self.is_valid_quote(&quote), "quote is still valid");
+            self.remove_quote(quote);
+        }
+    }
+
+    ///#Description
+    ///
+    /// the payer of a quote that expired before its premium was fully paid takes back
+    /// its payment. the quote is removed
+    ///
+    /// #Parameters
+    ///
+    /// *`quote_id` the expired quote
+    pub fn refund_premium(&mut self, quote_id: String) {
+        let undecided_quote = self
+            .undecided_quotes
+            .get(&quote_id)
+            .expect("quote not found");
+        assert!(
+            !self.is_valid_quote(&undecided_quote),
+            "quote is still valid"
+        );
+        let payment = undecided_quote.payment.clone().expect("no premium paid");
+        assert_eq!(
+            env::predecessor_account_id(),
+            payment.payer,
+            "only the payer can be refunded"
+        );
+        self.remove_quote(undecided_quote);
+    }
+
+    ///#Description
+    ///
+    /// the client binds a quote by paying its premium in NEAR. payments add up until the
+    /// premium is fully paid, which activates the policy. any excess is refunded
+    ///
+    /// #Parameters
+    ///
+    /// *`quote_id` the quote to bind
+    #[payable]
+    pub fn pay_premium(&mut self, quote_id: String) {
+        let payer = env::predecessor_account_id();
+        let excess = self.receive_premium(
+            quote_id,
+            payer.clone(),
+            PremiumAsset::Near,
+            env::attached_deposit(),
+        );
+        if excess > 0 {
+            Promise::new(payer).transfer(excess);
         }
     }
 
-    ///when a client accepts a quote through an offchain process the issuer
-    /// creates a valid policy by calling this function
-    pub fn issue_policy(&mut self, quote_id: String) {
+    ///#Description
+    ///
+    /// NEP-141 receiver. the client binds a quote by paying its premium with
+    /// `ft_transfer_call` of a whitelisted token, with the quote id as `msg`. payments
+    /// add up until the premium is fully paid, which activates the policy. a payment that
+    /// cannot be taken panics, so the token contract refunds it
+    ///
+    /// #Returns
+    ///
+    /// the excess over the premium, refunded by the token contract
+    pub fn ft_on_transfer(
+        &mut self,
+        sender_id: AccountId,
+        amount: U128,
+        msg: String,
+    ) -> PromiseOrValue<U128> {
+        let token = env::predecessor_account_id();
+        assert!(
+            self.premium_tokens.contains(&token),
+            "premium token not accepted"
+        );
+        let excess =
+            self.receive_premium(msg, sender_id, PremiumAsset::FungibleToken(token), amount.0);
+        PromiseOrValue::Value(U128(excess))
+    }
+
+    ///an issuer activates a quote whose premium is paid, i.e. a quote without premium.
+    /// paid quotes are activated by their last premium payment. the quote is only removed
+    /// once the `PolicyManager` confirms the activation in `on_policy_activated`
+    pub fn issue_policy(&mut self, quote_id: String) -> Promise {
         assert!(
             self.quote_issuers
                 .contains(&near_sdk::env::predecessor_account_id()),
             "Not permitted."
         );
-        if let Some(undecided_quote) = self.undecided_quotes.get(&quote_id) {
-            if self.is_valid_quote(&undecided_quote) {
-                let accepted_quote = undecided_quote.quote;
-                policy_manager::activate_policy(
-                    accepted_quote.client,
-                    accepted_quote.id,
-                    accepted_quote.triggers,
-                    accepted_quote.max_payout,
-                    accepted_quote.location,
-                    accepted_quote.coverage_period,
-                    &"policymanager.accountid",
-                    0,
-                    5_000_000_000_000,
-                );
+        let undecided_quote = self
+            .undecided_quotes
+            .get(&quote_id)
+            .expect("quote not found");
+        assert!(self.is_valid_quote(&undecided_quote), "quote has expired");
+        assert!(!undecided_quote.activating, "policy activation in progress");
+        assert!(undecided_quote.is_paid(), "premium not paid");
+        self.activate(quote_id)
+    }
+
+    ///#Description
+    ///
+    /// callback of the policy activation. when the `PolicyManager` activated the policy the
+    /// consumed `UndecidedQuote` is deleted and its premium is credited to the issuer,
+    /// otherwise the quote is kept and the premium refunded
+    ///
+    /// #Returns
+    ///
+    /// whether the policy was activated
+    #[private]
+    pub fn on_policy_activated(&mut self, quote_id: String) -> bool {
+        assert_eq!(env::promise_results_count(), 1, "ERR_TOO_MANY_RESULTS");
+        match env::promise_result(0) {
+            PromiseResult::NotReady => unreachable!(),
+            PromiseResult::Successful(_) => {
+                if let Some(undecided_quote) = self.undecided_quotes.remove(&quote_id) {
+                    let quote = undecided_quote.quote.clone();
+                    if undecided_quote.paid() > 0 {
+                        let issuer = quote.issuer.authorized_administrator.clone();
+                        self.credit_premium(&issuer, &quote.premium.asset, undecided_quote.paid());
+                        QuoteManagerEvent::PremiumCollected([PremiumData {
+                            quote_id: quote_id.clone(),
+                            account_id: issuer,
+                            asset: quote.premium.asset.clone(),
+                            amount: U128(undecided_quote.paid()),
+                        }])
+                        .emit();
+                    }
+                    QuoteManagerEvent::PolicyIssued([QuoteData {
+                        quote_id,
+                        client: quote.client.id,
+                    }])
+                    .emit();
+                }
+                true
+            }
+            PromiseResult::Failed => {
+                if let Some(mut undecided_quote) = self.undecided_quotes.get(&quote_id) {
+                    undecided_quote.activating = false;
+                    if let Some(payment) = undecided_quote.payment.take() {
+                        self.refund_payment(&undecided_quote.quote, payment);
+                    }
+                    self.undecided_quotes.insert(&quote_id, &undecided_quote);
+                }
+                false
             }
         }
     }
 
+    ///#Description
+    ///
+    /// an issuer withdraws the premiums of its activated policies
+    ///
+    /// #Parameters
+    ///
+    /// *`asset` what the premiums were paid in
+    /// *`amount` how much to withdraw
+    pub fn withdraw_premium(&mut self, asset: PremiumAsset, amount: U128) -> Promise {
+        let issuer = env::predecessor_account_id();
+        let key = (issuer.clone(), asset.clone());
+        let balance = self.premium_balances.get(&key).unwrap_or(U128(0)).0;
+        assert!(
+            amount.0 > 0 && amount.0 <= balance,
+            "insufficient premium balance"
+        );
+        self.premium_balances
+            .insert(&key, &U128(balance - amount.0));
+        QuoteManagerEvent::PremiumWithdrawn([PremiumBalanceData {
+            account_id: issuer.clone(),
+            asset: asset.clone(),
+            amount,
+        }])
+        .emit();
+        self.transfer_asset(&asset, issuer.clone(), amount.0)
+            .then(ext_self::on_premium_withdrawn(
+                issuer,
+                asset,
+                amount,
+                &env::current_account_id(),
+                0,
+                ON_PREMIUM_WITHDRAWN_GAS,
+            ))
+    }
+
+    ///callback of `withdraw_premium`. credits the premium back if the transfer failed
+    #[private]
+    pub fn on_premium_withdrawn(
+        &mut self,
+        issuer: AccountId,
+        asset: PremiumAsset,
+        amount: U128,
+    ) -> bool {
+        assert_eq!(env::promise_results_count(), 1, "ERR_TOO_MANY_RESULTS");
+        match env::promise_result(0) {
+            PromiseResult::NotReady => unreachable!(),
+            PromiseResult::Successful(_) => true,
+            PromiseResult::Failed => {
+                self.credit_premium(&issuer, &asset, amount.0);
+                false
+            }
+        }
+    }
+
+    ///#Description
+    ///
+    /// a payer claims the refunds held for it because their transfer failed, e.g. for want
+    /// of a storage registration on the token
+    ///
+    /// #Parameters
+    ///
+    /// *`asset` what the refunded premiums were paid in
+    pub fn claim_refund(&mut self, asset: PremiumAsset) -> Promise {
+        let payer = env::predecessor_account_id();
+        let amount = self
+            .refunds
+            .remove(&(payer.clone(), asset.clone()))
+            .expect("no refund held");
+        QuoteManagerEvent::RefundClaimed([PremiumBalanceData {
+            account_id: payer.clone(),
+            asset: asset.clone(),
+            amount,
+        }])
+        .emit();
+        self.send_refund(&asset, payer, amount)
+    }
+
+    ///callback of a refund. holds the refund for its payer to claim if the transfer failed
+    #[private]
+    pub fn on_premium_refunded(
+        &mut self,
+        payer: AccountId,
+        asset: PremiumAsset,
+        amount: U128,
+    ) -> bool {
+        assert_eq!(env::promise_results_count(), 1, "ERR_TOO_MANY_RESULTS");
+        match env::promise_result(0) {
+            PromiseResult::NotReady => unreachable!(),
+            PromiseResult::Successful(_) => true,
+            PromiseResult::Failed => {
+                let key = (payer.clone(), asset.clone());
+                let held = self.refunds.get(&key).unwrap_or(U128(0)).0;
+                self.refunds.insert(&key, &U128(held + amount.0));
+                QuoteManagerEvent::RefundHeld([PremiumBalanceData {
+                    account_id: payer,
+                    asset,
+                    amount,
+                }])
+                .emit();
+                false
+            }
+        }
+    }
+
+    ///get the refunds held for a payer in one asset
+    pub fn get_refund(&self, payer: AccountId, asset: PremiumAsset) -> U128 {
+        self.refunds.get(&(payer, asset)).unwrap_or(U128(0))
+    }
+
+    ///get the premiums held for an issuer in one asset
+    pub fn get_premium_balance(&self, issuer: AccountId, asset: PremiumAsset) -> U128 {
+        self.premium_balances
+            .get(&(issuer, asset))
+            .unwrap_or(U128(0))
+    }
+
+    ///get the NEP-141 tokens premiums can be paid in
+    pub fn get_premium_tokens(&self) -> Vec<AccountId> {
+        self.premium_tokens.clone()
+    }
+
+    ///accept premiums in a NEP-141 token
+    pub fn add_premium_token(&mut self, token: AccountId) {
+        assert!(
+            near_sdk::env::predecessor_account_id() == self.owner,
+            "only owner"
+        );
+        assert!(
+            !self.premium_tokens.contains(&token),
+            "token already accepted"
+        );
+        self.premium_tokens.push(token.clone());
+        QuoteManagerEvent::PremiumTokenAdded([AccountData { account_id: token }]).emit();
+    }
+
+    ///stop accepting premiums in a NEP-141 token. quotes already issued in it can still be paid
+    pub fn remove_premium_token(&mut self, token: AccountId) {
+        assert!(
+            near_sdk::env::predecessor_account_id() == self.owner,
+            "only owner"
+        );
+        let index = self
+            .premium_tokens
+            .iter()
+            .position(|x| *x == token)
+            .expect("token not accepted");
+        self.premium_tokens.remove(index);
+        QuoteManagerEvent::PremiumTokenRemoved([AccountData { account_id: token }]).emit();
+    }
+
     ///get  quote by its id
     pub fn get_quote(&self, quote_id: String) -> Option<UndecidedQuote> {
         self.undecided_quotes.get(&quote_id)
@@ -163,7 +552,12 @@ impl QuoteManager {
             "only valid issuer."
         );
         self.standard_days_valid
-            .insert(near_sdk::env::predecessor_account_id(), days_valid);
+            .insert(&near_sdk::env::predecessor_account_id(), &days_valid);
+        QuoteManagerEvent::DaysValidChanged([DaysValidData {
+            issuer: near_sdk::env::predecessor_account_id(),
+            days_valid,
+        }])
+        .emit();
     }
 
     ///change the owner of the Quote Manager contract
@@ -173,6 +567,10 @@ impl QuoteManager {
             "only owner"
         );
         self.owner = new_owner;
+        QuoteManagerEvent::OwnerChanged([AccountData {
+            account_id: self.owner.clone(),
+        }])
+        .emit();
     }
 
     ///add an valid quote issuer (insurer) to the white list
@@ -182,7 +580,13 @@ impl QuoteManager {
             "only owner"
         );
         self.quote_issuers.push(new_issuer.clone());
-        self.standard_days_valid.insert(new_issuer, deadline_length);
+        self.standard_days_valid
+            .insert(&new_issuer, &deadline_length);
+        QuoteManagerEvent::IssuerAdded([DaysValidData {
+            issuer: new_issuer,
+            days_valid: deadline_length,
+        }])
+        .emit();
     }
 
     ///remove a quote issuer from the white list
@@ -198,6 +602,10 @@ impl QuoteManager {
             .position(|x| *x == old_issuer)
             .unwrap();
         self.quote_issuers.remove(index);
+        QuoteManagerEvent::IssuerRemoved([AccountData {
+            account_id: old_issuer,
+        }])
+        .emit();
     }
 
     ///get the standard number of days that a quote issuers quotes are valid
@@ -215,20 +623,148 @@ impl QuoteManager {
         quote.accept_deadline > near_sdk::env::block_timestamp()
     }
 
+    ///take a premium payment towards a quote and activate the policy once the premium is
+    /// paid. panics, which returns the payment, if the quote cannot be paid
+    ///
+    /// #Returns
+    ///
+    /// the excess over the premium that must be refunded
+    fn receive_premium(
+        &mut self,
+        quote_id: String,
+        payer: AccountId,
+        asset: PremiumAsset,
+        amount: u128,
+    ) -> u128 {
+        let mut undecided_quote = self
+            .undecided_quotes
+            .get(&quote_id)
+            .expect("quote not found");
+        assert!(self.is_valid_quote(&undecided_quote), "quote has expired");
+        assert!(!undecided_quote.activating, "policy activation in progress");
+        assert!(
+            undecided_quote.quote.premium.asset == asset,
+            "wrong premium asset"
+        );
+        assert_eq!(
+            payer, undecided_quote.quote.client.authorized_administrator,
+            "only the client can pay the premium"
+        );
+        let paid = undecided_quote.paid();
+        let (accepted, excess) = undecided_quote.quote.premium.accept(paid, amount);
+        assert!(accepted > 0, "premium already paid");
+        undecided_quote.payment = Some(PremiumPayment {
+            payer: payer.clone(),
+            paid: U128(paid + accepted),
+        });
+        self.undecided_quotes.insert(&quote_id, &undecided_quote);
+        QuoteManagerEvent::PremiumPaid([PremiumData {
+            quote_id: quote_id.clone(),
+            account_id: payer,
+            asset,
+            amount: U128(accepted),
+        }])
+        .emit();
+        if undecided_quote.is_paid() {
+            self.activate(quote_id);
+        }
+        excess
+    }
+
+    ///ask the `PolicyManager` to activate the policy of a quote. the quote is locked until
+    /// `on_policy_activated`
+    fn activate(&mut self, quote_id: String) -> Promise {
+        let mut undecided_quote = self
+            .undecided_quotes
+            .get(&quote_id)
+            .expect("quote not found");
+        undecided_quote.activating = true;
+        self.undecided_quotes.insert(&quote_id, &undecided_quote);
+        let accepted_quote = undecided_quote.quote;
+        policy_manager::activate_policy(
+            accepted_quote.clone(),
+            &accepted_quote.policy_manager,
+            0,
+            ACTIVATE_POLICY_GAS,
+        )
+        .then(ext_self::on_policy_activated(
+            quote_id,
+            &env::current_account_id(),
+            0,
+            ON_POLICY_ACTIVATED_GAS,
+        ))
+    }
+
+    ///delete a quote, refunding any premium paid towards it
+    fn remove_quote(&mut self, undecided_quote: UndecidedQuote) {
+        assert!(!undecided_quote.activating, "policy activation in progress");
+        self.undecided_quotes.remove(&undecided_quote.quote.id);
+        if let Some(payment) = undecided_quote.payment.clone() {
+            self.refund_payment(&undecided_quote.quote, payment);
+        }
+        QuoteManagerEvent::QuoteRemoved([QuoteData {
+            quote_id: undecided_quote.quote.id,
+            client: undecided_quote.quote.client.id,
+        }])
+        .emit();
+    }
+
+    fn refund_payment(&self, quote: &Quote, payment: PremiumPayment) {
+        self.send_refund(&quote.premium.asset, payment.payer.clone(), payment.paid);
+        QuoteManagerEvent::PremiumRefunded([PremiumData {
+            quote_id: quote.id.clone(),
+            account_id: payment.payer,
+            asset: quote.premium.asset.clone(),
+            amount: payment.paid,
+        }])
+        .emit();
+    }
+
+    ///send a refund to its payer. it is held for the payer to claim if the transfer fails
+    fn send_refund(&self, asset: &PremiumAsset, payer: AccountId, amount: U128) -> Promise {
+        self.transfer_asset(asset, payer.clone(), amount.0)
+            .then(ext_self::on_premium_refunded(
+                payer,
+                asset.clone(),
+                amount,
+                &env::current_account_id(),
+                0,
+                ON_PREMIUM_REFUNDED_GAS,
+            ))
+    }
 
+    fn credit_premium(&mut self, issuer: &AccountId, asset: &PremiumAsset, amount: u128) {
+        let key = (issuer.clone(), asset.clone());
+        let balance = self.premium_balances.get(&key).unwrap_or(U128(0)).0;
+        self.premium_balances.insert(&key, &U128(balance + amount));
+    }
+
+    ///send `amount` of `asset` to `receiver`. NEP-141 transfers carry the required yoctoNEAR
+    fn transfer_asset(&self, asset: &PremiumAsset, receiver: AccountId, amount: u128) -> Promise {
+        match asset {
+            PremiumAsset::Near => Promise::new(receiver).transfer(amount),
+            PremiumAsset::FungibleToken(token) => {
+                fungible_token::ft_transfer(receiver, U128(amount), None, token, 1, FT_TRANSFER_GAS)
+            }
+        }
+    }
 }
 
 #[ext_contract(policy_manager)]
 pub trait PolicyManager {
-    //borsh serialization not implemented for Quote
-    fn activate_policy(
-        client: String,
-        id: String,
-        triggers: HashMap<String, i32>,
-        max_payout: u32,
-        location: String,
-        coverage_period: [u64; 2],
-    );
+    fn activate_policy(quote: Quote);
+}
+
+#[ext_contract(ext_self)]
+pub trait QuoteManagerCallbacks {
+    fn on_policy_activated(quote_id: String) -> bool;
+    fn on_premium_withdrawn(issuer: AccountId, asset: PremiumAsset, amount: U128) -> bool;
+    fn on_premium_refunded(payer: AccountId, asset: PremiumAsset, amount: U128) -> bool;
+}
+
+#[ext_contract(fungible_token)]
+pub trait FungibleToken {
+    fn ft_transfer(receiver_id: AccountId, amount: U128, memo: Option<String>);
 }
 
 #[ext_contract(event_manager)]
@@ -246,62 +782,42 @@ mod tests {
     #[should_panic]
     ///Unauthorized quote issuance
     fn unauthorized_issue_quote() {
-        let mut triggers = HashMap::new();
-        triggers.insert("hurricane_category".to_string(), 34);
-        triggers.insert("hurricane_distance".to_string(), 100);
         let context = get_context("hillridge.near".to_string(), 1000000, 0);
         testing_env!(context);
         let mut quote_manager = QuoteManager::new();
-        quote_manager.issue_quote(
-            "some.client.id".to_string(),
-            "some_id".to_string(),
-            "trigger.contract".to_string(),
-            triggers,
-            1000000000,
-            "someGeohash".to_string(),
-            [123123123, 1231023123],
-        );
+        issue_test_quote(&mut quote_manager, "some_id");
     }
 
     #[test]
     fn authorized_issue_quote() {
         let context = get_context("hillridge.near".to_string(), 1000000, 0);
         testing_env!(context);
-        let mut triggers = HashMap::new();
-        triggers.insert("hurricane_category".to_string(), 34);
-        triggers.insert("hurricane_distance".to_string(), 100);
-
         let mut quote_manager = QuoteManager::new();
         quote_manager.add_issuer("hillridge.near".to_string(), 7);
-        quote_manager.issue_quote(
-            "some.client.id".to_string(),
-            "some_id".to_string(),
-            "trigger.contract".to_string(),
-            triggers,
-            1000000000,
-            "someGeohash".to_string(),
-            [123123123, 1231023123],
-        );
+        issue_test_quote(&mut quote_manager, "some_id");
+        let quote = quote_manager.get_quote("some_id".to_string()).unwrap();
+        assert_eq!(quote.quote.issuer.id, "hillridge.near".to_string());
     }
 
     #[test]
     fn issue_policy() {
         let context = get_context("hillridge.near".to_string(), 1000000, 0);
         testing_env!(context);
-        let mut triggers = HashMap::new();
-        triggers.insert("hurricane_category".to_string(), 34);
-        triggers.insert("hurricane_distance".to_string(), 100);
         let mut quote_manager = QuoteManager::new();
         quote_manager.add_issuer("hillridge.near".to_string(), 7);
-        quote_manager.issue_quote(
-            "some.client.id".to_string(),
-            "some_id".to_string(),
-            "trigger.contract".to_string(),
-            triggers,
-            1000000000,
-            "someGeohash".to_string(),
-            [123123123, 1231023123],
-        );
+        issue_test_quote(&mut quote_manager, "some_id");
+        quote_manager.issue_policy("some_id".to_string());
+        // the quote is only consumed by the `on_policy_activated` callback
+        assert!(quote_manager.get_quote("some_id".to_string()).is_some());
+    }
+
+    #[test]
+    #[should_panic(expected = "quote not found")]
+    fn issue_policy_unknown_quote() {
+        let context = get_context("hillridge.near".to_string(), 1000000, 0);
+        testing_env!(context);
+        let mut quote_manager = QuoteManager::new();
+        quote_manager.add_issuer("hillridge.near".to_string(), 7);
         quote_manager.issue_policy("some_quote_id".to_string());
     }
 
@@ -309,22 +825,173 @@ mod tests {
     fn remove_invalid_quote() {
         let context = get_context("hillridge.near".to_string(), 1000000, 0);
         testing_env!(context);
-        let mut triggers = HashMap::new();
-        triggers.insert("hurricane_category".to_string(), 34);
-        triggers.insert("hurricane_distance".to_string(), 100);
+        let mut quote_manager = QuoteManager::new();
+        quote_manager.add_issuer("hillridge.near".to_string(), 7);
+        issue_test_quote(&mut quote_manager, "some_id");
+    }
+
+    #[test]
+    #[should_panic(expected = "TERMS_CURRENCY_MISMATCH")]
+    fn terms_must_fit_the_max_payout() {
+        testing_env!(get_context("hillridge.near".to_string(), 1000000, 0));
         let mut quote_manager = QuoteManager::new();
         quote_manager.add_issuer("hillridge.near".to_string(), 7);
         quote_manager.issue_quote(
-            "some.client.id".to_string(),
             "some_id".to_string(),
-            "trigger.contract".to_string(),
-            triggers,
-            1000000000,
-            "someGeohash".to_string(),
+            User {
+                user_type: UserType::Client,
+                id: "some.client.id".to_string(),
+                authorized_administrator: "client.near".to_string(),
+            },
+            "claims.contract".to_string(),
+            1,
+            1,
+            Amount::new(1000000000, "USD".to_string()),
+            near_premium(0),
+            CoverageTerms {
+                deductible: Some(Amount::new(1000, "EUR".to_string())),
+                ..Default::default()
+            },
+            Location::new(25.7617, -80.1918).unwrap(),
             [123123123, 1231023123],
+            "policymanager.near".to_string(),
         );
     }
 
+    fn issue_test_quote(quote_manager: &mut QuoteManager, id: &str) {
+        issue_test_quote_with_premium(
+            quote_manager,
+            id,
+            Premium {
+                asset: PremiumAsset::Near,
+                amount: U128(0),
+            },
+        );
+    }
+
+    fn issue_test_quote_with_premium(quote_manager: &mut QuoteManager, id: &str, premium: Premium) {
+        quote_manager.issue_quote(
+            id.to_string(),
+            User {
+                user_type: UserType::Client,
+                id: "some.client.id".to_string(),
+                authorized_administrator: "client.near".to_string(),
+            },
+            "claims.contract".to_string(),
+            1,
+            1,
+            Amount::new(1000000000, "USD".to_string()),
+            premium,
+            CoverageTerms::default(),
+            Location::new(25.7617, -80.1918).unwrap(),
+            [123123123, 1231023123],
+            "policymanager.near".to_string(),
+        );
+    }
+
+    fn near_premium(amount: u128) -> Premium {
+        Premium {
+            asset: PremiumAsset::Near,
+            amount: U128(amount),
+        }
+    }
+
+    fn paying_context(blocktime: u64, attached_deposit: u128) -> VMContext {
+        let mut context = get_context("client.near".to_string(), 1000000, blocktime);
+        context.attached_deposit = attached_deposit;
+        // the contract holds the premiums it refunds
+        context.account_balance = 10u128.pow(24);
+        context
+    }
+
+    #[test]
+    fn premium_paid_in_installments() {
+        testing_env!(get_context("hillridge.near".to_string(), 1000000, 0));
+        let mut quote_manager = QuoteManager::new();
+        quote_manager.add_issuer("hillridge.near".to_string(), 7);
+        issue_test_quote_with_premium(&mut quote_manager, "some_id", near_premium(1_000));
+        testing_env!(paying_context(0, 400));
+        quote_manager.pay_premium("some_id".to_string());
+        let quote = quote_manager.get_quote("some_id".to_string()).unwrap();
+        assert_eq!(quote.paid(), 400);
+        assert!(!quote.activating);
+        // the excess of 100 is refunded and the policy activated
+        testing_env!(paying_context(0, 700));
+        quote_manager.pay_premium("some_id".to_string());
+        let quote = quote_manager.get_quote("some_id".to_string()).unwrap();
+        assert_eq!(quote.paid(), 1_000);
+        assert!(quote.activating);
+    }
+
+    #[test]
+    #[should_panic(expected = "premium not paid")]
+    fn issue_policy_requires_the_premium() {
+        testing_env!(get_context("hillridge.near".to_string(), 1000000, 0));
+        let mut quote_manager = QuoteManager::new();
+        quote_manager.add_issuer("hillridge.near".to_string(), 7);
+        issue_test_quote_with_premium(&mut quote_manager, "some_id", near_premium(1_000));
+        quote_manager.issue_policy("some_id".to_string());
+    }
+
+    #[test]
+    #[should_panic(expected = "premium token not accepted")]
+    fn premium_token_must_be_accepted() {
+        testing_env!(get_context("usdc.near".to_string(), 1000000, 0));
+        let mut quote_manager = QuoteManager::new();
+        quote_manager.ft_on_transfer(
+            "client.near".to_string(),
+            U128(1_000),
+            "some_id".to_string(),
+        );
+    }
+
+    #[test]
+    fn expired_quote_refunds_partial_premium() {
+        testing_env!(get_context("hillridge.near".to_string(), 1000000, 0));
+        let mut quote_manager = QuoteManager::new();
+        quote_manager.add_issuer("hillridge.near".to_string(), 7);
+        issue_test_quote_with_premium(&mut quote_manager, "some_id", near_premium(1_000));
+        testing_env!(paying_context(0, 400));
+        quote_manager.pay_premium("some_id".to_string());
+        testing_env!(paying_context(8 * 86400000000000, 0));
+        quote_manager.refund_premium("some_id".to_string());
+        assert!(quote_manager.get_quote("some_id".to_string()).is_none());
+    }
+
+    #[test]
+    fn failed_refund_is_held_for_the_payer() {
+        testing_env!(get_context("hillridge.near".to_string(), 1000000, 0));
+        let mut quote_manager = QuoteManager::new();
+        // the refund transfer of 400 failed
+        testing_env!(
+            get_context("alice.testnet".to_string(), 1000000, 0),
+            Default::default(),
+            Default::default(),
+            Default::default(),
+            vec![PromiseResult::Failed]
+        );
+        assert!(!quote_manager.on_premium_refunded(
+            "client.near".to_string(),
+            PremiumAsset::Near,
+            U128(400)
+        ));
+        let refund = quote_manager.get_refund("client.near".to_string(), PremiumAsset::Near);
+        assert_eq!(refund, U128(400));
+        testing_env!(paying_context(0, 0));
+        quote_manager.claim_refund(PremiumAsset::Near);
+        let refund = quote_manager.get_refund("client.near".to_string(), PremiumAsset::Near);
+        assert_eq!(refund, U128(0));
+    }
+
+    #[test]
+    #[should_panic(expected = "no refund held")]
+    fn only_held_refunds_can_be_claimed() {
+        testing_env!(get_context("hillridge.near".to_string(), 1000000, 0));
+        let mut quote_manager = QuoteManager::new();
+        testing_env!(paying_context(0, 0));
+        quote_manager.claim_refund(PremiumAsset::Near);
+    }
+
     fn get_context(
         predecessor_account_id: String,
         storage_usage: u64,
@@ -0,0 +1,87 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::json_types::U128;
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::AccountId;
+
+///#Description
+///
+/// what a premium is paid in
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(crate = "near_sdk::serde")]
+#[serde(rename_all = "snake_case")]
+pub enum PremiumAsset {
+    ///yoctoNEAR attached to `pay_premium`
+    Near,
+    ///a whitelisted NEP-141 token sent with `ft_transfer_call`
+    FungibleToken(AccountId),
+}
+
+///#Description
+///
+/// the price of a `Quote`. mirrors `Premium` in `PolicyManager`
+///
+/// #Fields
+///
+/// *`asset` what the premium is paid in
+/// *`amount` the premium in the smallest unit of `asset`
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Premium {
+    pub asset: PremiumAsset,
+    pub amount: U128,
+}
+
+///#Description
+///
+/// what has been paid towards a quote's premium. it is held by the `QuoteManager` until the
+/// policy is activated, then credited to the issuer. it is refunded to `payer` if the quote
+/// expires or the activation fails
+///
+/// #Fields
+///
+/// *`payer` the account the premium came from
+/// *`paid` the amount paid so far
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct PremiumPayment {
+    pub payer: AccountId,
+    pub paid: U128,
+}
+
+impl Premium {
+    ///#Description
+    ///
+    /// split a payment of `amount` into the part that goes towards the premium and the
+    /// excess to refund
+    ///
+    /// #Parameters
+    ///
+    /// *`paid` what has already been paid
+    /// *`amount` the new payment
+    pub fn accept(&self, paid: u128, amount: u128) -> (u128, u128) {
+        let accepted = amount.min(self.amount.0.saturating_sub(paid));
+        (accepted, amount - accepted)
+    }
+
+    pub fn is_paid(&self, paid: u128) -> bool {
+        paid >= self.amount.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn payments_up_to_the_premium_are_accepted() {
+        let premium = Premium {
+            asset: PremiumAsset::FungibleToken("usdc.near".to_string()),
+            amount: U128(1_000),
+        };
+        assert_eq!(premium.accept(0, 400), (400, 0));
+        assert!(!premium.is_paid(400));
+        assert_eq!(premium.accept(400, 700), (600, 100));
+        assert!(premium.is_paid(1_000));
+        assert_eq!(premium.accept(1_000, 50), (0, 50));
+    }
+}